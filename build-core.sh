@@ -8,7 +8,7 @@ DEPLOYMENTS_DIR="./deployments" # Output directory for build files
 # Copy command helper (cross-platform)
 CP_CMD=$(command -v cp &> /dev/null && echo "cp" || echo "copy")
 
-core_contracts=("mock_nominator" "nomination_agent" "registry" "share_token" "vault")
+core_contracts=("governor" "mock_legacy_registry" "mock_legacy_vault" "mock_nominator" "mock_vault_v2" "multisig" "nomination_agent" "registry" "share_token" "vault" "weight_voting")
 
 # Build core contracts
 for i in "${core_contracts[@]}"
@@ -13,6 +13,7 @@ pub const SECOND: u64 = 1_000;
 pub const DAY: u64 = 86400 * SECOND;
 pub const YEAR: u64 = DAY * 365_25 / 100; // https://docs.alephzero.org/aleph-zero/use/stake/staking-rewards
 pub const BIPS: u128 = 10000;
+pub const MAX_VALIDATORS: usize = 16;
 
 #[derive(Debug, scale::Decode)]
 pub struct Agent {
@@ -21,6 +22,40 @@ pub struct Agent {
     pub disabled: bool,
 }
 
+#[derive(Debug, scale::Decode)]
+pub struct AgentMetadata {
+    pub label: String,
+    pub validators: Vec<AccountId32>,
+    pub created_at: u64,
+    pub creation_bond: u128,
+    pub admin: AccountId32,
+}
+
+#[derive(Debug, PartialEq, scale::Decode)]
+pub enum AgentStatus {
+    Active,
+    Disabled,
+    Draining,
+    Destroying,
+    Destroyed,
+}
+
+#[derive(Debug, scale::Decode)]
+pub struct AgentLifecycle {
+    pub status: AgentStatus,
+    pub updated_at: u64,
+    pub retired_at: u64,
+}
+
+#[derive(Debug, scale::Decode)]
+pub struct AgentOverview {
+    pub address: AccountId32,
+    pub weight: u128,
+    pub disabled: bool,
+    pub staked: u128,
+    pub unbonding: u128,
+}
+
 #[derive(Debug, scale::Decode, scale::Encode, serde::Deserialize, serde::Serialize)]
 pub struct WeightUpdate {
     pub agent: AccountId32,
@@ -28,6 +63,158 @@ pub struct WeightUpdate {
     pub increase: bool,
 }
 
+/// Mirrors `nomination_agent::errors::RuntimeError`
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, scale::Decode)]
+pub enum RuntimeError {
+    CallRuntimeFailed,
+    Unauthorized,
+    Active,
+    Overflow,
+    Underflow,
+    NoChange,
+    InvalidInput,
+}
+
+/// Mirrors `registry::errors::RegistryError`
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, scale::Decode)]
+pub enum RegistryError {
+    InvalidInput,
+    TooManyAgents,
+    TooManyValidators,
+    AgentNotFound,
+    AgentDisabled,
+    ActiveAgent,
+    InvalidPermissions,
+    InvalidRole,
+    TooManyRoleMembers,
+    NoChange,
+    CodeTimelocked,
+    NoPendingCode,
+    DuplicateAgent,
+    DuplicateValidator,
+    AgentNotDraining,
+    BondNotReclaimed,
+    TooManyPendingUpdates,
+    WeightUpdateNotFound,
+    TotalWeightMismatch,
+    ConcentrationLimit,
+    Overflow,
+    Underflow,
+    InkEnvError(String),
+    AgentError(RuntimeError),
+}
+
+/// Mirrors `vault::errors::VaultError` up to the token errors, which are not decoded by the tests
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, scale::Decode)]
+pub enum VaultError {
+    InvalidPercent,
+    InvalidFeeRecipients,
+    FeeStepTooLarge,
+    FeeTimelocked,
+    NoFeeProposal,
+    CodeTimelocked,
+    NoPendingCode,
+    InvalidDelay,
+    InvalidPeriod,
+    InvalidIndex,
+    InvalidUserUnlockRequest,
+    CooldownPeriod,
+    InvalidPermissions,
+    InvalidRole,
+    NoChange,
+    ZeroDepositing,
+    ZeroUnbonding,
+    ZeroTotalWeight,
+    ZeroCompounding,
+    MinimumStake,
+    ConcentrationLimit,
+    Overflow,
+    Underflow,
+    DivisionByZero,
+    InkEnvError(String),
+    InternalError(RuntimeError),
+}
+
+#[derive(Debug, PartialEq, scale::Decode)]
+pub enum HistoryAction {
+    WeightUpdated {
+        agent: AccountId32,
+        old_weight: u128,
+        new_weight: u128,
+    },
+    WeightUpdatesScheduled {
+        id: u32,
+        activate_at: u64,
+    },
+    WeightUpdatesCancelled {
+        id: u32,
+    },
+    RoleAccountChanged {
+        role_type: RoleType,
+        new_account: AccountId32,
+    },
+    RoleAdminChanged {
+        role_type: RoleType,
+        new_account: AccountId32,
+    },
+    RoleRenounced {
+        role_type: RoleType,
+        account: AccountId32,
+    },
+    RoleGranted {
+        role_type: RoleType,
+        account: AccountId32,
+    },
+    RoleRevoked {
+        role_type: RoleType,
+        account: AccountId32,
+    },
+}
+
+#[derive(Debug, scale::Decode)]
+pub struct HistoryEntry {
+    pub timestamp: u64,
+    pub actor: AccountId32,
+    pub action: HistoryAction,
+}
+
+#[derive(Debug, scale::Decode)]
+pub struct ScheduledWeightUpdates {
+    pub id: u32,
+    pub updates: Vec<WeightUpdate>,
+    pub activate_at: u64,
+    pub scheduler: AccountId32,
+}
+
+#[derive(Debug, PartialEq, scale::Decode)]
+pub struct UnlockRequest {
+    pub creation_time: u64,
+    pub azero: u128,
+}
+
+#[derive(Debug, scale::Decode, scale::Encode, serde::Deserialize, serde::Serialize)]
+pub struct Allocation {
+    pub agent: AccountId32,
+    pub bips: u16,
+}
+
+#[derive(Debug, PartialEq, scale::Decode)]
+pub struct Operation {
+    pub target: AccountId32,
+    pub call_data: Vec<u8>,
+    pub transferred_value: u128,
+    pub ready_at: u64,
+}
+
+#[derive(Debug, scale::Decode, scale::Encode, serde::Deserialize, serde::Serialize)]
+pub struct FeeRecipient {
+    pub account: AccountId32,
+    pub bips: u16,
+}
+
 pub fn update_days(
     mut sess: Session<MinimalRuntime>,
     days: u64,
@@ -46,6 +233,11 @@ pub fn update_in_milliseconds(
     sess
 }
 
+/// Formats accounts as a `Vec<AccountId>` message argument
+pub fn accounts_arg(accounts: &[AccountId32]) -> String {
+    let accounts: Vec<String> = accounts.iter().map(|a| a.to_string()).collect();
+    format!("[{}]", accounts.join(", "))
+}
 pub fn call_add_agent(
     sess: Session<MinimalRuntime>,
     registry: &AccountId32,
@@ -53,6 +245,25 @@ pub fn call_add_agent(
     admin: &AccountId32,
     validator: &AccountId32,
     pool_create_amount: u128,
+) -> Result<(AccountId32, Session<MinimalRuntime>), Box<dyn Error>> {
+    call_add_agent_with_validators(
+        sess,
+        registry,
+        sender,
+        admin,
+        &[validator.clone()],
+        false,
+        pool_create_amount,
+    )
+}
+pub fn call_add_agent_with_validators(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    admin: &AccountId32,
+    validators: &[AccountId32],
+    allow_duplicate_validators: bool,
+    pool_create_amount: u128,
 ) -> Result<(AccountId32, Session<MinimalRuntime>), Box<dyn Error>> {
     let sess: Session<MinimalRuntime> = call_function(
         sess,
@@ -61,7 +272,8 @@ pub fn call_add_agent(
         String::from("IRegistry::add_agent"),
         Some([
             admin.to_string(),
-            validator.to_string(),
+            accounts_arg(validators),
+            allow_duplicate_validators.to_string(),
         ].to_vec()),
         Some(pool_create_amount),
         transcoder_registry(),
@@ -88,6 +300,84 @@ pub fn call_update_agents(
     )?;
     Ok(sess)
 }
+pub fn call_schedule_weight_updates(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    updates: Vec<WeightUpdate>,
+    activate_at: u64,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::schedule_weight_updates"),
+        Some(vec![serde_json::to_string(&updates).unwrap(), activate_at.to_string()]),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn call_cancel_weight_updates(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    id: u32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::cancel_weight_updates"),
+        Some(vec![id.to_string()]),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn call_activate_weight_updates(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::activate_weight_updates"),
+        None,
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn call_set_weights(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    weights: Vec<(AccountId32, u128)>,
+    expected_total_weight: Option<u128>,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let weights_string = weights
+        .iter()
+        .map(|(agent, weight)| format!("({}, {})", agent, weight))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let expected_string = match expected_total_weight {
+        Some(total_weight) => format!("Some({})", total_weight),
+        None => String::from("None"),
+    };
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::set_weights"),
+        Some(vec![format!("[{}]", weights_string), expected_string]),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
 pub fn call_disable_agent(
     sess: Session<MinimalRuntime>,
     registry: &AccountId32,
@@ -105,6 +395,95 @@ pub fn call_disable_agent(
     )?;
     Ok(sess)
 }
+pub fn call_enable_agent(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    agent: &AccountId32,
+    weight: u128,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::enable_agent"),
+        Some([agent.to_string(), weight.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn call_update_agent_validators(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    agent: &AccountId32,
+    validators: Vec<AccountId32>,
+    allow_duplicate_validators: bool,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::update_agent_validators"),
+        Some([agent.to_string(), accounts_arg(&validators), allow_duplicate_validators.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn call_confirm_bond_reclaimed(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    agent: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::confirm_bond_reclaimed"),
+        Some([agent.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn call_admin_withdraw_bond(
+    sess: Session<MinimalRuntime>,
+    nominator: &AccountId32,
+    sender: &AccountId32,
+    to: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &nominator,
+        &sender,
+        String::from("INominationAgent::admin_withdraw_bond"),
+        Some([to.to_string()].to_vec()),
+        None,
+        transcoder_nominator(),
+    )?;
+    Ok(sess)
+}
+pub fn call_set_agent_code_for(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    agents: Vec<AccountId32>,
+    code_hash: String,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::set_agent_code_for"),
+        Some([accounts_arg(&agents), code_hash].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
 pub fn call_remove_agent(
     sess: Session<MinimalRuntime>,
     registry: &AccountId32,
@@ -122,6 +501,23 @@ pub fn call_remove_agent(
     )?;
     Ok(sess)
 }
+pub fn call_drain_agent(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    agent: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::drain_agent"),
+        Some([agent.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
 pub fn call_stake(
     sess: Session<MinimalRuntime>,
     vault: &AccountId32,
@@ -182,25 +578,32 @@ pub fn call_request_unlock(
 }
 
 #[allow(dead_code)]
+pub enum VaultRoleType {
+    AdjustFee,
+    FeeTo,
+    SetCode,
+    Pauser,
+    Keeper,
+    LimitsAdmin,
+    Guardian,
+}
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, scale::Decode)]
 pub enum RoleType {
     AddAgent,
     UpdateAgents,
     DisableAgent,
     RemoveAgent,
     SetCodeHash,
+    SetLimits,
+    EnableAgent,
 }
 pub fn get_role(
     mut sess: Session<MinimalRuntime>,
     registry: &AccountId32,
     role_type: &RoleType,
 ) -> Result<(AccountId32, Session<MinimalRuntime>), Box<dyn Error>> {
-    let role_string = match role_type {
-        RoleType::AddAgent => "AddAgent",
-        RoleType::UpdateAgents => "UpdateAgents",
-        RoleType::DisableAgent => "DisableAgent",
-        RoleType::RemoveAgent => "RemoveAgent",
-        RoleType::SetCodeHash => "SetCodeHash",
-    };
+    let role_string = role_type_string(role_type);
     sess.call_with_address(registry.clone(), "IRegistry::get_role", &[role_string], None)?;
 
     let role: Result<AccountId32, drink::errors::LangError> = sess.last_call_return().unwrap();
@@ -211,94 +614,231 @@ pub fn get_role_admin(
     registry: &AccountId32,
     role_type: &RoleType,
 ) -> Result<(AccountId32, Session<MinimalRuntime>), Box<dyn Error>> {
-    let role_string = match role_type {
-        RoleType::AddAgent => "AddAgent",
-        RoleType::UpdateAgents => "UpdateAgents",
-        RoleType::DisableAgent => "DisableAgent",
-        RoleType::RemoveAgent => "RemoveAgent",
-        RoleType::SetCodeHash => "SetCodeHash",
-    };
+    let role_string = role_type_string(role_type);
     sess.call_with_address(registry.clone(), "IRegistry::get_role_admin", &[role_string], None)?;
 
     let admin: Result<AccountId32, drink::errors::LangError> = sess.last_call_return().unwrap();
     Ok((admin.unwrap(), sess))
 }
-pub fn transfer_role(
+pub fn get_role_members(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    role_type: &RoleType,
+) -> Result<(Vec<AccountId32>, Session<MinimalRuntime>), Box<dyn Error>> {
+    let role_string = role_type_string(role_type);
+    sess.call_with_address(registry.clone(), "IRegistry::get_role_members", &[role_string], None)?;
+
+    let members: Result<Vec<AccountId32>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((members.unwrap(), sess))
+}
+pub fn has_role(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    role_type: &RoleType,
+    account: &AccountId32,
+) -> Result<(bool, Session<MinimalRuntime>), Box<dyn Error>> {
+    let role_string = role_type_string(role_type);
+    sess.call_with_address(
+        registry.clone(),
+        "IRegistry::has_role",
+        &[role_string.to_string(), account.to_string()],
+        None,
+    )?;
+
+    let result: Result<bool, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((result.unwrap(), sess))
+}
+pub fn grant_role(
     sess: Session<MinimalRuntime>,
     registry: &AccountId32,
     sender: &AccountId32,
     role_type: &RoleType,
-    new_account: &AccountId32,
+    account: &AccountId32,
 ) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
-    let role_string = match role_type {
-        RoleType::AddAgent => "AddAgent",
-        RoleType::UpdateAgents => "UpdateAgents",
-        RoleType::DisableAgent => "DisableAgent",
-        RoleType::RemoveAgent => "RemoveAgent",
-        RoleType::SetCodeHash => "SetCodeHash",
-    };
+    let role_string = role_type_string(role_type);
     let sess = call_function(
         sess,
         &registry,
         &sender,
-        String::from("IRegistry::transfer_role"),
-        Some([role_string.to_string(), new_account.to_string()].to_vec()),
+        String::from("IRegistry::grant_role"),
+        Some([role_string.to_string(), account.to_string()].to_vec()),
         None,
         transcoder_registry(),
     )?;
     Ok(sess)
 }
-pub fn transfer_role_admin(
+pub fn revoke_role(
     sess: Session<MinimalRuntime>,
     registry: &AccountId32,
     sender: &AccountId32,
     role_type: &RoleType,
-    new_account: &AccountId32,
+    account: &AccountId32,
 ) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
-    let role_string = match role_type {
+    let role_string = role_type_string(role_type);
+    let sess = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::revoke_role"),
+        Some([role_string.to_string(), account.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+fn role_type_string(role_type: &RoleType) -> &'static str {
+    match role_type {
         RoleType::AddAgent => "AddAgent",
         RoleType::UpdateAgents => "UpdateAgents",
         RoleType::DisableAgent => "DisableAgent",
         RoleType::RemoveAgent => "RemoveAgent",
         RoleType::SetCodeHash => "SetCodeHash",
-    };
+        RoleType::SetLimits => "SetLimits",
+        RoleType::EnableAgent => "EnableAgent",
+    }
+}
+pub fn propose_role(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    role_type: &RoleType,
+    new_account: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let role_string = role_type_string(role_type);
     let sess = call_function(
         sess,
         &registry,
         &sender,
-        String::from("IRegistry::transfer_role_admin"),
+        String::from("IRegistry::propose_role"),
         Some([role_string.to_string(), new_account.to_string()].to_vec()),
         None,
         transcoder_registry(),
     )?;
     Ok(sess)
 }
-pub fn get_role_adjust_fee(
-    mut sess: Session<MinimalRuntime>,
-    vault: &AccountId32,
-) -> Result<(AccountId32, Session<MinimalRuntime>), Box<dyn Error>> {
-    sess.call_with_address(vault.clone(), "IVault::get_role_adjust_fee", NO_ARGS, None)?;
-
-    let adjust_fee: Result<AccountId32, drink::errors::LangError> = sess.last_call_return().unwrap();
-    Ok((adjust_fee.unwrap(), sess))
-}
-pub fn get_role_fee_to(
+pub fn accept_role(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    role_type: &RoleType,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let role_string = role_type_string(role_type);
+    let sess = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::accept_role"),
+        Some([role_string.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn propose_role_admin(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    role_type: &RoleType,
+    new_account: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let role_string = role_type_string(role_type);
+    let sess = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::propose_role_admin"),
+        Some([role_string.to_string(), new_account.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn accept_role_admin(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    role_type: &RoleType,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let role_string = role_type_string(role_type);
+    let sess = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::accept_role_admin"),
+        Some([role_string.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn renounce_role(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    role_type: &RoleType,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let role_string = role_type_string(role_type);
+    let sess = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::renounce_role"),
+        Some([role_string.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+/// Proposes the new account as the role holder and accepts on its behalf
+pub fn transfer_role(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    role_type: &RoleType,
+    new_account: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = propose_role(sess, registry, sender, role_type, new_account)?;
+    accept_role(sess, registry, new_account, role_type)
+}
+/// Proposes the new account as the role admin and accepts on its behalf
+pub fn transfer_role_admin(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    role_type: &RoleType,
+    new_account: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = propose_role_admin(sess, registry, sender, role_type, new_account)?;
+    accept_role_admin(sess, registry, new_account, role_type)
+}
+pub fn get_pending_role(
     mut sess: Session<MinimalRuntime>,
-    vault: &AccountId32,
-) -> Result<(AccountId32, Session<MinimalRuntime>), Box<dyn Error>> {
-    sess.call_with_address(vault.clone(), "IVault::get_role_fee_to", NO_ARGS, None)?;
+    registry: &AccountId32,
+    role_type: &RoleType,
+) -> Result<(Option<AccountId32>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(registry.clone(), "IRegistry::get_pending_role", &[role_type_string(role_type)], None)?;
 
-    let fee_to: Result<AccountId32, drink::errors::LangError> = sess.last_call_return().unwrap();
-    Ok((fee_to.unwrap(), sess))
+    let pending: Result<Option<AccountId32>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((pending.unwrap(), sess))
 }
-pub fn get_role_set_code(
+pub fn get_vault_role(
     mut sess: Session<MinimalRuntime>,
     vault: &AccountId32,
+    role_type: &VaultRoleType,
 ) -> Result<(Option<AccountId32>, Session<MinimalRuntime>), Box<dyn Error>> {
-    sess.call_with_address(vault.clone(), "IVault::get_role_set_code", NO_ARGS, None)?;
+    let role_string = match role_type {
+        VaultRoleType::AdjustFee => "AdjustFee",
+        VaultRoleType::FeeTo => "FeeTo",
+        VaultRoleType::SetCode => "SetCode",
+        VaultRoleType::Pauser => "Pauser",
+        VaultRoleType::Keeper => "Keeper",
+        VaultRoleType::LimitsAdmin => "LimitsAdmin",
+        VaultRoleType::Guardian => "Guardian",
+    };
+    sess.call_with_address(vault.clone(), "IVault::get_role", &[role_string], None)?;
 
-    let set_code: Result<Option<AccountId32>, drink::errors::LangError> = sess.last_call_return().unwrap();
-    Ok((set_code.unwrap(), sess))
+    let role: Result<Option<AccountId32>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((role.unwrap(), sess))
 }
 pub fn get_agents(
     mut sess: Session<MinimalRuntime>,
@@ -311,6 +851,139 @@ pub fn get_agents(
 
     Ok((total_weight, agents, sess))
 }
+pub fn get_pending_weight_updates(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+) -> Result<(Vec<ScheduledWeightUpdates>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(registry.clone(), "IRegistry::get_pending_weight_updates", NO_ARGS, None)?;
+
+    let result: Result<Vec<ScheduledWeightUpdates>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((result.unwrap(), sess))
+}
+pub fn get_history(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    offset: u32,
+    len: u32,
+) -> Result<(Vec<HistoryEntry>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(registry.clone(), "IRegistry::get_history", &[offset.to_string(), len.to_string()], None)?;
+
+    let result: Result<Vec<HistoryEntry>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((result.unwrap(), sess))
+}
+pub fn get_history_count(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+) -> Result<(u32, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(registry.clone(), "IRegistry::get_history_count", NO_ARGS, None)?;
+
+    let result: Result<u32, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((result.unwrap(), sess))
+}
+pub fn get_agent(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    agent: &AccountId32,
+) -> Result<(Option<(Agent, AgentMetadata)>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(registry.clone(), "IRegistry::get_agent", &[agent.to_string()], None)?;
+
+    let result: Result<Option<(Agent, AgentMetadata)>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((result.unwrap(), sess))
+}
+pub fn get_agent_lifecycle(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    agent: &AccountId32,
+) -> Result<(Option<AgentLifecycle>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(registry.clone(), "IRegistry::get_agent_lifecycle", &[agent.to_string()], None)?;
+
+    let result: Result<Option<AgentLifecycle>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((result.unwrap(), sess))
+}
+pub fn get_retired_agents(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+) -> Result<(Vec<(AccountId32, AgentLifecycle)>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(registry.clone(), "IRegistry::get_retired_agents", NO_ARGS, None)?;
+
+    let result: Result<Vec<(AccountId32, AgentLifecycle)>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((result.unwrap(), sess))
+}
+pub fn get_agent_by_validator(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    validator: &AccountId32,
+) -> Result<(Option<(Agent, AgentMetadata)>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(registry.clone(), "IRegistry::get_agent_by_validator", &[validator.to_string()], None)?;
+
+    let result: Result<Option<(Agent, AgentMetadata)>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((result.unwrap(), sess))
+}
+pub fn get_agents_paginated(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    start: u32,
+    len: u32,
+) -> Result<(Vec<Agent>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(
+        registry.clone(),
+        "IRegistry::get_agents_paginated",
+        &[start.to_string(), len.to_string()],
+        None,
+    )?;
+
+    let result: Result<Vec<Agent>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((result.unwrap(), sess))
+}
+pub fn get_agent_count(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+) -> Result<(u32, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(registry.clone(), "IRegistry::get_agent_count", NO_ARGS, None)?;
+
+    let result: Result<u32, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((result.unwrap(), sess))
+}
+pub fn get_agent_code_hash(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    agent: &AccountId32,
+) -> Result<(Option<String>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(registry.clone(), "IRegistry::get_agent_code_hash", &[agent.to_string()], None)?;
+
+    let result: Result<Option<[u8; 32]>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    // Formatted like the deployed hashes for comparison
+    let code_hash = result.unwrap().map(|hash| {
+        format!("0x{}", hash.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+    });
+    Ok((code_hash, sess))
+}
+pub fn call_set_max_agents(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    new_max_agents: u32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::set_max_agents"),
+        Some([new_max_agents.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn get_agents_overview(
+    mut sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+) -> Result<(Vec<AgentOverview>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(registry.clone(), "IRegistry::get_agents_overview", NO_ARGS, None)?;
+
+    let result: Result<Vec<AgentOverview>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((result.unwrap(), sess))
+}
 pub fn get_current_virtual_shares(
     sess: Session<MinimalRuntime>,
     vault: &AccountId32,
@@ -325,8 +998,8 @@ pub fn get_current_virtual_shares(
         transcoder_vault(),
     )
     .unwrap();
-    let virtual_shares: Result<u128, drink::errors::LangError> = sess.last_call_return().unwrap();
-    Ok((virtual_shares.unwrap(), sess))
+    let virtual_shares: Result<Result<u128, VaultError>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((virtual_shares.unwrap().unwrap(), sess))
 }
 pub fn get_azero_from_shares(
     sess: Session<MinimalRuntime>,
@@ -343,8 +1016,8 @@ pub fn get_azero_from_shares(
         transcoder_vault(),
     )
         .unwrap();
-    let azero: Result<u128, drink::errors::LangError> = sess.last_call_return().unwrap();
-    Ok((azero.unwrap(), sess))
+    let azero: Result<Result<u128, VaultError>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((azero.unwrap().unwrap(), sess))
 }
 pub fn get_total_pooled(
     sess: Session<MinimalRuntime>,
@@ -360,8 +1033,41 @@ pub fn get_total_pooled(
         transcoder_vault(),
     )
     .unwrap();
-    let total_pooled: Result<u128, drink::errors::LangError> = sess.last_call_return().unwrap();
-    Ok((total_pooled.unwrap(), sess))
+    let total_pooled: Result<Result<u128, VaultError>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((total_pooled.unwrap().unwrap(), sess))
+}
+pub fn get_unvested_rewards(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+) -> Result<(u128, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        vault,
+        &AccountId32::new([1u8; 32]),
+        String::from("IVault::get_unvested_rewards"),
+        None,
+        None,
+        transcoder_vault(),
+    )
+    .unwrap();
+    let unvested: Result<Result<u128, VaultError>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((unvested.unwrap().unwrap(), sess))
+}
+pub fn call_compound(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::compound"),
+        None,
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
 }
 pub fn query_nominator_balance(
     sess: Session<MinimalRuntime>,
@@ -394,6 +1100,23 @@ pub fn query_nominator_balance(
 
     Ok((stake, unbond, sess))
 }
+pub fn get_agent_validators(
+    sess: Session<MinimalRuntime>,
+    nominator: &AccountId32,
+) -> Result<(Vec<AccountId32>, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &nominator,
+        &AccountId32::new([1u8; 32]),
+        String::from("INominationAgent::get_validators"),
+        None,
+        None,
+        transcoder_nominator(),
+    )?;
+
+    let validators: Result<Vec<AccountId32>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((validators.unwrap(), sess))
+}
 pub fn query_token_balance(
     mut sess: Session<MinimalRuntime>,
     token: &AccountId32,
@@ -475,6 +1198,450 @@ pub fn call_withdraw_fees(
     Ok(sess)
 }
 
+pub fn call_propose_fee(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    new_fee: u16,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::propose_fee"),
+        Some(vec![new_fee.to_string()]),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+pub fn call_execute_fee(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::execute_fee"),
+        None,
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+pub fn call_propose_performance_fee(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    new_fee: u16,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::propose_performance_fee"),
+        Some(vec![new_fee.to_string()]),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+pub fn call_execute_performance_fee(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::execute_performance_fee"),
+        None,
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+pub fn call_propose_fee_model(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    fee_model: &str,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::propose_fee_model"),
+        Some(vec![fee_model.to_string()]),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+pub fn call_execute_fee_model(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::execute_fee_model"),
+        None,
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+pub fn call_propose_reward_vesting_period(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    new_period: u64,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::propose_reward_vesting_period"),
+        Some(vec![new_period.to_string()]),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+pub fn call_execute_reward_vesting_period(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::execute_reward_vesting_period"),
+        None,
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+pub fn get_unlock_requests(
+    mut sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    user: &AccountId32,
+) -> Result<(Vec<UnlockRequest>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(vault.clone(), "IVault::get_unlock_requests", &[user.to_string()], None)?;
+
+    let requests: Result<Vec<UnlockRequest>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((requests.unwrap(), sess))
+}
+pub fn get_storage_version(
+    mut sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+) -> Result<(u16, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(vault.clone(), "IVault::get_storage_version", NO_ARGS, None)?;
+
+    let version: Result<u16, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((version.unwrap(), sess))
+}
+pub fn get_fee_percentage(
+    mut sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+) -> Result<(u16, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(vault.clone(), "IVault::get_fee_percentage", NO_ARGS, None)?;
+
+    let fee: Result<u16, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((fee.unwrap(), sess))
+}
+pub fn get_performance_fee_percentage(
+    mut sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+) -> Result<(u16, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(vault.clone(), "IVault::get_performance_fee_percentage", NO_ARGS, None)?;
+
+    let fee: Result<u16, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((fee.unwrap(), sess))
+}
+pub fn get_reward_vesting_period(
+    mut sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+) -> Result<(u64, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(vault.clone(), "IVault::get_reward_vesting_period", NO_ARGS, None)?;
+
+    let period: Result<u64, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((period.unwrap(), sess))
+}
+pub fn call_set_fee_recipients(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    recipients: Vec<FeeRecipient>,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::set_fee_recipients"),
+        Some(vec![serde_json::to_string(&recipients).unwrap()]),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+
+/// Encodes a message call (selector and arguments) as a hex string for the governor
+pub fn encode_call(
+    transcoder: Option<Rc<ContractMessageTranscoder>>,
+    func_name: &str,
+    args: &[String],
+) -> String {
+    let call_data = transcoder.unwrap().encode(func_name, args).unwrap();
+    format!("0x{}", call_data.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+pub fn call_governor_schedule(
+    sess: Session<MinimalRuntime>,
+    governor: &AccountId32,
+    sender: &AccountId32,
+    target: &AccountId32,
+    call_data: String,
+) -> Result<(u32, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &governor,
+        &sender,
+        String::from("IGovernor::schedule"),
+        Some([target.to_string(), call_data, "0".to_string()].to_vec()),
+        None,
+        transcoder_governor(),
+    )?;
+    // Reverted calls return early above so only the success variant is decoded here
+    let id: Result<Result<u32, ()>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((id.unwrap().unwrap(), sess))
+}
+pub fn call_governor_schedule_propose_fee(
+    sess: Session<MinimalRuntime>,
+    governor: &AccountId32,
+    sender: &AccountId32,
+    new_fee: u16,
+) -> Result<(u32, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &governor,
+        &sender,
+        String::from("IGovernor::schedule_propose_fee"),
+        Some(vec![new_fee.to_string()]),
+        None,
+        transcoder_governor(),
+    )?;
+    let id: Result<Result<u32, ()>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((id.unwrap().unwrap(), sess))
+}
+pub fn call_governor_schedule_execute_fee(
+    sess: Session<MinimalRuntime>,
+    governor: &AccountId32,
+    sender: &AccountId32,
+) -> Result<(u32, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &governor,
+        &sender,
+        String::from("IGovernor::schedule_execute_fee"),
+        None,
+        None,
+        transcoder_governor(),
+    )?;
+    let id: Result<Result<u32, ()>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((id.unwrap().unwrap(), sess))
+}
+pub fn call_governor_schedule_update_agents(
+    sess: Session<MinimalRuntime>,
+    governor: &AccountId32,
+    sender: &AccountId32,
+    updates: Vec<WeightUpdate>,
+) -> Result<(u32, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &governor,
+        &sender,
+        String::from("IGovernor::schedule_update_agents"),
+        Some(vec![serde_json::to_string(&updates).unwrap()]),
+        None,
+        transcoder_governor(),
+    )?;
+    let id: Result<Result<u32, ()>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((id.unwrap().unwrap(), sess))
+}
+pub fn call_governor_execute(
+    sess: Session<MinimalRuntime>,
+    governor: &AccountId32,
+    sender: &AccountId32,
+    id: u32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &governor,
+        &sender,
+        String::from("IGovernor::execute"),
+        Some([id.to_string()].to_vec()),
+        None,
+        transcoder_governor(),
+    )?;
+    Ok(sess)
+}
+pub fn get_operation(
+    mut sess: Session<MinimalRuntime>,
+    governor: &AccountId32,
+    id: u32,
+) -> Result<(Option<Operation>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(governor.clone(), "IGovernor::get_operation", &[id.to_string()], None)?;
+
+    let operation: Result<Option<Operation>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((operation.unwrap(), sess))
+}
+pub fn call_governor_cancel(
+    sess: Session<MinimalRuntime>,
+    governor: &AccountId32,
+    sender: &AccountId32,
+    id: u32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &governor,
+        &sender,
+        String::from("IGovernor::cancel"),
+        Some([id.to_string()].to_vec()),
+        None,
+        transcoder_governor(),
+    )?;
+    Ok(sess)
+}
+pub fn call_multisig_propose(
+    sess: Session<MinimalRuntime>,
+    multisig: &AccountId32,
+    sender: &AccountId32,
+    target: &AccountId32,
+    call_data: String,
+    transferred_value: u128,
+) -> Result<(u32, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &multisig,
+        &sender,
+        String::from("IMultisig::propose"),
+        Some([target.to_string(), call_data, transferred_value.to_string()].to_vec()),
+        None,
+        transcoder_multisig(),
+    )?;
+    // Reverted calls return early above so only the success variant is decoded here
+    let id: Result<Result<u32, ()>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((id.unwrap().unwrap(), sess))
+}
+pub fn call_multisig_approve(
+    sess: Session<MinimalRuntime>,
+    multisig: &AccountId32,
+    sender: &AccountId32,
+    id: u32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &multisig,
+        &sender,
+        String::from("IMultisig::approve"),
+        Some([id.to_string()].to_vec()),
+        None,
+        transcoder_multisig(),
+    )?;
+    Ok(sess)
+}
+pub fn call_multisig_execute(
+    sess: Session<MinimalRuntime>,
+    multisig: &AccountId32,
+    sender: &AccountId32,
+    id: u32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &multisig,
+        &sender,
+        String::from("IMultisig::execute"),
+        Some([id.to_string()].to_vec()),
+        None,
+        transcoder_multisig(),
+    )?;
+    Ok(sess)
+}
+pub fn get_multisig_threshold(
+    mut sess: Session<MinimalRuntime>,
+    multisig: &AccountId32,
+) -> Result<(u16, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(multisig.clone(), "IMultisig::get_threshold", NO_ARGS, None)?;
+
+    let threshold: Result<u16, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((threshold.unwrap(), sess))
+}
+pub fn get_multisig_approval_count(
+    mut sess: Session<MinimalRuntime>,
+    multisig: &AccountId32,
+    id: u32,
+) -> Result<(u16, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(multisig.clone(), "IMultisig::get_approval_count", &[id.to_string()], None)?;
+
+    let approvals: Result<u16, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((approvals.unwrap(), sess))
+}
+pub fn get_multisig_owners(
+    mut sess: Session<MinimalRuntime>,
+    multisig: &AccountId32,
+) -> Result<(Vec<AccountId32>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(multisig.clone(), "IMultisig::get_owners", NO_ARGS, None)?;
+
+    let owners: Result<Vec<AccountId32>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((owners.unwrap(), sess))
+}
+pub fn call_allocate(
+    sess: Session<MinimalRuntime>,
+    weight_voting: &AccountId32,
+    sender: &AccountId32,
+    allocations: Vec<Allocation>,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &weight_voting,
+        &sender,
+        String::from("IWeightVoting::allocate"),
+        Some(vec![serde_json::to_string(&allocations).unwrap()]),
+        None,
+        transcoder_weight_voting(),
+    )?;
+    Ok(sess)
+}
+pub fn call_finalize(
+    sess: Session<MinimalRuntime>,
+    weight_voting: &AccountId32,
+    sender: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &weight_voting,
+        &sender,
+        String::from("IWeightVoting::finalize"),
+        None,
+        None,
+        transcoder_weight_voting(),
+    )?;
+    Ok(sess)
+}
+/// Decodes the output of the last call, which is recorded even if the call reverted
+pub fn last_call_output<T: scale::Decode>(sess: &Session<MinimalRuntime>) -> Result<T, Box<dyn Error>> {
+    let result = sess.last_call_result().ok_or("No call has been made")?;
+    let output = result.result.as_ref().map_err(|e| format!("Call failed: {:?}", e))?;
+    Ok(T::decode(&mut &output.data[..])?)
+}
 pub fn call_function(
     mut sess: Session<MinimalRuntime>,
     contract: &AccountId32,
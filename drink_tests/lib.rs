@@ -337,7 +337,7 @@ mod tests {
             &ctx.vault,
             &ctx.bob,
             String::from("IVault::adjust_fee"),
-            Some(vec![String::from("1000")]),
+            Some(vec![String::from("100")]), // lowered from 2%
             None,
             helpers::transcoder_vault(),
         )
@@ -353,7 +353,119 @@ mod tests {
         )
         .unwrap();
         let res: Result<u16, drink::errors::LangError> = sess.last_call_return().unwrap();
-        assert_eq!(res.unwrap(), 1000)
+        assert_eq!(res.unwrap(), 100)
+    }
+    #[test]
+    fn test_fee_adjustment_panic_because_increase_is_timelocked() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        match helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_fee"),
+            Some(vec![String::from("300")]), // raised from 2%
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because fee increases must be proposed"),
+            Err(_) => (),
+        }
+    }
+    #[test]
+    fn test_fee_proposal_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_propose_fee(ctx.sess, &ctx.vault, &ctx.bob, 400).unwrap();
+
+        // Fee is unchanged until the proposal is executed
+        let sess = helpers::update_days(sess, 7);
+        let (fee, sess) = helpers::get_fee_percentage(sess, &ctx.vault).unwrap();
+        assert_eq!(fee, 200);
+
+        let sess = helpers::call_execute_fee(sess, &ctx.vault, &ctx.bob).unwrap();
+        let (fee, _sess) = helpers::get_fee_percentage(sess, &ctx.vault).unwrap();
+        assert_eq!(fee, 400);
+    }
+    #[test]
+    fn test_execute_fee_panic_because_timelocked() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_propose_fee(ctx.sess, &ctx.vault, &ctx.bob, 400).unwrap();
+        let sess = helpers::update_days(sess, 6);
+
+        match helpers::call_execute_fee(sess, &ctx.vault, &ctx.bob) {
+            Ok(_) => panic!("Should panic because the fee proposal delay has not elapsed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_propose_fee_panic_because_step_too_large() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_propose_fee(ctx.sess, &ctx.vault, &ctx.bob, 500) { // 3% step from 2%
+            Ok(_) => panic!("Should panic because the fee step is too large"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_propose_fee_panic_because_above_hard_cap() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let mut sess = ctx.sess;
+
+        // Step the fee up to the 10% hard cap
+        for new_fee in [400, 600, 800, 1000] {
+            sess = helpers::call_propose_fee(sess, &ctx.vault, &ctx.bob, new_fee).unwrap();
+            sess = helpers::update_days(sess, 7);
+            sess = helpers::call_execute_fee(sess, &ctx.vault, &ctx.bob).unwrap();
+        }
+
+        match helpers::call_propose_fee(sess, &ctx.vault, &ctx.bob, 1100) {
+            Ok(_) => panic!("Should panic because the fee exceeds the hard cap"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_cancel_fee_proposal_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_propose_fee(ctx.sess, &ctx.vault, &ctx.bob, 400).unwrap();
+
+        // Guardian (Bob) cancels the proposal
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::cancel_fee_proposal"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        let sess = helpers::update_days(sess, 7);
+        match helpers::call_execute_fee(sess, &ctx.vault, &ctx.bob) {
+            Ok(_) => panic!("Should panic because the fee proposal was cancelled"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_cancel_fee_proposal_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_propose_fee(ctx.sess, &ctx.vault, &ctx.bob, 400).unwrap();
+
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.ed, // neither guardian nor adjust fee role
+            String::from("IVault::cancel_fee_proposal"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
     }
     #[test]
     fn test_fee_adjustment_panic_because_caller_restricted() {
@@ -388,6 +500,45 @@ mod tests {
         };
     }
     #[test]
+    fn test_get_azero_from_shares_panic_because_overflow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Stake 100 AZERO
+        let (_, mut sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 100e12 as u128).unwrap();
+
+        // Compound 100 AZERO of rewards to move the redemption ratio above 1:1
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), 100e12 as u128);
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::compound"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        // u128::MAX shares are worth more than u128::MAX AZERO
+        let mut sess = sess;
+        sess.set_actor(ctx.bob.clone());
+        assert!(sess.call_with_address(ctx.vault.clone(), "IVault::get_azero_from_shares", &[u128::MAX.to_string()], None).is_err());
+        let output: Result<Result<u128, helpers::VaultError>, drink::errors::LangError> = helpers::last_call_output(&sess).unwrap();
+        assert_eq!(output.unwrap(), Err(helpers::VaultError::Overflow));
+    }
+    #[test]
+    fn test_request_unlock_panic_because_shares_exceed_supply() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Stake 100 AZERO
+        let (shares, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 100e12 as u128).unwrap();
+
+        match helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, shares + 1) {
+            Ok(_) => panic!("Should panic because shares cannot underflow"),
+            Err(_) => (),
+        };
+    }
+    #[test]
     fn test_withdraw_fees_after_one_second_success() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
@@ -473,8 +624,12 @@ mod tests {
 
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
+        // Propose fee of 4% ahead of time
+        let sess = helpers::call_propose_fee(ctx.sess, &ctx.vault, &ctx.bob, 400).unwrap(); // 4% in helpers::BIPS
+        let sess = helpers::update_days(sess, 7);
+
         // Initial stake
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE).unwrap();
 
         // 2% fee for 1 day
         let sess = helpers::update_days(sess, 1);
@@ -485,16 +640,7 @@ mod tests {
         );
 
         // Adjust fee to 4%
-        let sess = helpers::call_function(
-            sess,
-            &ctx.vault,
-            &ctx.bob,
-            String::from("IVault::adjust_fee"),
-            Some(vec![String::from("400")]), // 4% in helpers::BIPS
-            None,
-            helpers::transcoder_vault(),
-        )
-        .unwrap();
+        let sess = helpers::call_execute_fee(sess, &ctx.vault, &ctx.bob).unwrap();
 
         // 4% fee for 1 day
         let sess = helpers::update_days(sess, 1);
@@ -515,6 +661,93 @@ mod tests {
         );
     }
     #[test]
+    fn test_withdraw_fees_split_among_recipients() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        const STAKE_AMOUNT: u128 = 100e12 as u128;
+
+        // Treasury (Charlie) 70%, insurance (Dave) 20%, referral partner (Ed) 10%
+        let sess = helpers::call_set_fee_recipients(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            vec![
+                helpers::FeeRecipient { account: ctx.charlie.clone(), bips: 7000 },
+                helpers::FeeRecipient { account: ctx.dave.clone(), bips: 2000 },
+                helpers::FeeRecipient { account: ctx.ed.clone(), bips: 1000 },
+            ],
+        )
+            .unwrap();
+
+        // Stake 100 AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, STAKE_AMOUNT).unwrap();
+
+        let sess = helpers::update_days(sess, 365);
+
+        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+
+        // Withdraw fees
+        let sess = helpers::call_withdraw_fees(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        let (charlie_shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.charlie).unwrap();
+        let (dave_shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.dave).unwrap();
+        let (ed_shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.ed).unwrap();
+        let (bob_shares, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+
+        assert_eq!(dave_shares, claimable_fees * 2000 / helpers::BIPS);
+        assert_eq!(ed_shares, claimable_fees * 1000 / helpers::BIPS);
+        assert_eq!(charlie_shares, claimable_fees - dave_shares - ed_shares); // includes dust
+        assert_eq!(bob_shares, 0);
+    }
+    #[test]
+    fn test_set_fee_recipients_panic_because_bips_do_not_sum() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_set_fee_recipients(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            vec![
+                helpers::FeeRecipient { account: ctx.charlie.clone(), bips: 7000 },
+                helpers::FeeRecipient { account: ctx.dave.clone(), bips: 2000 },
+            ],
+        ) {
+            Ok(_) => panic!("Should panic because fee split does not sum to 10000"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_set_fee_recipients_panic_because_duplicate_recipient() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_set_fee_recipients(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            vec![
+                helpers::FeeRecipient { account: ctx.charlie.clone(), bips: 5000 },
+                helpers::FeeRecipient { account: ctx.charlie.clone(), bips: 5000 },
+            ],
+        ) {
+            Ok(_) => panic!("Should panic because recipients must be unique"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_set_fee_recipients_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_set_fee_recipients(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.ed, // not bob
+            vec![helpers::FeeRecipient { account: ctx.ed.clone(), bips: 10000 }],
+        ) {
+            Ok(_) => panic!("Should panic because caller does not have fee to role (Bob)"),
+            Err(_) => (),
+        };
+    }
+    #[test]
     fn test_withdraw_fees_panic_because_caller_restricted() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
         let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
@@ -529,14 +762,14 @@ mod tests {
         };
     }
     #[test]
-    fn test_vault_transfer_role_adjust_fee_panic_because_caller_restricted() {
+    fn test_vault_propose_role_adjust_fee_panic_because_caller_restricted() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
         match helpers::call_function(
             ctx.sess,
             &ctx.vault,
             &ctx.alice, // not bob
-            String::from("IVault::transfer_role_adjust_fee"),
-            Some([ctx.charlie.to_string()].to_vec()),
+            String::from("IVault::propose_role"),
+            Some(["AdjustFee".to_string(), ctx.charlie.to_string()].to_vec()),
             None,
             helpers::transcoder_vault(),
         ) {
@@ -548,549 +781,2805 @@ mod tests {
     fn test_vault_transfer_role_adjust_fee_flow() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        let (adjust_fee, sess) = helpers::get_role_adjust_fee(ctx.sess, &ctx.vault).unwrap();
-        assert_eq!(adjust_fee, ctx.bob);
+        let (adjust_fee, sess) = helpers::get_vault_role(ctx.sess, &ctx.vault, &helpers::VaultRoleType::AdjustFee).unwrap();
+        assert_eq!(adjust_fee, Some(ctx.bob.clone()));
+
+        // Propose Charlie
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_role"),
+            Some(["AdjustFee".to_string(), ctx.charlie.to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        // Role is unchanged until accepted
+        let (adjust_fee, sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::AdjustFee).unwrap();
+        assert_eq!(adjust_fee, Some(ctx.bob.clone()));
 
-        // Transfer role to Charlie
         let sess = helpers::call_function(
             sess,
             &ctx.vault,
-            &adjust_fee,
-            String::from("IVault::transfer_role_adjust_fee"),
-            Some([ctx.charlie.to_string()].to_vec()),
+            &ctx.charlie,
+            String::from("IVault::accept_role"),
+            Some(["AdjustFee".to_string()].to_vec()),
             None,
             helpers::transcoder_vault(),
         )
             .unwrap();
 
-        let (adjust_fee, _sess) = helpers::get_role_adjust_fee(sess, &ctx.vault).unwrap();
-        assert_eq!(adjust_fee, ctx.charlie);
+        let (adjust_fee, _sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::AdjustFee).unwrap();
+        assert_eq!(adjust_fee, Some(ctx.charlie.clone()));
     }
     #[test]
-    fn test_vault_transfer_role_fee_to_panic_because_caller_restricted() {
+    fn test_vault_accept_role_adjust_fee_panic_because_caller_not_proposed() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
-        match helpers::call_function(
+
+        let sess = helpers::call_function(
             ctx.sess,
             &ctx.vault,
-            &ctx.alice, // not bob
-            String::from("IVault::transfer_role_fee_to"),
-            Some([ctx.charlie.to_string()].to_vec()),
+            &ctx.bob,
+            String::from("IVault::propose_role"),
+            Some(["AdjustFee".to_string(), ctx.charlie.to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.dave, // not charlie
+            String::from("IVault::accept_role"),
+            Some(["AdjustFee".to_string()].to_vec()),
             None,
             helpers::transcoder_vault(),
         ) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+            Ok(_) => panic!("Should panic because caller is not the proposed account"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_vault_transfer_role_fee_to_flow() {
+    fn test_vault_cancel_role_adjust_fee_proposal_flow() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        let (fee_to, sess) = helpers::get_role_fee_to(ctx.sess, &ctx.vault).unwrap();
-        assert_eq!(fee_to, ctx.bob);
-
-        // Transfer role to Charlie
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_role"),
+            Some(["AdjustFee".to_string(), ctx.charlie.to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
         let sess = helpers::call_function(
             sess,
             &ctx.vault,
-            &fee_to,
-            String::from("IVault::transfer_role_fee_to"),
-            Some([ctx.charlie.to_string()].to_vec()),
+            &ctx.bob,
+            String::from("IVault::cancel_role_proposal"),
+            Some(["AdjustFee".to_string()].to_vec()),
             None,
             helpers::transcoder_vault(),
-        )
-            .unwrap();
+        ).unwrap();
 
-        let (fee_to, _sess) = helpers::get_role_fee_to(sess, &ctx.vault).unwrap();
-        assert_eq!(fee_to, ctx.charlie);
-    }
-    #[test]
-    fn test_vault_transfer_role_set_code_panic_because_caller_restricted() {
-        let ctx = setup(VALIDATOR_COUNT).unwrap();
         match helpers::call_function(
-            ctx.sess,
+            sess,
             &ctx.vault,
-            &ctx.alice, // not bob
-            String::from("IVault::transfer_role_set_code"),
-            Some([ctx.charlie.to_string()].to_vec()),
+            &ctx.charlie,
+            String::from("IVault::accept_role"),
+            Some(["AdjustFee".to_string()].to_vec()),
             None,
             helpers::transcoder_vault(),
         ) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+            Ok(_) => panic!("Should panic because the proposal was cancelled"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_vault_disable_set_code_flow() {
+    fn test_vault_renounce_role_fee_to_flow() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        let (set_code, sess) = helpers::get_role_set_code(ctx.sess, &ctx.vault).unwrap();
-        assert_eq!(set_code, Some(ctx.bob.clone()));
-
         let sess = helpers::call_function(
-            sess,
+            ctx.sess,
             &ctx.vault,
             &ctx.bob,
-            String::from("IVault::disable_set_code"),
+            String::from("IVault::propose_role"),
+            Some(["FeeTo".to_string(), ctx.charlie.to_string()].to_vec()),
             None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.charlie,
+            String::from("IVault::accept_role"),
+            Some(["FeeTo".to_string()].to_vec()),
             None,
             helpers::transcoder_vault(),
         ).unwrap();
 
-        let (set_code, sess) = helpers::get_role_set_code(sess, &ctx.vault).unwrap();
-        assert_eq!(set_code, None);
+        // Charlie gives the role back to its admin (Bob)
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.charlie,
+            String::from("IVault::renounce_role"),
+            Some(["FeeTo".to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+
+        let (fee_to, _sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::FeeTo).unwrap();
+        assert_eq!(fee_to, Some(ctx.bob.clone()));
+    }
+    #[test]
+    fn test_vault_new_roles_assigned_to_admin() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
 
+        let (pauser, sess) = helpers::get_vault_role(ctx.sess, &ctx.vault, &helpers::VaultRoleType::Pauser).unwrap();
+        assert_eq!(pauser, Some(ctx.bob.clone()));
+        let (keeper, sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::Keeper).unwrap();
+        assert_eq!(keeper, Some(ctx.bob.clone()));
+        let (limits_admin, _sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::LimitsAdmin).unwrap();
+        assert_eq!(limits_admin, Some(ctx.bob.clone()));
+    }
+    #[test]
+    fn test_vault_propose_role_fee_to_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
         match helpers::call_function(
-            sess,
+            ctx.sess,
             &ctx.vault,
-            &ctx.bob,
-            String::from("IVault::transfer_role_set_code"),
-            Some([ctx.charlie.to_string()].to_vec()),
+            &ctx.alice, // not bob
+            String::from("IVault::propose_role"),
+            Some(["FeeTo".to_string(), ctx.charlie.to_string()].to_vec()),
             None,
             helpers::transcoder_vault(),
         ) {
-            Ok(_) => panic!("Should panic because role is disabled"),
+            Ok(_) => panic!("Should panic because caller is restricted"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_vault_transfer_role_set_code_flow() {
+    fn test_vault_transfer_role_fee_to_flow() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        let (set_code, sess) = helpers::get_role_set_code(ctx.sess, &ctx.vault).unwrap();
-        assert_eq!(set_code, Some(ctx.bob.clone()));
+        let (fee_to, sess) = helpers::get_vault_role(ctx.sess, &ctx.vault, &helpers::VaultRoleType::FeeTo).unwrap();
+        assert_eq!(fee_to, Some(ctx.bob.clone()));
 
-        // Transfer role to Charlie
+        // Propose Charlie
         let sess = helpers::call_function(
             sess,
             &ctx.vault,
-            &set_code.unwrap(),
-            String::from("IVault::transfer_role_set_code"),
-            Some([ctx.charlie.to_string()].to_vec()),
+            &ctx.bob,
+            String::from("IVault::propose_role"),
+            Some(["FeeTo".to_string(), ctx.charlie.to_string()].to_vec()),
             None,
             helpers::transcoder_vault(),
         )
             .unwrap();
 
-        let (set_code, _sess) = helpers::get_role_set_code(sess, &ctx.vault).unwrap();
-        assert_eq!(set_code, Some(ctx.charlie.clone()));
-    }
-    #[test]
-    fn test_nominator_add_agent_role_flow() {
-        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        // Role is unchanged until accepted
+        let (fee_to, sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::FeeTo).unwrap();
+        assert_eq!(fee_to, Some(ctx.bob.clone()));
 
-        // Check roles
-        let (role, sess) = helpers::get_role(ctx.sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
-        assert_eq!(role, ctx.bob);
-        let (admin, sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
-        assert_eq!(admin, ctx.bob);
-
-        // Bob (admin) transfers role to Charlie
-        let sess = helpers::transfer_role(sess, &ctx.registry, &admin, &helpers::RoleType::AddAgent, &ctx.charlie).unwrap();
-        // Bob (admin) transfers admin to Charlie
-        let sess = helpers::transfer_role_admin(sess, &ctx.registry, &admin, &helpers::RoleType::AddAgent, &ctx.charlie).unwrap();
-
-        // Check roles
-        let (role, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
-        assert_eq!(role, ctx.charlie);
-        let (admin, _sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
-        assert_eq!(admin, ctx.charlie);
-    }
-    #[test]
-    fn test_nominator_add_agent_role_panic_on_transfer_role_because_caller_not_admin() {
-        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.charlie,
+            String::from("IVault::accept_role"),
+            Some(["FeeTo".to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
 
-        // Charlie (not admin) cannot transfer role
-        match helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
-            Err(_) => (),
-        };
+        let (fee_to, _sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::FeeTo).unwrap();
+        assert_eq!(fee_to, Some(ctx.charlie.clone()));
     }
     #[test]
-    fn test_nominator_add_agent_role_panic_on_transfer_admin_because_caller_not_admin() {
+    fn test_vault_propose_role_set_code_panic_because_caller_restricted() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
-
-        // Charlie (not admin) cannot transfer admin
-        match helpers::transfer_role_admin(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
+        match helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.alice, // not bob
+            String::from("IVault::propose_role"),
+            Some(["SetCode".to_string(), ctx.charlie.to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ) {
             Ok(_) => panic!("Should panic because caller is restricted"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_update_agents_role_flow() {
+    fn test_vault_disable_set_code_flow() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        // Check roles
-        let (role, sess) = helpers::get_role(ctx.sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
-        assert_eq!(role, ctx.bob);
-        let (admin, sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
-        assert_eq!(admin, ctx.bob);
+        let (set_code, sess) = helpers::get_vault_role(ctx.sess, &ctx.vault, &helpers::VaultRoleType::SetCode).unwrap();
+        assert_eq!(set_code, Some(ctx.bob.clone()));
 
-        // Bob (admin) transfers role to Charlie
-        let sess = helpers::transfer_role(sess, &ctx.registry, &admin, &helpers::RoleType::UpdateAgents, &ctx.charlie).unwrap();
-        // Bob (admin) transfers admin to Charlie
-        let sess = helpers::transfer_role_admin(sess, &ctx.registry, &admin, &helpers::RoleType::UpdateAgents, &ctx.charlie).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::disable_set_code"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
 
-        // Check roles
-        let (role, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
-        assert_eq!(role, ctx.charlie);
-        let (admin, _sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
-        assert_eq!(admin, ctx.charlie);
-    }
-    #[test]
-    fn test_nominator_update_agents_role_panic_on_transfer_role_because_caller_not_admin() {
-        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (set_code, sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::SetCode).unwrap();
+        assert_eq!(set_code, None);
 
-        // Charlie (not admin) cannot transfer role
-        match helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_role"),
+            Some(["SetCode".to_string(), ctx.charlie.to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because role is disabled"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_update_agents_role_panic_on_transfer_admin_because_caller_not_admin() {
+    fn test_vault_apply_code_panic_because_timelocked() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        // Charlie (not admin) cannot transfer admin
-        match helpers::transfer_role_admin(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::announce_code"),
+            Some([helpers::hash_vault()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let sess = helpers::update_days(sess, 13);
+
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::apply_code"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because the announcement delay has not elapsed"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_remove_agent_role_flow() {
+    fn test_vault_announce_code_flow() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        // Check roles
-        let (role, sess) = helpers::get_role(ctx.sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
-        assert_eq!(role, ctx.bob);
-        let (admin, sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
-        assert_eq!(admin, ctx.bob);
-
-        // Bob (admin) transfers role to Charlie
-        let sess = helpers::transfer_role(sess, &ctx.registry, &admin, &helpers::RoleType::RemoveAgent, &ctx.charlie).unwrap();
-        // Bob (admin) transfers admin to Charlie
-        let sess = helpers::transfer_role_admin(sess, &ctx.registry, &admin, &helpers::RoleType::RemoveAgent, &ctx.charlie).unwrap();
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::announce_code"),
+            Some([helpers::hash_vault()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let sess = helpers::update_days(sess, 14);
 
-        // Check roles
-        let (role, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
-        assert_eq!(role, ctx.charlie);
-        let (admin, _sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
-        assert_eq!(admin, ctx.charlie);
-    }
-    #[test]
-    fn test_nominator_disable_agent_role_panic_on_transfer_role_because_caller_not_admin() {
-        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::apply_code"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
 
-        // Charlie (not admin) cannot transfer role
-        match helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::DisableAgent, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+        // Announcement is consumed
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::apply_code"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because there is no pending code hash"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_disable_agent_role_panic_on_transfer_admin_because_caller_not_admin() {
+    fn test_vault_cancel_code_flow() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        // Charlie (not admin) cannot transfer admin
-        match helpers::transfer_role_admin(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::DisableAgent, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
-            Err(_) => (),
-        };
-    }
-    #[test]
-    fn test_nominator_remove_agent_role_panic_on_transfer_role_because_caller_not_admin() {
-        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::announce_code"),
+            Some([helpers::hash_vault()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::cancel_code"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let sess = helpers::update_days(sess, 14);
 
-        // Charlie (not admin) cannot transfer role
-        match helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::apply_code"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because the announcement was cancelled"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_remove_agent_role_panic_on_transfer_admin_because_caller_not_admin() {
+    fn test_vault_adjust_set_code_delay_panic_because_below_cooldown() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        // Charlie (not admin) cannot transfer admin
-        match helpers::transfer_role_admin(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
-            Err(_) => (),
-        };
-    }
-    #[test]
-    fn test_nominator_addition_panic_because_caller_restricted() {
-        let ctx = setup(2 as usize).unwrap();
-
-        match helpers::call_add_agent(
+        match helpers::call_function(
             ctx.sess,
-            &ctx.registry,
-            &ctx.charlie, // does not have `helpers::RoleType::AddAgent`
-            &ctx.charlie,
-            &ctx.validators[ctx.validators.len() - 1],
-            100e12 as u128,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_set_code_delay"),
+            Some([(helpers::DAY * 13).to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
         ) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+            Ok(_) => panic!("Should panic because the delay is shorter than the cooldown period"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_addition_panic_because_too_many_agents() {
+    fn test_registry_apply_agent_code_panic_because_timelocked() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        let (_, agents_before, mut sess) = helpers::get_agents(ctx.sess, &ctx.registry).unwrap();
-        let prior_agent_count = agents_before.len();
-
-        for i in prior_agent_count..ctx.max_agents {
-            (_, sess) = helpers::call_add_agent(
-                sess,
-                &ctx.registry,
-                &ctx.bob,
-                &ctx.charlie,
-                &AccountId32::new([i as u8; 32]),
-                100e12 as u128,
-            ).unwrap();
-        }
-
-        let (_, agents_after, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
-        assert_eq!(agents_after.len(), ctx.max_agents);
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            String::from("IRegistry::announce_agent_code"),
+            Some([helpers::hash_nominator()].to_vec()),
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+        let sess = helpers::update_days(sess, 13);
 
-        match helpers::call_add_agent(
+        match helpers::call_function(
             sess,
             &ctx.registry,
             &ctx.bob,
-            &ctx.charlie,
-            &AccountId32::new([ctx.max_agents as u8; 32]),
-            100e12 as u128,
+            String::from("IRegistry::apply_agent_code"),
+            None,
+            None,
+            helpers::transcoder_registry(),
         ) {
-            Ok(_) => panic!("Should panic because agent count exceeded"),
+            Ok(_) => panic!("Should panic because the announcement delay has not elapsed"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_update_panic_because_caller_restricted() {
+    fn test_registry_announce_agent_code_flow() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        match helpers::call_update_agents(
+        let sess = helpers::call_function(
             ctx.sess,
             &ctx.registry,
-            &ctx.charlie, // does not have `helpers::RoleType::UpdateAgents`
-            vec![helpers::WeightUpdate {
-                agent: ctx.nominators[0].clone(),
-                weight: 100,
-                increase: true,
-            }],
-        ) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
-            Err(_) => (),
-        };
-    }
-    #[test]
-    fn test_nominator_update_panic_because_new_weight_too_low() {
-        let ctx = setup(VALIDATOR_COUNT).unwrap();
+            &ctx.bob,
+            String::from("IRegistry::announce_agent_code"),
+            Some([helpers::hash_nominator()].to_vec()),
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+        let sess = helpers::update_days(sess, 14);
 
-        match helpers::call_update_agents(
-            ctx.sess,
+        helpers::call_function(
+            sess,
             &ctx.registry,
             &ctx.bob,
-            vec![helpers::WeightUpdate {
-                agent: ctx.nominators[0].clone(),
-                weight: 101, // weight is already set to 100
-                increase: false,
-            }],
-        ) {
-            Ok(_) => panic!("Should panic because weight cannot underflow"),
-            Err(_) => (),
-        };
+            String::from("IRegistry::apply_agent_code"),
+            None,
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
     }
     #[test]
-    fn test_nominator_disable_panic_because_caller_restricted() {
+    fn test_registry_agent_code_hash_recorded_on_add() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        match helpers::call_disable_agent(
+        let (code_hash, _sess) = helpers::get_agent_code_hash(ctx.sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        assert_eq!(code_hash, Some(helpers::hash_nominator()));
+    }
+    #[test]
+    fn test_registry_set_agent_code_for_current_hash() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Agents already running the current code are skipped
+        let sess = helpers::call_set_agent_code_for(
             ctx.sess,
             &ctx.registry,
-            &ctx.charlie, // does not have `helpers::RoleType::DisableAgent`
-            &ctx.nominators[0],
-        ) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
-            Err(_) => (),
-        };
+            &ctx.bob,
+            [ctx.nominators[0].clone()].to_vec(),
+            helpers::hash_nominator(),
+        ).unwrap();
+
+        let (code_hash, _sess) = helpers::get_agent_code_hash(sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        assert_eq!(code_hash, Some(helpers::hash_nominator()));
     }
     #[test]
-    fn test_nominator_disable_success() {
+    fn test_registry_set_agent_code_for_reports_failed_agents() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
+        // Announced but never uploaded, so every agent fails to switch to it
+        let missing_hash = format!("0x{}", "09".repeat(32));
 
-        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+        let sess = helpers::call_function(
             ctx.sess,
             &ctx.registry,
-        )
-            .unwrap();
-
-        let agent_to_disable = agents_before[0].address.clone();
+            &ctx.bob,
+            String::from("IRegistry::announce_agent_code"),
+            Some([missing_hash.clone()].to_vec()),
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+        let sess = helpers::update_days(sess, 14);
 
-        // Disable agent
-        let sess = helpers::call_disable_agent(
+        // Failures are returned rather than reverting the call
+        let sess = helpers::call_set_agent_code_for(
             sess,
             &ctx.registry,
-            &ctx.bob, // has `helpers::RoleType::DisableAgent`
-            &agent_to_disable,
-        )
-            .unwrap();
+            &ctx.bob,
+            [ctx.nominators[0].clone()].to_vec(),
+            missing_hash.clone(),
+        ).unwrap();
 
-        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+        let (code_hash, sess) = helpers::get_agent_code_hash(sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        assert_eq!(code_hash, Some(helpers::hash_nominator()));
+
+        // Announcement is not consumed by a staged rollout
+        let sess = helpers::call_function(
             sess,
             &ctx.registry,
-        )
-            .unwrap();
+            &ctx.bob,
+            String::from("IRegistry::apply_agent_code"),
+            None,
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
 
-        assert_eq!(agents_after.len(), agents_before.len());
-        assert_eq!(total_weight_after, total_weight_before - agents_before[0].weight);
-        assert_eq!(agents_after[0].address, agents_before[0].address);
-        assert_eq!(agents_after[0].weight, 0);
+        let (code_hash, _sess) = helpers::get_agent_code_hash(sess, &ctx.registry, &ctx.nominators[1]).unwrap();
+        assert_eq!(code_hash, Some(helpers::hash_nominator()));
+    }
+    #[test]
+    fn test_registry_set_agent_code_for_panic_because_not_announced() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        // Attempt to add weight back
-        match helpers::call_update_agents(
-            sess,
+        match helpers::call_set_agent_code_for(
+            ctx.sess,
             &ctx.registry,
             &ctx.bob,
-            vec![helpers::WeightUpdate {
-                agent: agent_to_disable.clone(),
-                weight: 100,
-                increase: true,
-            }],
+            [ctx.nominators[0].clone()].to_vec(),
+            format!("0x{}", "09".repeat(32)),
         ) {
-            Ok(_) => panic!("Should panic because agent is disabled"),
+            Ok(_) => panic!("Should panic because the code hash was not announced"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_remove_panic_because_stake_is_non_zero() {
+    fn test_registry_set_agent_code_for_panic_because_timelocked() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let missing_hash = format!("0x{}", "09".repeat(32));
 
-        // Stake 1k AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            String::from("IRegistry::announce_agent_code"),
+            Some([missing_hash.clone()].to_vec()),
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+        let sess = helpers::update_days(sess, 13);
 
-        match helpers::call_remove_agent(
+        match helpers::call_set_agent_code_for(
             sess,
             &ctx.registry,
-            &ctx.bob, // has `helpers::RoleType::RemoveAgent`
-            &ctx.nominators[0],
+            &ctx.bob,
+            [ctx.nominators[0].clone()].to_vec(),
+            missing_hash,
         ) {
-            Ok(_) => panic!("Should panic because nominators[0] has stake"),
+            Ok(_) => panic!("Should panic because the announcement delay has not elapsed"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_remove_panic_because_caller_restricted() {
+    fn test_registry_set_agent_code_for_panic_because_caller_restricted() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        match helpers::call_remove_agent(
+        match helpers::call_set_agent_code_for(
             ctx.sess,
             &ctx.registry,
-            &ctx.charlie, // does not have `helpers::RoleType::RemoveAgent`
-            &ctx.nominators[0],
+            &ctx.alice, // not bob
+            [ctx.nominators[0].clone()].to_vec(),
+            helpers::hash_nominator(),
         ) {
             Ok(_) => panic!("Should panic because caller is restricted"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_remove_success() {
+    fn test_registry_announce_code_panic_because_caller_restricted() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+        match helpers::call_function(
             ctx.sess,
             &ctx.registry,
-        )
-            .unwrap();
+            &ctx.alice, // not bob
+            String::from("IRegistry::announce_code"),
+            Some([helpers::hash_registry()].to_vec()),
+            None,
+            helpers::transcoder_registry(),
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_vault_migrate_panic_because_already_current() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        // Remove agent
-        let sess = helpers::call_remove_agent(
-            sess,
-            &ctx.registry,
-            &ctx.bob, // has `helpers::RoleType::RemoveAgent`
-            &ctx.nominators[0],
-        )
-            .unwrap();
+        let (version, sess) = helpers::get_storage_version(ctx.sess, &ctx.vault).unwrap();
+        assert_eq!(version, 2);
 
-        let (total_weight_after, agents_after, _sess) = helpers::get_agents(
+        match helpers::call_function(
             sess,
-            &ctx.registry,
-        )
-            .unwrap();
-
-        assert_eq!(agents_after.len(), agents_before.len() - 1);
-        assert_eq!(total_weight_after, total_weight_before - agents_before[1].weight);
-        assert_eq!(agents_after[0].address, agents_before[1].address);
-        assert_eq!(agents_after[0].weight, agents_before[1].weight);
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::migrate"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because storage is already on the current version"),
+            Err(_) => (),
+        };
     }
     #[test]
-    fn test_nominator_addition_equal_weights() -> Result<(), Box<dyn Error>> {
-        let ctx = setup(2 as usize).unwrap();
-
-        // Stake 10 million AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000e12 as u128).unwrap();
+    fn test_vault_upgrade_preserves_storage() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
 
-        let (stake1, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        let (stake2, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        assert_eq!(stake1, 5_000_000e12 as u128); // 50% to agent0
-        assert_eq!(stake2, 5_000_000e12 as u128); // 50% to agent1
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 100e12 as u128).unwrap();
+        let (requests_v1, sess) = helpers::get_unlock_requests(sess, &ctx.vault, &ctx.alice).unwrap();
+        assert_eq!(requests_v1.len(), 1);
 
-        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+        // Transfer a role away from the admin so that roles are not all identical
+        let sess = helpers::call_function(
             sess,
-            &ctx.registry,
-        )
-            .unwrap();
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_role"),
+            Some(["FeeTo".to_string(), ctx.charlie.to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let mut sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.charlie,
+            String::from("IVault::accept_role"),
+            Some(["FeeTo".to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
 
-        // Add nomination agent
-        let (_new_agent, sess) = helpers::call_add_agent(
+        // Upgrade to v2
+        sess.upload(helpers::bytes_mock_vault_v2()).expect("Session should upload mock vault v2 bytes");
+        let sess = helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.vault,
             &ctx.bob,
+            String::from("IVault::announce_code"),
+            Some([helpers::hash_mock_vault_v2()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let sess = helpers::update_days(sess, 14);
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
             &ctx.bob,
-            &AccountId32::new([101u8; 32]),
-            100e12 as u128,
-        )?;
+            String::from("IVault::apply_code"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
 
-        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+        // Storage remains on the previous version until migrated
+        let mut sess = sess;
+        sess.set_transcoder(ctx.vault.clone(), &helpers::transcoder_mock_vault_v2().unwrap());
+        let (version, sess) = helpers::get_storage_version(sess, &ctx.vault).unwrap();
+        assert_eq!(version, 2);
+
+        let sess = helpers::call_function(
             sess,
-            &ctx.registry,
-        )
-            .unwrap();
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::migrate"),
+            None,
+            None,
+            helpers::transcoder_mock_vault_v2(),
+        ).unwrap();
+        let (version, sess) = helpers::get_storage_version(sess, &ctx.vault).unwrap();
+        assert_eq!(version, 3);
 
-        assert_eq!(agents_after.len(), agents_before.len() + 1);
-        assert_eq!(total_weight_after, total_weight_before);
-        assert_eq!(agents_after[2].weight, 0);
+        // Unlock requests survive
+        let (requests_v2, sess) = helpers::get_unlock_requests(sess, &ctx.vault, &ctx.alice).unwrap();
+        assert_eq!(requests_v2, requests_v1);
 
-        // Update weight from 0 to 100
-        let sess = helpers::call_update_agents(
+        // Roles survive
+        let (set_code, sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::SetCode).unwrap();
+        assert_eq!(set_code, Some(ctx.bob.clone()));
+        let (adjust_fee, sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::AdjustFee).unwrap();
+        assert_eq!(adjust_fee, Some(ctx.bob.clone()));
+        let (fee_to, sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::FeeTo).unwrap();
+        assert_eq!(fee_to, Some(ctx.charlie.clone()));
+
+        // Migration can only run once per version
+        match helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.vault,
             &ctx.bob,
-            vec![helpers::WeightUpdate {
-                agent: agents_after[2].address.clone(),
-                weight: 100,
-                increase: true,
-            }],
-        )
-            .unwrap();
+            String::from("IVault::migrate"),
+            None,
+            None,
+            helpers::transcoder_mock_vault_v2(),
+        ) {
+            Ok(_) => panic!("Should panic because storage is already migrated"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_vault_migrate_from_unversioned_storage() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let mut sess = ctx.sess;
 
-        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+        // Deploy with the layout which predates storage versioning and upgrade to the current code
+        sess.set_actor(ctx.bob.clone());
+        let vault = sess.deploy(
+            helpers::bytes_mock_legacy_vault(),
+            "new_v0",
+            &[
+                ctx.charlie.to_string(),
+                ctx.share_token.to_string(),
+                ctx.registry.to_string(),
+                1_000e12.to_string(),
+                helpers::DAY.to_string(),
+            ],
+            vec![1],
+            None,
+            &helpers::transcoder_mock_legacy_vault().unwrap(),
+        ).unwrap();
+        let mut sess = helpers::call_function(
             sess,
-            &ctx.registry,
-        )
-            .unwrap();
+            &vault,
+            &ctx.bob,
+            String::from("set_code"),
+            Some([helpers::hash_vault()].to_vec()),
+            None,
+            helpers::transcoder_mock_legacy_vault(),
+        ).unwrap();
+        sess.set_transcoder(vault.clone(), &helpers::transcoder_vault().unwrap());
 
-        assert_eq!(agents_after.len(), agents_before.len() + 1);
-        assert_eq!(total_weight_after, total_weight_before + 100);
-        assert_eq!(agents_after[2].weight, 100);
+        let (version, mut sess) = helpers::get_storage_version(sess, &vault).unwrap();
+        assert_eq!(version, 0);
 
-        // Stake additional 10 million AZERO
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000e12 as u128).unwrap();
+        // Only the legacy set code role can migrate
+        sess.set_actor(ctx.charlie.clone());
+        assert!(sess.call_with_address(vault.clone(), "IVault::migrate", drink::session::NO_ARGS, None).is_err());
 
-        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &vault,
+            &ctx.bob,
+            String::from("IVault::migrate"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let (version, sess) = helpers::get_storage_version(sess, &vault).unwrap();
+        assert_eq!(version, 2);
+
+        // Packed fields survive
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &vault).unwrap();
+        assert_eq!(total_pooled, 1_000e12 as u128);
+
+        // Legacy roles move into the role mapping, the guardian is assigned to the migrating account
+        let (adjust_fee, sess) = helpers::get_vault_role(sess, &vault, &helpers::VaultRoleType::AdjustFee).unwrap();
+        assert_eq!(adjust_fee, Some(ctx.bob.clone()));
+        let (fee_to, sess) = helpers::get_vault_role(sess, &vault, &helpers::VaultRoleType::FeeTo).unwrap();
+        assert_eq!(fee_to, Some(ctx.charlie.clone()));
+        let (set_code, sess) = helpers::get_vault_role(sess, &vault, &helpers::VaultRoleType::SetCode).unwrap();
+        assert_eq!(set_code, Some(ctx.bob.clone()));
+        let (guardian, sess) = helpers::get_vault_role(sess, &vault, &helpers::VaultRoleType::Guardian).unwrap();
+        assert_eq!(guardian, Some(ctx.bob.clone()));
+
+        // Settings introduced before storage versioning are initialized
+        let (performance_fee, sess) = helpers::get_performance_fee_percentage(sess, &vault).unwrap();
+        assert_eq!(performance_fee, 10_00);
+        let sess = helpers::call_function(
+            sess,
+            &vault,
+            &ctx.bob,
+            String::from("IVault::get_fee_recipients"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let recipients: Result<Vec<helpers::FeeRecipient>, drink::errors::LangError> = sess.last_call_return().unwrap();
+        let recipients = recipients.unwrap();
+        assert_eq!(recipients.len(), 1);
+        assert_eq!(recipients[0].account, ctx.charlie);
+        assert_eq!(recipients[0].bips, 10_000);
+        let sess = helpers::call_function(
+            sess,
+            &vault,
+            &ctx.bob,
+            String::from("IVault::get_set_code_delay"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let delay: Result<u64, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(delay.unwrap(), helpers::DAY * 14);
+    }
+    #[test]
+    fn test_vault_migrate_from_storage_version_1() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let mut sess = ctx.sess;
+
+        // Deploy with the layout of storage version 1 and upgrade to the current code
+        sess.set_actor(ctx.bob.clone());
+        let vault = sess.deploy(
+            helpers::bytes_mock_legacy_vault(),
+            "new_v1",
+            &[
+                ctx.charlie.to_string(),
+                ctx.alice.to_string(),
+                ctx.share_token.to_string(),
+                ctx.registry.to_string(),
+                1_000e12.to_string(),
+                helpers::DAY.to_string(),
+            ],
+            vec![1],
+            None,
+            &helpers::transcoder_mock_legacy_vault().unwrap(),
+        ).unwrap();
+        let mut sess = helpers::call_function(
+            sess,
+            &vault,
+            &ctx.bob,
+            String::from("set_code"),
+            Some([helpers::hash_vault()].to_vec()),
+            None,
+            helpers::transcoder_mock_legacy_vault(),
+        ).unwrap();
+        sess.set_transcoder(vault.clone(), &helpers::transcoder_vault().unwrap());
+
+        let (version, sess) = helpers::get_storage_version(sess, &vault).unwrap();
+        assert_eq!(version, 1);
+
+        let sess = helpers::call_function(
+            sess,
+            &vault,
+            &ctx.bob,
+            String::from("IVault::migrate"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let (version, sess) = helpers::get_storage_version(sess, &vault).unwrap();
+        assert_eq!(version, 2);
+
+        // Each legacy role holder keeps its role and administers it, including the guardian
+        let (fee_to, sess) = helpers::get_vault_role(sess, &vault, &helpers::VaultRoleType::FeeTo).unwrap();
+        assert_eq!(fee_to, Some(ctx.charlie.clone()));
+        let (set_code, sess) = helpers::get_vault_role(sess, &vault, &helpers::VaultRoleType::SetCode).unwrap();
+        assert_eq!(set_code, Some(ctx.bob.clone()));
+        let (guardian, sess) = helpers::get_vault_role(sess, &vault, &helpers::VaultRoleType::Guardian).unwrap();
+        assert_eq!(guardian, Some(ctx.alice.clone()));
+        let sess = helpers::call_function(
+            sess,
+            &vault,
+            &ctx.bob,
+            String::from("IVault::get_role_admin"),
+            Some(["Guardian".to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let guardian_admin: Result<Option<AccountId32>, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(guardian_admin.unwrap(), Some(ctx.alice.clone()));
+
+        // Roles introduced with storage version 2 are assigned to the migrating account
+        let (keeper, sess) = helpers::get_vault_role(sess, &vault, &helpers::VaultRoleType::Keeper).unwrap();
+        assert_eq!(keeper, Some(ctx.bob.clone()));
+
+        // Settings of version 1 are kept as they were
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &vault).unwrap();
+        assert_eq!(total_pooled, 1_000e12 as u128);
+        let sess = helpers::call_function(
+            sess,
+            &vault,
+            &ctx.bob,
+            String::from("IVault::get_reward_vesting_period"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+        let period: Result<u64, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(period.unwrap(), helpers::DAY);
+    }
+    #[test]
+    fn test_registry_migrate_from_unversioned_storage() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let mut sess = ctx.sess;
+
+        // Deploy with the layout which predates storage versioning and upgrade to the current code
+        sess.set_actor(ctx.bob.clone());
+        let registry = sess.deploy(
+            helpers::bytes_mock_legacy_registry(),
+            "new",
+            &[ctx.vault.to_string(), helpers::hash_nominator()],
+            vec![1],
+            None,
+            &helpers::transcoder_mock_legacy_registry().unwrap(),
+        ).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &registry,
+            &ctx.bob,
+            String::from("set_code"),
+            Some([helpers::hash_registry()].to_vec()),
+            None,
+            helpers::transcoder_mock_legacy_registry(),
+        ).unwrap();
+
+        let sess = helpers::call_function(
+            sess,
+            &registry,
+            &ctx.bob,
+            String::from("IRegistry::migrate"),
+            None,
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &registry,
+            &ctx.bob,
+            String::from("IRegistry::get_storage_version"),
+            None,
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+        let version: Result<u16, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(version.unwrap(), 1);
+
+        // Roles survive and roles introduced since are assigned to the migrating account
+        let (set_code_hash, sess) = helpers::get_role(sess, &registry, &helpers::RoleType::SetCodeHash).unwrap();
+        assert_eq!(set_code_hash, ctx.bob);
+        let (set_limits, sess) = helpers::get_role(sess, &registry, &helpers::RoleType::SetLimits).unwrap();
+        assert_eq!(set_limits, ctx.bob);
+
+        // Code upgrades use the minimum delay until it is changed
+        let sess = helpers::call_function(
+            sess,
+            &registry,
+            &ctx.bob,
+            String::from("IRegistry::get_code_delay"),
+            None,
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+        let delay: Result<u64, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(delay.unwrap(), helpers::DAY * 14);
+        helpers::call_function(
+            sess,
+            &registry,
+            &ctx.bob,
+            String::from("IRegistry::announce_code"),
+            Some([helpers::hash_registry()].to_vec()),
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+    }
+    #[test]
+    fn test_vault_transfer_role_set_code_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (set_code, sess) = helpers::get_vault_role(ctx.sess, &ctx.vault, &helpers::VaultRoleType::SetCode).unwrap();
+        assert_eq!(set_code, Some(ctx.bob.clone()));
+
+        // Propose Charlie
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_role"),
+            Some(["SetCode".to_string(), ctx.charlie.to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        // Role is unchanged until accepted
+        let (set_code, sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::SetCode).unwrap();
+        assert_eq!(set_code, Some(ctx.bob.clone()));
+
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.charlie,
+            String::from("IVault::accept_role"),
+            Some(["SetCode".to_string()].to_vec()),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        let (set_code, _sess) = helpers::get_vault_role(sess, &ctx.vault, &helpers::VaultRoleType::SetCode).unwrap();
+        assert_eq!(set_code, Some(ctx.charlie.clone()));
+    }
+    #[test]
+    fn test_nominator_add_agent_role_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Check roles
+        let (role, sess) = helpers::get_role(ctx.sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
+        assert_eq!(role, ctx.bob);
+        let (admin, sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
+        assert_eq!(admin, ctx.bob);
+
+        // Bob (admin) transfers role to Charlie
+        let sess = helpers::transfer_role(sess, &ctx.registry, &admin, &helpers::RoleType::AddAgent, &ctx.charlie).unwrap();
+        // Bob (admin) transfers admin to Charlie
+        let sess = helpers::transfer_role_admin(sess, &ctx.registry, &admin, &helpers::RoleType::AddAgent, &ctx.charlie).unwrap();
+
+        // Check roles
+        let (role, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
+        assert_eq!(role, ctx.charlie);
+        let (admin, _sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
+        assert_eq!(admin, ctx.charlie);
+    }
+    #[test]
+    fn test_nominator_role_transfer_requires_acceptance() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::propose_role(ctx.sess, &ctx.registry, &ctx.bob, &helpers::RoleType::AddAgent, &ctx.charlie).unwrap();
+
+        // Role is unchanged until accepted
+        let (role, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
+        assert_eq!(role, ctx.bob);
+        let (pending, sess) = helpers::get_pending_role(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
+        assert_eq!(pending, Some(ctx.charlie.clone()));
+
+        // Dave (not proposed) cannot accept
+        match helpers::accept_role(sess, &ctx.registry, &ctx.dave, &helpers::RoleType::AddAgent) {
+            Ok(_) => panic!("Should panic because caller is not the proposed account"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_renounce_role_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.bob, &helpers::RoleType::UpdateAgents, &ctx.charlie).unwrap();
+        let (pending, sess) = helpers::get_pending_role(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(pending, None);
+
+        // Charlie gives the role back to its admin (Bob)
+        let sess = helpers::renounce_role(sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents).unwrap();
+        let (role, _sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(role, ctx.bob);
+    }
+    #[test]
+    fn test_nominator_add_agent_role_panic_on_transfer_role_because_caller_not_admin() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Charlie (not admin) cannot transfer role
+        match helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_add_agent_role_panic_on_transfer_admin_because_caller_not_admin() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Charlie (not admin) cannot transfer admin
+        match helpers::transfer_role_admin(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_agents_role_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Check roles
+        let (role, sess) = helpers::get_role(ctx.sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(role, ctx.bob);
+        let (admin, sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(admin, ctx.bob);
+
+        // Bob (admin) transfers role to Charlie
+        let sess = helpers::transfer_role(sess, &ctx.registry, &admin, &helpers::RoleType::UpdateAgents, &ctx.charlie).unwrap();
+        // Bob (admin) transfers admin to Charlie
+        let sess = helpers::transfer_role_admin(sess, &ctx.registry, &admin, &helpers::RoleType::UpdateAgents, &ctx.charlie).unwrap();
+
+        // Check roles
+        let (role, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(role, ctx.charlie);
+        let (admin, _sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(admin, ctx.charlie);
+    }
+    #[test]
+    fn test_nominator_update_agents_role_granted_to_second_account() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::grant_role(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &helpers::RoleType::UpdateAgents,
+            &ctx.charlie,
+        ).unwrap();
+
+        let (members, sess) = helpers::get_role_members(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(members, vec![ctx.bob.clone(), ctx.charlie.clone()]);
+        let (account, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(account, ctx.bob);
+
+        // Both accounts can use the role
+        let sess = helpers::call_set_weights(sess, &ctx.registry, &ctx.bob, vec![(ctx.nominators[0].clone(), 200)], None).unwrap();
+        let sess = helpers::call_set_weights(sess, &ctx.registry, &ctx.charlie, vec![(ctx.nominators[1].clone(), 200)], None).unwrap();
+
+        let (_, agents, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(agents[0].weight, 200);
+        assert_eq!(agents[1].weight, 200);
+    }
+    #[test]
+    fn test_nominator_update_agents_role_revoked() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::grant_role(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &helpers::RoleType::UpdateAgents,
+            &ctx.charlie,
+        ).unwrap();
+        let sess = helpers::revoke_role(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &helpers::RoleType::UpdateAgents,
+            &ctx.charlie,
+        ).unwrap();
+
+        let (has_role, sess) = helpers::has_role(sess, &ctx.registry, &helpers::RoleType::UpdateAgents, &ctx.charlie).unwrap();
+        assert!(!has_role);
+
+        match helpers::call_set_weights(sess, &ctx.registry, &ctx.charlie, vec![(ctx.nominators[0].clone(), 200)], None) {
+            Ok(_) => panic!("Should panic because the role was revoked"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_agents_role_revoke_role_account() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::propose_role(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &helpers::RoleType::UpdateAgents,
+            &ctx.charlie,
+        ).unwrap();
+        let sess = helpers::accept_role(sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents).unwrap();
+        let sess = helpers::grant_role(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &helpers::RoleType::UpdateAgents,
+            &ctx.dave,
+        ).unwrap();
+
+        // Role account passes to the remaining member
+        let sess = helpers::revoke_role(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &helpers::RoleType::UpdateAgents,
+            &ctx.charlie,
+        ).unwrap();
+        let (account, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(account, ctx.dave);
+
+        // Role returns to the admin once no members remain
+        let sess = helpers::revoke_role(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &helpers::RoleType::UpdateAgents,
+            &ctx.dave,
+        ).unwrap();
+        let (members, _sess) = helpers::get_role_members(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(members, vec![ctx.bob.clone()]);
+    }
+    #[test]
+    fn test_nominator_update_agents_role_panic_on_grant_because_caller_not_admin() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::grant_role(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie,
+            &helpers::RoleType::UpdateAgents,
+            &ctx.charlie,
+        ) {
+            Ok(_) => panic!("Should panic because caller is not the role admin"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_agents_role_panic_on_transfer_role_because_caller_not_admin() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Charlie (not admin) cannot transfer role
+        match helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents, &ctx.dave) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_agents_role_panic_on_transfer_admin_because_caller_not_admin() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Charlie (not admin) cannot transfer admin
+        match helpers::transfer_role_admin(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents, &ctx.dave) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_agent_role_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Check roles
+        let (role, sess) = helpers::get_role(ctx.sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
+        assert_eq!(role, ctx.bob);
+        let (admin, sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
+        assert_eq!(admin, ctx.bob);
+
+        // Bob (admin) transfers role to Charlie
+        let sess = helpers::transfer_role(sess, &ctx.registry, &admin, &helpers::RoleType::RemoveAgent, &ctx.charlie).unwrap();
+        // Bob (admin) transfers admin to Charlie
+        let sess = helpers::transfer_role_admin(sess, &ctx.registry, &admin, &helpers::RoleType::RemoveAgent, &ctx.charlie).unwrap();
+
+        // Check roles
+        let (role, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
+        assert_eq!(role, ctx.charlie);
+        let (admin, _sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
+        assert_eq!(admin, ctx.charlie);
+    }
+    #[test]
+    fn test_nominator_disable_agent_role_panic_on_transfer_role_because_caller_not_admin() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Charlie (not admin) cannot transfer role
+        match helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::DisableAgent, &ctx.dave) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_disable_agent_role_panic_on_transfer_admin_because_caller_not_admin() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Charlie (not admin) cannot transfer admin
+        match helpers::transfer_role_admin(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::DisableAgent, &ctx.dave) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_agent_role_panic_on_transfer_role_because_caller_not_admin() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Charlie (not admin) cannot transfer role
+        match helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_agent_role_panic_on_transfer_admin_because_caller_not_admin() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Charlie (not admin) cannot transfer admin
+        match helpers::transfer_role_admin(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_addition_panic_because_caller_restricted() {
+        let ctx = setup(2 as usize).unwrap();
+
+        match helpers::call_add_agent(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::AddAgent`
+            &ctx.charlie,
+            &ctx.validators[ctx.validators.len() - 1],
+            100e12 as u128,
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_addition_panic_because_too_many_agents() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (_, agents_before, mut sess) = helpers::get_agents(ctx.sess, &ctx.registry).unwrap();
+        let prior_agent_count = agents_before.len();
+
+        for i in prior_agent_count..ctx.max_agents {
+            (_, sess) = helpers::call_add_agent(
+                sess,
+                &ctx.registry,
+                &ctx.bob,
+                &ctx.charlie,
+                &AccountId32::new([i as u8; 32]),
+                100e12 as u128,
+            ).unwrap();
+        }
+
+        let (_, agents_after, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(agents_after.len(), ctx.max_agents);
+
+        match helpers::call_add_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.charlie,
+            &AccountId32::new([ctx.max_agents as u8; 32]),
+            100e12 as u128,
+        ) {
+            Ok(_) => panic!("Should panic because agent count exceeded"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_lower_max_agents_keeps_existing_agents() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_set_max_agents(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob, // has `helpers::RoleType::SetLimits`
+            10,
+        ).unwrap();
+
+        // Agents above the new cap remain registered
+        let (agent_count, sess) = helpers::get_agent_count(sess, &ctx.registry).unwrap();
+        assert_eq!(agent_count as usize, VALIDATOR_COUNT);
+
+        // Existing agents can still be removed
+        let sess = helpers::call_disable_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_drain_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_remove_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+
+        // Staking still reaches every remaining agent
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (staked, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[VALIDATOR_COUNT - 1]).unwrap();
+        assert!(staked > 0);
+
+        // New agents cannot be added until the count drops below the cap
+        match helpers::call_add_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &AccountId32::new([200u8; 32]),
+            100e12 as u128,
+        ) {
+            Ok(_) => panic!("Should panic because agent count exceeds the cap"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_set_max_agents_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_set_max_agents(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::SetLimits`
+            100,
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_stake_rotates_through_agent_pages() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let page_size = 50;
+        let agent_count = page_size + 10;
+
+        let mut sess = helpers::call_set_max_agents(ctx.sess, &ctx.registry, &ctx.bob, agent_count as u32).unwrap();
+
+        // Add agents beyond the first page with equal weight
+        let mut new_agents = Vec::new();
+        for i in VALIDATOR_COUNT..agent_count {
+            let agent;
+            (agent, sess) = helpers::call_add_agent(
+                sess,
+                &ctx.registry,
+                &ctx.bob,
+                &ctx.bob,
+                &AccountId32::new([i as u8; 32]),
+                100e12 as u128,
+            ).unwrap();
+            new_agents.push(helpers::WeightUpdate {
+                agent,
+                weight: 100,
+                increase: true,
+            });
+        }
+        let last_agent = new_agents[new_agents.len() - 1].agent.clone();
+        let sess = helpers::call_update_agents(sess, &ctx.registry, &ctx.bob, new_agents).unwrap();
+
+        // First deposit only reaches the first page
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 6_000e12 as u128).unwrap();
+        let (staked, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert!(staked > 0);
+        let (staked, _, sess) = helpers::query_nominator_balance(sess, &last_agent).unwrap();
+        assert_eq!(staked, 0);
+
+        // Next deposit moves on to the second page
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000e12 as u128).unwrap();
+        let (staked, _, _sess) = helpers::query_nominator_balance(sess, &last_agent).unwrap();
+        assert_eq!(staked, 100e12 as u128);
+    }
+    #[test]
+    fn test_nominator_addition_multiple_validators() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let validators: Vec<AccountId32> = (0..helpers::MAX_VALIDATORS)
+            .map(|i| AccountId32::new([150u8 + i as u8; 32]))
+            .collect();
+
+        let (agent, sess) = helpers::call_add_agent_with_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &validators,
+            false,
+            100e12 as u128,
+        ).unwrap();
+
+        let (nominated, _sess) = helpers::get_agent_validators(sess, &agent).unwrap();
+        assert_eq!(nominated, validators);
+    }
+    #[test]
+    fn test_nominator_addition_panic_because_too_many_validators() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let validators: Vec<AccountId32> = (0..helpers::MAX_VALIDATORS + 1)
+            .map(|i| AccountId32::new([150u8 + i as u8; 32]))
+            .collect();
+
+        match helpers::call_add_agent_with_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &validators,
+            false,
+            100e12 as u128,
+        ) {
+            Ok(_) => panic!("Should panic because validator count exceeded"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_addition_panic_because_no_validators() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_add_agent_with_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &[],
+            false,
+            100e12 as u128,
+        ) {
+            Ok(_) => panic!("Should panic because no validators are given"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_update_agents(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::UpdateAgents`
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 100,
+                increase: true,
+            }],
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_panic_because_new_weight_too_low() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_update_agents(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 101, // weight is already set to 100
+                increase: false,
+            }],
+        ) {
+            Ok(_) => panic!("Should panic because weight cannot underflow"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_panic_because_new_weight_overflows() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_update_agents(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: u128::MAX, // weight is already set to 100
+                increase: true,
+            }],
+        ) {
+            Ok(_) => panic!("Should panic because weight cannot overflow"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_panic_because_total_weight_overflows() {
+        let ctx = setup(2 as usize).unwrap();
+
+        // Each agent individually fits in u128 but their summation does not
+        match helpers::call_update_agents(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![
+                helpers::WeightUpdate {
+                    agent: ctx.nominators[0].clone(),
+                    weight: u128::MAX / 2,
+                    increase: true,
+                },
+                helpers::WeightUpdate {
+                    agent: ctx.nominators[1].clone(),
+                    weight: u128::MAX / 2,
+                    increase: true,
+                },
+            ],
+        ) {
+            Ok(_) => panic!("Should panic because total weight cannot overflow"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_set_weights_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_set_weights(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![(ctx.nominators[0].clone(), 300), (ctx.nominators[1].clone(), 0)],
+            Some(100 * VALIDATOR_COUNT as u128),
+        ).unwrap();
+
+        let (total_weight, agents, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(agents[0].weight, 300);
+        assert_eq!(agents[1].weight, 0);
+        assert_eq!(agents[2].weight, 100);
+        assert_eq!(total_weight, 100 * VALIDATOR_COUNT as u128 + 100);
+        assert_eq!(total_weight, agents.iter().map(|a| a.weight).sum::<u128>());
+
+        // Retrying the same batch leaves weights unchanged
+        let sess = helpers::call_set_weights(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![(ctx.nominators[0].clone(), 300), (ctx.nominators[1].clone(), 0)],
+            None,
+        ).unwrap();
+
+        let (total_weight_retry, agents, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(agents[0].weight, 300);
+        assert_eq!(agents[1].weight, 0);
+        assert_eq!(total_weight_retry, total_weight);
+    }
+    #[test]
+    fn test_nominator_set_weights_panic_because_total_weight_mismatch() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_set_weights(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![(ctx.nominators[0].clone(), 300)],
+            Some(100 * VALIDATOR_COUNT as u128),
+        ).unwrap();
+
+        // Retry conditioned on the original total weight is rejected
+        match helpers::call_set_weights(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![(ctx.nominators[0].clone(), 300)],
+            Some(100 * VALIDATOR_COUNT as u128),
+        ) {
+            Ok(_) => panic!("Should panic because total weight changed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_set_weights_panic_because_duplicate_agent() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_set_weights(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![(ctx.nominators[0].clone(), 300), (ctx.nominators[0].clone(), 200)],
+            None,
+        ) {
+            Ok(_) => panic!("Should panic because agent appears twice"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_set_weights_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_set_weights(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::UpdateAgents`
+            vec![(ctx.nominators[0].clone(), 300)],
+            None,
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_schedule_weight_updates_activated_by_anyone() {
+        let mut ctx = setup(VALIDATOR_COUNT).unwrap();
+        let activate_at = ctx.sess.chain_api().get_timestamp() + helpers::DAY;
+
+        let (total_weight_before, _, sess) = helpers::get_agents(ctx.sess, &ctx.registry).unwrap();
+        let sess = helpers::call_schedule_weight_updates(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 100,
+                increase: true,
+            }],
+            activate_at,
+        ).unwrap();
+
+        // Not effective before the activation time
+        let (total_weight, agents, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(total_weight, total_weight_before);
+        assert_eq!(agents[0].weight, 100);
+        let (pending, sess) = helpers::get_pending_weight_updates(sess, &ctx.registry).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].activate_at, activate_at);
+
+        // Views only reflect the batch once persisted
+        let sess = helpers::update_days(sess, 1);
+        let (total_weight, agents, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(total_weight, total_weight_before);
+        assert_eq!(agents[0].weight, 100);
+
+        // Any account can persist due batches
+        let sess = helpers::call_activate_weight_updates(sess, &ctx.registry, &ctx.charlie).unwrap();
+        let (total_weight, agents, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(total_weight, total_weight_before + 100);
+        assert_eq!(agents[0].weight, 200);
+        let (pending, _sess) = helpers::get_pending_weight_updates(sess, &ctx.registry).unwrap();
+        assert_eq!(pending.len(), 0);
+    }
+    #[test]
+    fn test_nominator_schedule_weight_updates_activated_by_stake() {
+        let mut ctx = setup(VALIDATOR_COUNT).unwrap();
+        let activate_at = ctx.sess.chain_api().get_timestamp() + helpers::DAY;
+
+        let (total_weight_before, _, sess) = helpers::get_agents(ctx.sess, &ctx.registry).unwrap();
+        let sess = helpers::call_schedule_weight_updates(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 100,
+                increase: true,
+            }],
+            activate_at,
+        ).unwrap();
+
+        // Vault persists due batches before delegating
+        let sess = helpers::update_days(sess, 1);
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 100e12 as u128).unwrap();
+        let (total_weight, agents, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(total_weight, total_weight_before + 100);
+        assert_eq!(agents[0].weight, 200);
+        let (pending, _sess) = helpers::get_pending_weight_updates(sess, &ctx.registry).unwrap();
+        assert_eq!(pending.len(), 0);
+    }
+    #[test]
+    fn test_nominator_schedule_weight_updates_skipped_because_concentration_limit_lowered() {
+        let mut ctx = setup(VALIDATOR_COUNT).unwrap();
+        let activate_at = ctx.sess.chain_api().get_timestamp() + helpers::DAY;
+
+        // 200 / 3100 is within the limit when scheduled
+        let sess = helpers::call_schedule_weight_updates(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 100,
+                increase: true,
+            }],
+            activate_at,
+        ).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            String::from("IRegistry::set_max_agent_share"),
+            Some(vec![String::from("500")]), // 5%
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+
+        // Exceeds the lowered limit once due, so it is skipped when persisted
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_activate_weight_updates(sess, &ctx.registry, &ctx.charlie).unwrap();
+        let (total_weight, agents, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(total_weight, 100 * VALIDATOR_COUNT as u128);
+        assert_eq!(agents[0].weight, 100);
+        let (pending, _sess) = helpers::get_pending_weight_updates(sess, &ctx.registry).unwrap();
+        assert_eq!(pending.len(), 0);
+    }
+    #[test]
+    fn test_nominator_cancel_weight_updates() {
+        let mut ctx = setup(VALIDATOR_COUNT).unwrap();
+        let activate_at = ctx.sess.chain_api().get_timestamp() + helpers::DAY;
+
+        let sess = helpers::call_schedule_weight_updates(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 100,
+                increase: false,
+            }],
+            activate_at,
+        ).unwrap();
+        let (pending, sess) = helpers::get_pending_weight_updates(sess, &ctx.registry).unwrap();
+        let sess = helpers::call_cancel_weight_updates(sess, &ctx.registry, &ctx.bob, pending[0].id).unwrap();
+
+        let sess = helpers::update_days(sess, 1);
+        let (_, agents, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(agents[0].weight, 100);
+    }
+    #[test]
+    fn test_nominator_schedule_weight_updates_panic_because_activation_in_past() {
+        let mut ctx = setup(VALIDATOR_COUNT).unwrap();
+        let activate_at = ctx.sess.chain_api().get_timestamp();
+
+        match helpers::call_schedule_weight_updates(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 100,
+                increase: true,
+            }],
+            activate_at,
+        ) {
+            Ok(_) => panic!("Should panic because the activation time is not in the future"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_schedule_weight_updates_panic_because_weight_underflow() {
+        let mut ctx = setup(VALIDATOR_COUNT).unwrap();
+        let activate_at = ctx.sess.chain_api().get_timestamp() + helpers::DAY;
+
+        match helpers::call_schedule_weight_updates(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 101,
+                increase: false,
+            }],
+            activate_at,
+        ) {
+            Ok(_) => panic!("Should panic because the decrease exceeds the agent weight"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_schedule_weight_updates_panic_because_caller_restricted() {
+        let mut ctx = setup(VALIDATOR_COUNT).unwrap();
+        let activate_at = ctx.sess.chain_api().get_timestamp() + helpers::DAY;
+
+        match helpers::call_schedule_weight_updates(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::UpdateAgents`
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 100,
+                increase: true,
+            }],
+            activate_at,
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_registry_history_records_weight_updates() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Setup sets the weight of every agent
+        let (count, sess) = helpers::get_history_count(ctx.sess, &ctx.registry).unwrap();
+        assert_eq!(count, VALIDATOR_COUNT as u32);
+
+        let (history, sess) = helpers::get_history(sess, &ctx.registry, count - 1, 10).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].actor, ctx.bob);
+        assert_eq!(history[0].action, helpers::HistoryAction::WeightUpdated {
+            agent: ctx.nominators[VALIDATOR_COUNT - 1].clone(),
+            old_weight: 0,
+            new_weight: 100,
+        });
+
+        let (history, _sess) = helpers::get_history(sess, &ctx.registry, count, 10).unwrap();
+        assert_eq!(history.len(), 0);
+    }
+    #[test]
+    fn test_registry_history_records_scheduler_of_activated_updates() {
+        let mut ctx = setup(VALIDATOR_COUNT).unwrap();
+        let activate_at = ctx.sess.chain_api().get_timestamp() + helpers::DAY;
+
+        let sess = helpers::call_schedule_weight_updates(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 100,
+                increase: true,
+            }],
+            activate_at,
+        ).unwrap();
+        let (pending, sess) = helpers::get_pending_weight_updates(sess, &ctx.registry).unwrap();
+        assert_eq!(pending[0].scheduler, ctx.bob);
+
+        // Another member's transaction activates the batch
+        let sess = helpers::grant_role(sess, &ctx.registry, &ctx.bob, &helpers::RoleType::UpdateAgents, &ctx.charlie).unwrap();
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_set_weights(
+            sess,
+            &ctx.registry,
+            &ctx.charlie,
+            vec![(ctx.nominators[1].clone(), 200)],
+            None,
+        ).unwrap();
+
+        let (count, sess) = helpers::get_history_count(sess, &ctx.registry).unwrap();
+        let (history, _sess) = helpers::get_history(sess, &ctx.registry, count - 2, 2).unwrap();
+        assert_eq!(history[0].actor, ctx.bob);
+        assert_eq!(history[0].action, helpers::HistoryAction::WeightUpdated {
+            agent: ctx.nominators[0].clone(),
+            old_weight: 100,
+            new_weight: 200,
+        });
+        assert_eq!(history[1].actor, ctx.charlie);
+        assert_eq!(history[1].action, helpers::HistoryAction::WeightUpdated {
+            agent: ctx.nominators[1].clone(),
+            old_weight: 100,
+            new_weight: 200,
+        });
+    }
+    #[test]
+    fn test_registry_history_records_role_changes() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::propose_role(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &helpers::RoleType::UpdateAgents,
+            &ctx.charlie,
+        ).unwrap();
+        let sess = helpers::accept_role(sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents).unwrap();
+
+        let (count, sess) = helpers::get_history_count(sess, &ctx.registry).unwrap();
+        let (history, _sess) = helpers::get_history(sess, &ctx.registry, count - 1, 1).unwrap();
+        assert_eq!(history[0].actor, ctx.charlie);
+        assert_eq!(history[0].action, helpers::HistoryAction::RoleAccountChanged {
+            role_type: helpers::RoleType::UpdateAgents,
+            new_account: ctx.charlie.clone(),
+        });
+    }
+    #[test]
+    fn test_nominator_update_panic_because_concentration_limit() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            String::from("IRegistry::set_max_agent_share"),
+            Some(vec![String::from("1000")]), // 10%
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+
+        // 1100 / 4000 exceeds 10%
+        match helpers::call_update_agents(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 1000,
+                increase: true,
+            }],
+        ) {
+            Ok(_) => panic!("Should panic because agent exceeds the maximum share"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_set_weights_within_concentration_limit() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            String::from("IRegistry::set_max_agent_share"),
+            Some(vec![String::from("1000")]), // 10%
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+
+        // 300 / 3200 is within 10%
+        let sess = helpers::call_set_weights(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![(ctx.nominators[0].clone(), 300)],
+            None,
+        ).unwrap();
+
+        let (_, agents, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(agents[0].weight, 300);
+    }
+    #[test]
+    fn test_nominator_set_max_agent_share_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_function(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::SetLimits`
+            String::from("IRegistry::set_max_agent_share"),
+            Some(vec![String::from("1000")]),
+            None,
+            helpers::transcoder_registry(),
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_stake_panic_because_vault_concentration_limit() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Agent #0 is assigned 300 / 3200 of the weight
+        let sess = helpers::call_set_weights(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![(ctx.nominators[0].clone(), 300)],
+            None,
+        ).unwrap();
+        // 30 agents at 3% cannot hold all of the pool
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_max_agent_share"),
+            Some(vec![String::from("300")]), // 3%
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+
+        match helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128) {
+            Ok(_) => panic!("Should panic because no agent has room below the maximum share of total pooled"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_stake_caps_agent_above_vault_concentration_limit() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Agent #0 is assigned 300 / 3200 of the weight
+        let sess = helpers::call_set_weights(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![(ctx.nominators[0].clone(), 300)],
+            None,
+        ).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_max_agent_share"),
+            Some(vec![String::from("500")]), // 5%
+            None,
+            helpers::transcoder_vault(),
+        ).unwrap();
+
+        // Deposit into agent #0 is capped and the excess goes to the other agents
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        let (staked, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert!(staked <= total_pooled * 5 / 100);
+
+        // Agent #0 stays capped on later stakes
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000e12 as u128).unwrap();
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        let (staked, _, _sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert!(staked <= total_pooled * 5 / 100);
+    }
+    #[test]
+    fn test_nominator_disable_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_disable_agent(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::DisableAgent`
+            &ctx.nominators[0],
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_disable_success() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+            ctx.sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        let agent_to_disable = agents_before[0].address.clone();
+
+        // Disable agent
+        let sess = helpers::call_disable_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob, // has `helpers::RoleType::DisableAgent`
+            &agent_to_disable,
+        )
+            .unwrap();
+
+        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len());
+        assert_eq!(total_weight_after, total_weight_before - agents_before[0].weight);
+        assert_eq!(agents_after[0].address, agents_before[0].address);
+        assert_eq!(agents_after[0].weight, 0);
+
+        // Attempt to add weight back
+        match helpers::call_update_agents(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: agent_to_disable.clone(),
+                weight: 100,
+                increase: true,
+            }],
+        ) {
+            Ok(_) => panic!("Should panic because agent is disabled"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_enable_success() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+            ctx.sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        let agent_to_enable = agents_before[0].address.clone();
+
+        let sess = helpers::call_disable_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob, // has `helpers::RoleType::DisableAgent`
+            &agent_to_enable,
+        )
+            .unwrap();
+
+        // Re-enable agent with a different weight
+        let sess = helpers::call_enable_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob, // has `helpers::RoleType::EnableAgent`
+            &agent_to_enable,
+            500,
+        )
+            .unwrap();
+
+        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len());
+        assert_eq!(total_weight_after, total_weight_before - agents_before[0].weight + 500);
+        assert_eq!(agents_after[0].address, agent_to_enable);
+        assert_eq!(agents_after[0].weight, 500);
+        assert_eq!(agents_after[0].disabled, false);
+
+        // Weight can be updated again
+        helpers::call_update_agents(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: agent_to_enable.clone(),
+                weight: 100,
+                increase: true,
+            }],
+        )
+            .unwrap();
+    }
+    #[test]
+    fn test_nominator_enable_panic_because_agent_active() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_enable_agent(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+            100,
+        ) {
+            Ok(_) => panic!("Should panic because agent is not disabled"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_enable_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_disable_agent(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+        )
+            .unwrap();
+
+        match helpers::call_enable_agent(
+            sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::EnableAgent`
+            &ctx.nominators[0],
+            100,
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_enable_panic_because_agent_removed() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_disable_agent(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+        )
+            .unwrap();
+        let sess = helpers::call_drain_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_remove_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+        )
+            .unwrap();
+
+        // Removed agents are being destroyed and cannot come back
+        match helpers::call_enable_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+            100,
+        ) {
+            Ok(_) => panic!("Should panic because agent was removed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_after_enable_success() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+            ctx.sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        let sess = helpers::call_disable_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+        )
+            .unwrap();
+        let sess = helpers::call_enable_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+            agents_before[0].weight,
+        )
+            .unwrap();
+
+        // Re-enabled agent must be drained again before removal
+        let mut sess = sess;
+        sess.set_actor(ctx.bob.clone());
+        assert!(sess.call_with_address(ctx.registry.clone(), "IRegistry::remove_agent", &[ctx.nominators[0].to_string()], None).is_err());
+        let output: Result<Result<(), helpers::RegistryError>, drink::errors::LangError> = helpers::last_call_output(&sess).unwrap();
+        assert_eq!(output.unwrap(), Err(helpers::RegistryError::AgentNotDraining));
+
+        let sess = helpers::call_disable_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_drain_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_remove_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+        )
+            .unwrap();
+
+        let (total_weight_after, agents_after, _sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len() - 1);
+        assert_eq!(total_weight_after, total_weight_before - agents_before[0].weight);
+        assert_eq!(agents_after[0].address, agents_before[1].address);
+    }
+    #[test]
+    fn test_nominator_update_validators_success() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (validators, sess) = helpers::get_agent_validators(ctx.sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(validators, vec![ctx.validators[0].clone()]);
+
+        // Stake 1k AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (staked_before, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+
+        let new_validators = vec![AccountId32::new([150u8; 32]), AccountId32::new([151u8; 32])];
+        let sess = helpers::call_update_agent_validators(
+            sess,
+            &ctx.registry,
+            &ctx.bob, // has `helpers::RoleType::UpdateAgents`
+            &ctx.nominators[0],
+            new_validators.clone(),
+            false,
+        )
+            .unwrap();
+
+        let (validators, sess) = helpers::get_agent_validators(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(validators, new_validators);
+
+        // Stake stays with the agent
+        let (staked_after, _, _sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked_after, staked_before);
+    }
+    #[test]
+    fn test_nominator_update_validators_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_update_agent_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::UpdateAgents`
+            &ctx.nominators[0],
+            vec![AccountId32::new([150u8; 32])],
+            false,
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_validators_panic_because_empty() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_update_agent_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+            vec![],
+            false,
+        ) {
+            Ok(_) => panic!("Should panic because no validators are given"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_validators_panic_because_duplicate_validator() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_update_agent_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+            vec![AccountId32::new([150u8; 32]), AccountId32::new([150u8; 32])],
+            false,
+        ) {
+            Ok(_) => panic!("Should panic because of duplicate validators"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_validators_panic_because_unchanged() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_update_agent_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+            vec![ctx.validators[0].clone()],
+            false,
+        ) {
+            Ok(_) => panic!("Should panic because validators are unchanged"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_panic_because_stake_is_non_zero() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Stake 1k AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let sess = helpers::call_disable_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_drain_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+
+        match helpers::call_remove_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob, // has `helpers::RoleType::RemoveAgent`
+            &ctx.nominators[0],
+        ) {
+            Ok(_) => panic!("Should panic because nominators[0] has stake"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_remove_agent(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::RemoveAgent`
+            &ctx.nominators[0],
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_panic_because_agent_active() {
+        let mut ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        ctx.sess.set_actor(ctx.bob.clone()); // has `helpers::RoleType::RemoveAgent`
+        assert!(ctx.sess.call_with_address(ctx.registry.clone(), "IRegistry::remove_agent", &[ctx.nominators[0].to_string()], None).is_err());
+        let output: Result<Result<(), helpers::RegistryError>, drink::errors::LangError> = helpers::last_call_output(&ctx.sess).unwrap();
+        assert_eq!(output.unwrap(), Err(helpers::RegistryError::AgentNotDraining));
+
+        // Disabled agents must also be drained first
+        let sess = helpers::call_disable_agent(ctx.sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        match helpers::call_remove_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]) {
+            Ok(_) => panic!("Should panic because nominators[0] is not draining"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_success() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+            ctx.sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        // Remove agent
+        let sess = helpers::call_disable_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_drain_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_remove_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob, // has `helpers::RoleType::RemoveAgent`
+            &ctx.nominators[0],
+        )
+            .unwrap();
+
+        let (total_weight_after, agents_after, _sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len() - 1);
+        assert_eq!(total_weight_after, total_weight_before - agents_before[1].weight);
+        assert_eq!(agents_after[0].address, agents_before[1].address);
+        assert_eq!(agents_after[0].weight, agents_before[1].weight);
+    }
+    #[test]
+    fn test_nominator_lifecycle_disable_and_enable() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (lifecycle, sess) = helpers::get_agent_lifecycle(ctx.sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        assert_eq!(lifecycle.unwrap().status, helpers::AgentStatus::Active);
+
+        let sess = helpers::call_disable_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let (lifecycle, sess) = helpers::get_agent_lifecycle(sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        assert_eq!(lifecycle.unwrap().status, helpers::AgentStatus::Disabled);
+
+        let sess = helpers::call_enable_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0], 100).unwrap();
+        let (lifecycle, _sess) = helpers::get_agent_lifecycle(sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        assert_eq!(lifecycle.unwrap().status, helpers::AgentStatus::Active);
+    }
+    #[test]
+    fn test_nominator_drain_disabled_agent_with_stake() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+
+        // Only disabled agents can be drained
+        let mut sess = sess;
+        sess.set_actor(ctx.bob.clone());
+        assert!(sess.call_with_address(ctx.registry.clone(), "IRegistry::drain_agent", &[ctx.nominators[0].to_string()], None).is_err());
+
+        let sess = helpers::call_disable_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+
+        // Removal fails instead of draining while the agent holds AZERO
+        let mut sess = sess;
+        sess.set_actor(ctx.bob.clone());
+        assert!(sess.call_with_address(ctx.registry.clone(), "IRegistry::remove_agent", &[ctx.nominators[0].to_string()], None).is_err());
+
+        let sess = helpers::call_drain_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+
+        let (lifecycle, sess) = helpers::get_agent_lifecycle(sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        let lifecycle = lifecycle.unwrap();
+        assert_eq!(lifecycle.status, helpers::AgentStatus::Draining);
+        assert_eq!(lifecycle.retired_at, 0);
+
+        // Agent remains registered until its stake is withdrawn
+        let (agent, mut sess) = helpers::get_agent(sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        assert!(agent.is_some());
+
+        sess.set_actor(ctx.bob.clone());
+        assert!(sess.call_with_address(ctx.registry.clone(), "IRegistry::remove_agent", &[ctx.nominators[0].to_string()], None).is_err());
+
+        match helpers::call_drain_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]) {
+            Ok(_) => panic!("Should panic because the agent is already draining"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_drain_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_disable_agent(ctx.sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+
+        match helpers::call_drain_agent(
+            sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::RemoveAgent`
+            &ctx.nominators[0],
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_removal_lifecycle_until_bond_reclaimed() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_disable_agent(ctx.sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_drain_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_remove_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+
+        let (retired, sess) = helpers::get_retired_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(retired.len(), 1);
+        assert_eq!(retired[0].0, ctx.nominators[0]);
+        assert_eq!(retired[0].1.status, helpers::AgentStatus::Destroying);
+        assert!(retired[0].1.retired_at > 0);
+
+        // Retired agents are no longer returned as registered agents
+        let (agent, sess) = helpers::get_agent(sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        assert!(agent.is_none());
+
+        let sess = helpers::call_admin_withdraw_bond(sess, &ctx.nominators[0], &ctx.bob, &ctx.bob).unwrap();
+        let sess = helpers::call_confirm_bond_reclaimed(sess, &ctx.registry, &ctx.alice, &ctx.nominators[0]).unwrap();
+
+        let (retired, _sess) = helpers::get_retired_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(retired[0].1.status, helpers::AgentStatus::Destroyed);
+    }
+    #[test]
+    fn test_nominator_confirm_bond_reclaimed_panic_because_bond_not_withdrawn() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_disable_agent(ctx.sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_drain_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_remove_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+
+        match helpers::call_confirm_bond_reclaimed(sess, &ctx.registry, &ctx.alice, &ctx.nominators[0]) {
+            Ok(_) => panic!("Should panic because the admin has not withdrawn the bond"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_confirm_bond_reclaimed_panic_because_agent_registered() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_confirm_bond_reclaimed(ctx.sess, &ctx.registry, &ctx.alice, &ctx.nominators[0]) {
+            Ok(_) => panic!("Should panic because the agent has not been removed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_get_agent_metadata() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (details, sess) = helpers::get_agent(ctx.sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        let (agent, metadata) = details.unwrap();
+        assert_eq!(agent.address, ctx.nominators[0]);
+        assert_eq!(metadata.label, String::new());
+        assert_eq!(metadata.validators, vec![ctx.validators[0].clone()]);
+        assert_eq!(metadata.creation_bond, 100e12 as u128);
+        assert_eq!(metadata.admin, ctx.bob);
+
+        let sess = helpers::call_function(
+            sess,
+            &ctx.registry,
+            &ctx.bob, // has `helpers::RoleType::AddAgent`
+            String::from("IRegistry::set_agent_label"),
+            Some(vec![ctx.nominators[0].to_string(), String::from("\"Agent 0\"")]),
+            None,
+            helpers::transcoder_registry(),
+        ).unwrap();
+
+        let (details, sess) = helpers::get_agent(sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        assert_eq!(details.unwrap().1.label, String::from("Agent 0"));
+
+        // Unknown agent
+        let (details, _sess) = helpers::get_agent(sess, &ctx.registry, &ctx.alice).unwrap();
+        assert!(details.is_none());
+    }
+    #[test]
+    fn test_nominator_set_agent_label_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_function(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::AddAgent`
+            String::from("IRegistry::set_agent_label"),
+            Some(vec![ctx.nominators[0].to_string(), String::from("\"Agent 0\"")]),
+            None,
+            helpers::transcoder_registry(),
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_get_agent_by_validator() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (details, sess) = helpers::get_agent_by_validator(ctx.sess, &ctx.registry, &ctx.validators[1]).unwrap();
+        assert_eq!(details.unwrap().0.address, ctx.nominators[1]);
+
+        // Follows validator updates
+        let new_validator = AccountId32::new([150u8; 32]);
+        let sess = helpers::call_update_agent_validators(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[1],
+            vec![new_validator.clone()],
+            false,
+        ).unwrap();
+
+        let (details, sess) = helpers::get_agent_by_validator(sess, &ctx.registry, &new_validator).unwrap();
+        assert_eq!(details.unwrap().0.address, ctx.nominators[1]);
+        let (details, _sess) = helpers::get_agent_by_validator(sess, &ctx.registry, &ctx.validators[1]).unwrap();
+        assert!(details.is_none());
+    }
+    #[test]
+    fn test_nominator_addition_panic_because_duplicate_validator() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_add_agent(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &ctx.validators[0], // nominated by `ctx.nominators[0]`
+            100e12 as u128,
+        ) {
+            Ok(_) => panic!("Should panic because validator is already nominated"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_addition_duplicate_validator_override() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (agent, sess) = helpers::call_add_agent_with_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &[ctx.validators[0].clone()],
+            true,
+            100e12 as u128,
+        ).unwrap();
+
+        let (details, sess) = helpers::get_agent(sess, &ctx.registry, &agent).unwrap();
+        assert_eq!(details.unwrap().1.validators, vec![ctx.validators[0].clone()]);
+
+        // Lookup returns the agent which nominated the validator first
+        let (details, _sess) = helpers::get_agent_by_validator(sess, &ctx.registry, &ctx.validators[0]).unwrap();
+        assert_eq!(details.unwrap().0.address, ctx.nominators[0]);
+    }
+    #[test]
+    fn test_nominator_update_validators_panic_because_duplicate_across_agents() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        match helpers::call_update_agent_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+            vec![ctx.validators[1].clone()], // nominated by `ctx.nominators[1]`
+            false,
+        ) {
+            Ok(_) => panic!("Should panic because validator is already nominated"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_releases_validator() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let sess = helpers::call_disable_agent(ctx.sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_drain_agent(sess, &ctx.registry, &ctx.bob, &ctx.nominators[0]).unwrap();
+        let sess = helpers::call_remove_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+        ).unwrap();
+
+        let (details, sess) = helpers::get_agent_by_validator(sess, &ctx.registry, &ctx.validators[0]).unwrap();
+        assert!(details.is_none());
+
+        // Validator can be nominated again without the override
+        let (agent, sess) = helpers::call_add_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &ctx.validators[0],
+            100e12 as u128,
+        ).unwrap();
+
+        let (details, _sess) = helpers::get_agent_by_validator(sess, &ctx.registry, &ctx.validators[0]).unwrap();
+        assert_eq!(details.unwrap().0.address, agent);
+    }
+    #[test]
+    fn test_nominator_get_agents_paginated() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        let (_, agents, sess) = helpers::get_agents(ctx.sess, &ctx.registry).unwrap();
+
+        let (page, sess) = helpers::get_agents_paginated(sess, &ctx.registry, 1, 1).unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].address, agents[1].address);
+
+        // Page past the end is truncated
+        let (page, _sess) = helpers::get_agents_paginated(sess, &ctx.registry, 1, 100).unwrap();
+        assert_eq!(page.len(), agents.len() - 1);
+    }
+    #[test]
+    fn test_nominator_get_agents_overview() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Stake 1k AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+
+        let (overview, mut sess) = helpers::get_agents_overview(sess, &ctx.registry).unwrap();
+        assert_eq!(overview.len(), ctx.nominators.len());
+        for (i, agent) in overview.iter().enumerate() {
+            let (staked, unbonding);
+            (staked, unbonding, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[i]).unwrap();
+            assert_eq!(agent.address, ctx.nominators[i]);
+            assert_eq!(agent.staked, staked);
+            assert_eq!(agent.unbonding, unbonding);
+        }
+    }
+    #[test]
+    fn test_nominator_addition_equal_weights() -> Result<(), Box<dyn Error>> {
+        let ctx = setup(2 as usize).unwrap();
+
+        // Stake 10 million AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000e12 as u128).unwrap();
+
+        let (stake1, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        let (stake2, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(stake1, 5_000_000e12 as u128); // 50% to agent0
+        assert_eq!(stake2, 5_000_000e12 as u128); // 50% to agent1
+
+        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        // Add nomination agent
+        let (_new_agent, sess) = helpers::call_add_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &AccountId32::new([200u8; 32]),
+            100e12 as u128,
+        )?;
+
+        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len() + 1);
+        assert_eq!(total_weight_after, total_weight_before);
+        assert_eq!(agents_after[2].weight, 0);
+
+        // Update weight from 0 to 100
+        let sess = helpers::call_update_agents(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: agents_after[2].address.clone(),
+                weight: 100,
+                increase: true,
+            }],
+        )
+            .unwrap();
+
+        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len() + 1);
+        assert_eq!(total_weight_after, total_weight_before + 100);
+        assert_eq!(agents_after[2].weight, 100);
+
+        // Stake additional 10 million AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000e12 as u128).unwrap();
+
+        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
         let (stake3, _, _sess) = helpers::query_nominator_balance(sess, &agents_after[2].address).unwrap();
         assert_eq!(stake1, 6_666_666_666666666668);
         assert_eq!(stake2, 6_666_666_666666666666);
@@ -1099,353 +3588,1302 @@ mod tests {
         Ok(())
     }
     #[test]
-    fn test_nominator_addition_unequal_weights() -> Result<(), Box<dyn Error>> {
+    fn test_nominator_addition_unequal_weights() -> Result<(), Box<dyn Error>> {
+        let ctx = setup(2 as usize).unwrap();
+
+        // Stake 10m AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000e12 as u128).unwrap();
+
+        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(stake1, 5_000_000e12 as u128); // 50% to agent0
+        assert_eq!(stake2, 5_000_000e12 as u128); // 50% to agent1
+
+        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        // Add nomination agent
+        let (_new_agent, sess) = helpers::call_add_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &AccountId32::new([200u8; 32]),
+            100e12 as u128,
+        )?;
+
+        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len() + 1);
+        assert_eq!(total_weight_after, total_weight_before);
+        assert_eq!(agents_after[2].weight, 0);
+
+        // Update weight from 0 to 50
+        let sess = helpers::call_update_agents(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: agents_after[2].address.clone(),
+                weight: 50,
+                increase: true,
+            }],
+        )
+            .unwrap();
+
+        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(total_weight_after, total_weight_before + 50);
+        assert_eq!(agents_after[2].weight, 50);
+
+        // Stake another 10m AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000e12 as u128).unwrap();
+
+        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        let (stake3, _, _sess) = helpers::query_nominator_balance(sess, &agents_after[2].address).unwrap();
+        assert_eq!(stake1, 8_000_000e12 as u128);
+        assert_eq!(stake2, 8_000_000e12 as u128);
+        assert_eq!(stake3, 4_000_000e12 as u128);
+
+        Ok(())
+    }
+    #[test]
+    fn test_gas_cost_of_nominator_addition_flow_with_many_agents() -> Result<(), Box<dyn Error>> {
+        let ctx = setup(VALIDATOR_COUNT - 1).unwrap();
+
+        // Stake 10 million AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000e12 as u128).unwrap();
+
+        let (total_weight_before, agents_before, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+
+        // Add nomination agent
+        let (_new_agent, sess) = helpers::call_add_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &AccountId32::new([200u8; 32]),
+            100e12 as u128,
+        )?;
+
+        let (total_weight_after, agents_after, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len() + 1);
+        assert_eq!(total_weight_after, total_weight_before);
+        assert_eq!(agents_after[agents_after.len() - 1].weight, 0);
+
+        // Update weight from 0 to 100
+        let sess = helpers::call_update_agents(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: agents_after[agents_after.len() - 1].address.clone(),
+                weight: 100,
+                increase: true,
+            }],
+        )
+            .unwrap();
+
+        let (total_weight_after, agents_after, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len() + 1);
+        assert_eq!(total_weight_after, total_weight_before + 100);
+        assert_eq!(agents_after[agents_after.len() - 1].weight, 100);
+
+        Ok(())
+    }
+    #[test]
+    fn test_unlock_weight_change() -> Result<(), Box<dyn Error>> {
+        let ctx = setup(2 as usize).unwrap();
+        let sess = ctx.sess;
+
+        // Stake 5 million AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1_000_000e12 as u128).unwrap();
+
+        let sess = helpers::update_days(sess, 2);
+
+        // Update agent #1 weight from 100/200 to 50/150
+        // Change weight -50 (from 100 to 50)
+        let sess = helpers::call_update_agents(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 50,
+                increase: false,
+            }],
+        )
+            .unwrap();
+
+        // Request unlocks of all 5 million sA0
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1_000_000e12 as u128).unwrap();
+
+        // Fees accumulated for 5m AZERO staked for 2 days
+        let expected_fees = 5_000_000e12 as u128 * 2 * helpers::DAY as u128 / helpers::YEAR as u128 * 200 / helpers::BIPS;
+        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(claimable_fees, expected_fees);
+
+        let (expected_azero, sess) = helpers::get_azero_from_shares(sess, &ctx.vault, expected_fees).unwrap();
+
+        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        let (stake2, _, _sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(stake1, expected_azero * 50 / 150); // agent 0 fees
+        assert_eq!(stake2, expected_azero * 100 / 150 + 1); // agent 1 fees
+
+        Ok(())
+    }
+    #[test]
+    fn test_withdraw_all() -> Result<(), Box<dyn Error>> {
         let ctx = setup(2 as usize).unwrap();
+        let sess = ctx.sess;
 
-        // Stake 10m AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000e12 as u128).unwrap();
+        // Stake 5 million AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1_000_000e12 as u128).unwrap();
 
-        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        assert_eq!(stake1, 5_000_000e12 as u128); // 50% to agent0
-        assert_eq!(stake2, 5_000_000e12 as u128); // 50% to agent1
+        // Allow fees to accumulate
+        let sess = helpers::update_days(sess, 2);
 
-        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+        // Update agent weight from 100/200 to 50/150
+        // Change weight -50 (from 100 to 50)
+        let sess = helpers::call_update_agents(
             sess,
             &ctx.registry,
+            &ctx.bob,
+            vec![helpers::WeightUpdate {
+                agent: ctx.nominators[0].clone(),
+                weight: 50,
+                increase: false,
+            }],
         )
             .unwrap();
 
-        // Add nomination agent
-        let (_new_agent, sess) = helpers::call_add_agent(
+        // Request unlocking of 5 million AZERO
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1_000_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1_000_000e12 as u128).unwrap();
+
+        // Wait for cooldown period
+        let sess = helpers::update_days(sess, 14);
+
+        // Fees accumulated for 5m AZERO staked for 2 days
+        let expected_fees_a = 5_000_000e12 as u128 * 2 * helpers::DAY as u128 / helpers::YEAR as u128 * 200 / helpers::BIPS;
+        let expected_fees_b = expected_fees_a * 14 * helpers::DAY as u128 / helpers::YEAR as u128 * 200 / helpers::BIPS;
+        let expected_fees = expected_fees_a + expected_fees_b;
+
+        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(claimable_fees, expected_fees);
+
+        let (expected_azero, sess) = helpers::get_azero_from_shares(sess, &ctx.vault, expected_fees).unwrap();
+
+        // Verify all AZERO is withdrawn except fees
+        let (stake, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(stake, expected_azero * 50 / 150); // agent 0 weight
+        let (stake, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(stake, expected_azero * 100 / 150 + 1); // agent 1 weight
+
+        let fee_split = expected_azero / 5;
+        let (redeemed, sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        assert_eq!(redeemed, 1_000_000e12 as u128 + 32 - fee_split - 10);
+        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.bob, 0).unwrap();
+        assert_eq!(redeemed, 1_000_000e12 as u128 + 32 - fee_split - 8);
+        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.charlie, 0).unwrap();
+        assert_eq!(redeemed, 1_000_000e12 as u128 + 32 - fee_split - 8);
+        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.dave, 0).unwrap();
+        assert_eq!(redeemed, 1_000_000e12 as u128 + 32 - fee_split - 8);
+        let (redeemed, mut sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.ed, 0).unwrap();
+        assert_eq!(redeemed, 1_000_000e12 as u128 + 32 - fee_split - 8);
+
+        let vault_balance = sess.chain_api().balance(&ctx.vault);
+        assert_eq!(vault_balance, 1, "Vault should only have dust remaining");
+
+        Ok(())
+    }
+    #[test]
+    fn test_token_transfer_from_panics_properly() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Bob stakes 1m AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
+
+        // Ed attempts to transfer 1k of Bob's sA0
+        match helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.share_token,
+            &ctx.ed, // not bob
+            String::from("PSP22::transfer_from"),
+            Some(vec![ctx.bob.to_string(), ctx.ed.to_string(), 1_000e12.to_string(), "[]".to_string()]),
+            None,
+            helpers::transcoder_share_token(),
+        )  {
+            Ok(_) => panic!("Should panic because Bob has not approved Ed to transfer sA0"),
+            Err(res) => println!("{:?}", res.to_string()),
+        };
+    }
+    #[test]
+    fn test_token_transfer_from_works_normally() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Bob stakes 1m AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
+
+        // Bob approves Ed to transfer 1k sA0
+        let sess = helpers::call_function(
+            sess,
+            &ctx.share_token,
+            &ctx.bob,
+            String::from("PSP22::approve"),
+            Some(vec![ctx.ed.to_string(), 1_000e12.to_string()]),
+            None,
+            helpers::transcoder_share_token(),
+        ).unwrap();
+
+        // Ed transfers 1k of Bob's sA0
+        helpers::call_function(
+            sess,
+            &ctx.share_token,
+            &ctx.ed, // previously approved
+            String::from("PSP22::transfer_from"),
+            Some(vec![ctx.bob.to_string(), ctx.ed.to_string(), 1_000e12.to_string(), "[]".to_string()]),
+            None,
+            helpers::transcoder_share_token(),
+        ).unwrap();
+    }
+    #[test]
+    fn test_compound_call() -> Result<(), Box<dyn Error>> {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+
+        // Stake 1 AZERO
+        let deposit_amount = 1e12 as u128;
+        let (shares, mut sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
+        assert_eq!(shares, deposit_amount); // 1:1 ratio
+
+        // Fund nominator agents to simulate AZERO being claimed
+        let mock_reward = 10_000;
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
+        sess.chain_api().add_tokens(ctx.nominators[1].clone(), mock_reward);
+
+        // Compound
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
             &ctx.bob,
+            String::from("IVault::compound"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        // Rewards have not vested yet
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, deposit_amount);
+        let (unvested, sess) = helpers::get_unvested_rewards(sess, &ctx.vault).unwrap();
+        assert_eq!(unvested, mock_reward * 2);
+
+        // Rewards fully vest after one era
+        let sess = helpers::update_days(sess, 1);
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, deposit_amount + mock_reward * 2);
+        let (unvested, _sess) = helpers::get_unvested_rewards(sess, &ctx.vault).unwrap();
+        assert_eq!(unvested, 0);
+
+        Ok(())
+    }
+    #[test]
+    fn test_compound_rewards_vest_linearly() -> Result<(), Box<dyn Error>> {
+        let ctx = setup(2 as usize).unwrap();
+
+        // Stake 1k AZERO
+        let deposit_amount = 1_000e12 as u128;
+        let (_, mut sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
+
+        // Fund nominator agents to simulate AZERO being claimed
+        let mock_reward = 100e12 as u128;
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
+
+        let sess = helpers::call_compound(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        // Half of the rewards vest after half an era
+        let sess = helpers::update_in_milliseconds(sess, helpers::DAY / 2);
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, deposit_amount + mock_reward / 2);
+        let (unvested, mut sess) = helpers::get_unvested_rewards(sess, &ctx.vault).unwrap();
+        assert_eq!(unvested, mock_reward / 2);
+
+        // Compounding again locks the remainder alongside the new rewards
+        sess.chain_api().add_tokens(ctx.nominators[1].clone(), mock_reward);
+        let sess = helpers::call_compound(sess, &ctx.vault, &ctx.bob).unwrap();
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, deposit_amount + mock_reward / 2);
+        let (unvested, sess) = helpers::get_unvested_rewards(sess, &ctx.vault).unwrap();
+        assert_eq!(unvested, mock_reward + mock_reward / 2);
+
+        let sess = helpers::update_days(sess, 1);
+        let (total_pooled, _sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, deposit_amount + mock_reward * 2);
+
+        Ok(())
+    }
+    #[test]
+    fn test_compound_sandwich_earns_no_rewards() -> Result<(), Box<dyn Error>> {
+        let ctx = setup(2 as usize).unwrap();
+
+        // Bob stakes 1k AZERO long before rewards are compounded
+        let deposit_amount = 1_000e12 as u128;
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
+
+        // Fund nominator agents to simulate AZERO being claimed
+        let mut sess = helpers::update_days(sess, 1);
+        let mock_reward = 100e12 as u128;
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
+
+        // Alice stakes right before the compound
+        let (alice_shares, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, deposit_amount).unwrap();
+        let (alice_azero_before, sess) = helpers::get_azero_from_shares(sess, &ctx.vault, alice_shares).unwrap();
+
+        let sess = helpers::call_compound(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        // Alice's shares are worth the same immediately after the compound
+        let (alice_azero_after, _sess) = helpers::get_azero_from_shares(sess, &ctx.vault, alice_shares).unwrap();
+        assert_eq!(alice_azero_after, alice_azero_before);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_performance_fee_minted_on_compound() -> Result<(), Box<dyn Error>> {
+        let ctx = setup(2 as usize).unwrap();
+
+        // Switch to the performance fee model
+        let sess = helpers::call_propose_fee_model(ctx.sess, &ctx.vault, &ctx.bob, "Performance").unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_execute_fee_model(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        // Alice stakes 1k AZERO
+        let deposit_amount = 1_000e12 as u128;
+        let (_, mut sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, deposit_amount).unwrap();
+
+        // Fund nominator agents to simulate AZERO being claimed
+        let mock_reward = 100e12 as u128;
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
+
+        let (shares_before, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        let sess = helpers::call_compound(sess, &ctx.vault, &ctx.alice).unwrap();
+        let (shares_after, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+
+        // Default performance fee of 10% priced at the 1:1 ratio
+        let expected_fee_shares = mock_reward * 10_00 / helpers::BIPS;
+        assert_eq!(shares_after - shares_before, expected_fee_shares);
+
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
             &ctx.bob,
-            &AccountId32::new([101u8; 32]),
-            100e12 as u128,
-        )?;
+            String::from("IVault::get_total_performance_fee_shares"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let res: Result<u128, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(res.unwrap(), expected_fee_shares);
 
-        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+        // No management fees accrue under the performance fee model
+        let sess = helpers::update_days(sess, 365);
+        let (virtual_shares, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(virtual_shares, 0);
+
+        // Remaining rewards have vested to the existing stakers
+        let (total_pooled, _sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, deposit_amount + mock_reward);
+
+        Ok(())
+    }
+    #[test]
+    fn test_propose_fee_model_panic_because_caller_restricted() {
+        let ctx = setup(2 as usize).unwrap();
+        match helpers::call_propose_fee_model(ctx.sess, &ctx.vault, &ctx.ed, "Performance") { // not bob
+            Ok(_) => panic!("Should panic because caller does not have adjust fees role (Bob)"),
+            Err(_) => (),
+        }
+    }
+    #[test]
+    fn test_execute_fee_model_panic_because_timelocked() {
+        let ctx = setup(2 as usize).unwrap();
+
+        let sess = helpers::call_propose_fee_model(ctx.sess, &ctx.vault, &ctx.bob, "Performance").unwrap();
+        let sess = helpers::update_days(sess, 6);
+
+        match helpers::call_execute_fee_model(sess, &ctx.vault, &ctx.bob) {
+            Ok(_) => panic!("Should panic because the fee model proposal delay has not elapsed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_cancel_fee_model_proposal_flow() {
+        let ctx = setup(2 as usize).unwrap();
+
+        let sess = helpers::call_propose_fee_model(ctx.sess, &ctx.vault, &ctx.bob, "Performance").unwrap();
+        let sess = helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::cancel_fee_model_proposal"),
+            None,
+            None,
+            helpers::transcoder_vault(),
         )
             .unwrap();
 
-        assert_eq!(agents_after.len(), agents_before.len() + 1);
-        assert_eq!(total_weight_after, total_weight_before);
-        assert_eq!(agents_after[2].weight, 0);
+        let sess = helpers::update_days(sess, 7);
+        match helpers::call_execute_fee_model(sess, &ctx.vault, &ctx.bob) {
+            Ok(_) => panic!("Should panic because the fee model proposal was cancelled"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_performance_fee_adjustment_success() {
+        let ctx = setup(2 as usize).unwrap();
 
-        // Update weight from 0 to 50
-        let sess = helpers::call_update_agents(
-            sess,
-            &ctx.registry,
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
             &ctx.bob,
-            vec![helpers::WeightUpdate {
-                agent: agents_after[2].address.clone(),
-                weight: 50,
-                increase: true,
-            }],
+            String::from("IVault::adjust_performance_fee"),
+            Some(vec![String::from("500")]), // lowered from 10%
+            None,
+            helpers::transcoder_vault(),
         )
             .unwrap();
+        let (fee, _sess) = helpers::get_performance_fee_percentage(sess, &ctx.vault).unwrap();
+        assert_eq!(fee, 500);
+    }
+    #[test]
+    fn test_performance_fee_adjustment_panic_because_increase_is_timelocked() {
+        let ctx = setup(2 as usize).unwrap();
 
-        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_performance_fee"),
+            Some(vec![String::from("500")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        match helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_performance_fee"),
+            Some(vec![String::from("600")]),
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because performance fee increases must be proposed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_performance_fee_proposal_flow() {
+        let ctx = setup(2 as usize).unwrap();
+
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_performance_fee"),
+            Some(vec![String::from("500")]),
+            None,
+            helpers::transcoder_vault(),
         )
             .unwrap();
+        let sess = helpers::call_propose_performance_fee(sess, &ctx.vault, &ctx.bob, 700).unwrap();
 
-        assert_eq!(total_weight_after, total_weight_before + 50);
-        assert_eq!(agents_after[2].weight, 50);
+        let sess = helpers::update_days(sess, 6);
+        let (fee, sess) = helpers::get_performance_fee_percentage(sess, &ctx.vault).unwrap();
+        assert_eq!(fee, 500);
 
-        // Stake another 10m AZERO
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000e12 as u128).unwrap();
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_execute_performance_fee(sess, &ctx.vault, &ctx.bob).unwrap();
+        let (fee, _sess) = helpers::get_performance_fee_percentage(sess, &ctx.vault).unwrap();
+        assert_eq!(fee, 700);
+    }
+    #[test]
+    fn test_propose_performance_fee_panic_because_above_hard_cap() {
+        let ctx = setup(2 as usize).unwrap();
 
-        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        let (stake3, _, _sess) = helpers::query_nominator_balance(sess, &agents_after[2].address).unwrap();
-        assert_eq!(stake1, 8_000_000e12 as u128);
-        assert_eq!(stake2, 8_000_000e12 as u128);
-        assert_eq!(stake3, 4_000_000e12 as u128);
+        // Default performance fee of 10% is already at the hard cap
+        match helpers::call_propose_performance_fee(ctx.sess, &ctx.vault, &ctx.bob, 1100) {
+            Ok(_) => panic!("Should panic because the performance fee exceeds the hard cap"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_reward_vesting_period_proposal_flow() {
+        let ctx = setup(2 as usize).unwrap();
 
-        Ok(())
+        let (period_before, sess) = helpers::get_reward_vesting_period(ctx.sess, &ctx.vault).unwrap();
+        let sess = helpers::call_propose_reward_vesting_period(sess, &ctx.vault, &ctx.bob, 2 * helpers::DAY).unwrap();
+
+        // Not applied before the delay elapses
+        let sess = helpers::update_days(sess, 6);
+        let (period, sess) = helpers::get_reward_vesting_period(sess, &ctx.vault).unwrap();
+        assert_eq!(period, period_before);
+
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_execute_reward_vesting_period(sess, &ctx.vault, &ctx.bob).unwrap();
+        let (period, _sess) = helpers::get_reward_vesting_period(sess, &ctx.vault).unwrap();
+        assert_eq!(period, 2 * helpers::DAY);
     }
     #[test]
-    fn test_gas_cost_of_nominator_addition_flow_with_many_agents() -> Result<(), Box<dyn Error>> {
-        let ctx = setup(VALIDATOR_COUNT - 1).unwrap();
+    fn test_execute_reward_vesting_period_panic_because_timelocked() {
+        let ctx = setup(2 as usize).unwrap();
 
-        // Stake 10 million AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000e12 as u128).unwrap();
+        let sess = helpers::call_propose_reward_vesting_period(ctx.sess, &ctx.vault, &ctx.bob, 2 * helpers::DAY).unwrap();
+        let sess = helpers::update_days(sess, 6);
 
-        let (total_weight_before, agents_before, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        match helpers::call_execute_reward_vesting_period(sess, &ctx.vault, &ctx.bob) {
+            Ok(_) => panic!("Should panic because the vesting period proposal delay has not elapsed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_propose_reward_vesting_period_panic_because_below_minimum() {
+        let mut ctx = setup(2 as usize).unwrap();
 
-        // Add nomination agent
-        let (_new_agent, sess) = helpers::call_add_agent(
-            sess,
-            &ctx.registry,
-            &ctx.bob,
-            &ctx.bob,
-            &AccountId32::new([101u8; 32]),
-            100e12 as u128,
-        )?;
+        ctx.sess.set_actor(ctx.bob.clone());
+        assert!(ctx.sess.call_with_address(ctx.vault.clone(), "IVault::propose_reward_vesting_period", &["0".to_string()], None).is_err());
+        let output: Result<Result<(), helpers::VaultError>, drink::errors::LangError> = helpers::last_call_output(&ctx.sess).unwrap();
+        assert_eq!(output.unwrap(), Err(helpers::VaultError::InvalidPeriod));
+    }
+    #[test]
+    fn test_propose_reward_vesting_period_panic_because_caller_restricted() {
+        let ctx = setup(2 as usize).unwrap();
 
-        let (total_weight_after, agents_after, sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        match helpers::call_propose_reward_vesting_period(ctx.sess, &ctx.vault, &ctx.ed, 2 * helpers::DAY) { // not bob
+            Ok(_) => panic!("Should panic because caller does not have adjust fees role (Bob)"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_compound_before_initial_stake() -> Result<(), Box<dyn Error>> {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let mut sess = ctx.sess;
 
-        assert_eq!(agents_after.len(), agents_before.len() + 1);
-        assert_eq!(total_weight_after, total_weight_before);
-        assert_eq!(agents_after[agents_after.len() - 1].weight, 0);
+        // Send funds to an agent
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), 1000);
 
-        // Update weight from 0 to 100
-        let sess = helpers::call_update_agents(
+        // Compound
+        let sess = helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.vault,
             &ctx.bob,
-            vec![helpers::WeightUpdate {
-                agent: agents_after[agents_after.len() - 1].address.clone(),
-                weight: 100,
-                increase: true,
-            }],
+            String::from("IVault::compound"),
+            None,
+            None,
+            helpers::transcoder_vault(),
         )
             .unwrap();
 
-        let (total_weight_after, agents_after, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        // Compounded AZERO is not yet vested
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, 0);
+        let (unvested, sess) = helpers::get_unvested_rewards(sess, &ctx.vault).unwrap();
+        assert_eq!(unvested, 1000);
 
-        assert_eq!(agents_after.len(), agents_before.len() + 1);
-        assert_eq!(total_weight_after, total_weight_before + 100);
-        assert_eq!(agents_after[agents_after.len() - 1].weight, 100);
+        let deposit_amount = 1e12 as u128;
+        let (shares, _sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
+        assert_eq!(shares, deposit_amount); // 1:1 ratio
 
         Ok(())
     }
-    #[test]
-    fn test_unlock_weight_change() -> Result<(), Box<dyn Error>> {
-        let ctx = setup(2 as usize).unwrap();
-        let sess = ctx.sess;
+    /// Deploys a governor with bob as proposer and executor, alice as guardian,
+    /// and hands it every vault and registry role along with their admin
+    fn setup_governor(ctx: TestContext) -> Result<(AccountId32, TestContext), Box<dyn Error>> {
+        let mut ctx = ctx;
+        let governor = ctx.sess.deploy(
+            helpers::bytes_governor(),
+            "new",
+            &[
+                ctx.vault.to_string(),
+                ctx.registry.to_string(),
+                format!("[{}]", ctx.bob),
+                format!("[{}]", ctx.bob),
+                ctx.alice.to_string(),
+                helpers::DAY.to_string(),
+            ],
+            vec![1],
+            None,
+            &helpers::transcoder_governor().unwrap(),
+        )?;
+        ctx.sess.set_transcoder(governor.clone(), &helpers::transcoder_governor().unwrap());
 
-        // Stake 5 million AZERO
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1_000_000e12 as u128).unwrap();
+        let vault_roles = ["AdjustFee", "FeeTo", "SetCode", "Pauser", "Keeper", "LimitsAdmin", "Guardian"];
+        let registry_roles = ["AddAgent", "UpdateAgents", "DisableAgent", "RemoveAgent", "SetCodeHash", "SetLimits", "EnableAgent"];
 
-        let sess = helpers::update_days(sess, 2);
+        // Current holders propose the governor for every role and role admin
+        let mut sess = ctx.sess;
+        let mut calls: Vec<(AccountId32, String)> = Vec::new();
+        for role in vault_roles {
+            for (propose, accept) in [
+                ("IVault::propose_role", "IVault::accept_role"),
+                ("IVault::propose_role_admin", "IVault::accept_role_admin"),
+            ] {
+                sess = helpers::call_function(
+                    sess,
+                    &ctx.vault,
+                    &ctx.bob,
+                    String::from(propose),
+                    Some([role.to_string(), governor.to_string()].to_vec()),
+                    None,
+                    helpers::transcoder_vault(),
+                )?;
+                calls.push((
+                    ctx.vault.clone(),
+                    helpers::encode_call(helpers::transcoder_vault(), accept, &[role.to_string()]),
+                ));
+            }
+        }
+        for role in registry_roles {
+            for (propose, accept) in [
+                ("IRegistry::propose_role", "IRegistry::accept_role"),
+                ("IRegistry::propose_role_admin", "IRegistry::accept_role_admin"),
+            ] {
+                sess = helpers::call_function(
+                    sess,
+                    &ctx.registry,
+                    &ctx.bob,
+                    String::from(propose),
+                    Some([role.to_string(), governor.to_string()].to_vec()),
+                    None,
+                    helpers::transcoder_registry(),
+                )?;
+                calls.push((
+                    ctx.registry.clone(),
+                    helpers::encode_call(helpers::transcoder_registry(), accept, &[role.to_string()]),
+                ));
+            }
+        }
 
-        // Update agent #1 weight from 100/200 to 50/150
-        // Change weight -50 (from 100 to 50)
-        let sess = helpers::call_update_agents(
+        // Governor accepts through the timelock
+        let mut ids = Vec::new();
+        for (target, call_data) in calls {
+            let id;
+            (id, sess) = helpers::call_governor_schedule(sess, &governor, &ctx.bob, &target, call_data)?;
+            ids.push(id);
+        }
+        sess = helpers::update_days(sess, 1);
+        for id in ids {
+            sess = helpers::call_governor_execute(sess, &governor, &ctx.bob, id)?;
+        }
+
+        ctx.sess = sess;
+        Ok((governor, ctx))
+    }
+    #[test]
+    fn test_governor_holds_every_role() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (governor, ctx) = setup_governor(ctx).unwrap();
+
+        let mut sess = ctx.sess;
+        for role_type in [
+            helpers::VaultRoleType::AdjustFee,
+            helpers::VaultRoleType::FeeTo,
+            helpers::VaultRoleType::SetCode,
+            helpers::VaultRoleType::Pauser,
+            helpers::VaultRoleType::Keeper,
+            helpers::VaultRoleType::LimitsAdmin,
+            helpers::VaultRoleType::Guardian,
+        ] {
+            let role;
+            (role, sess) = helpers::get_vault_role(sess, &ctx.vault, &role_type).unwrap();
+            assert_eq!(role, Some(governor.clone()));
+        }
+        for role_type in [
+            helpers::RoleType::AddAgent,
+            helpers::RoleType::UpdateAgents,
+            helpers::RoleType::DisableAgent,
+            helpers::RoleType::RemoveAgent,
+            helpers::RoleType::SetCodeHash,
+            helpers::RoleType::SetLimits,
+            helpers::RoleType::EnableAgent,
+        ] {
+            let (role, admin);
+            (role, sess) = helpers::get_role(sess, &ctx.registry, &role_type).unwrap();
+            assert_eq!(role, governor);
+            (admin, sess) = helpers::get_role_admin(sess, &ctx.registry, &role_type).unwrap();
+            assert_eq!(admin, governor);
+        }
+
+        // Previous holder can no longer act directly
+        match helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_fee"),
+            Some(vec![String::from("100")]),
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_governor_fee_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (governor, ctx) = setup_governor(ctx).unwrap();
+
+        let (id, sess) = helpers::call_governor_schedule_propose_fee(ctx.sess, &governor, &ctx.bob, 300).unwrap(); // raised from 2%
+        let (operation, sess) = helpers::get_operation(sess, &governor, id).unwrap();
+        assert_eq!(operation.unwrap().target, ctx.vault);
+
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_governor_execute(sess, &governor, &ctx.bob, id).unwrap();
+
+        let (id, sess) = helpers::call_governor_schedule_execute_fee(sess, &governor, &ctx.bob).unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_governor_execute(sess, &governor, &ctx.bob, id).unwrap();
+
+        let (fee, _sess) = helpers::get_fee_percentage(sess, &ctx.vault).unwrap();
+        assert_eq!(fee, 300);
+    }
+    #[test]
+    fn test_governor_execute_keeps_operation_when_call_reverts() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (governor, ctx) = setup_governor(ctx).unwrap();
+
+        let (id, sess) = helpers::call_governor_schedule_propose_fee(ctx.sess, &governor, &ctx.bob, 300).unwrap();
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_governor_execute(sess, &governor, &ctx.bob, id).unwrap();
+
+        // Vault rejects the call with FeeTimelocked until 7 days after the proposal
+        let (id, sess) = helpers::call_governor_schedule_execute_fee(sess, &governor, &ctx.bob).unwrap();
+        let mut sess = helpers::update_days(sess, 1);
+        sess.set_actor(ctx.bob.clone());
+        assert!(sess.call_with_address(governor.clone(), "IGovernor::execute", &[id.to_string()], None).is_err());
+
+        let (operation, sess) = helpers::get_operation(sess, &governor, id).unwrap();
+        assert!(operation.is_some());
+        let (fee, sess) = helpers::get_fee_percentage(sess, &ctx.vault).unwrap();
+        assert_eq!(fee, 200);
+
+        // Same operation succeeds once the vault delay has elapsed
+        let sess = helpers::update_days(sess, 6);
+        let sess = helpers::call_governor_execute(sess, &governor, &ctx.bob, id).unwrap();
+        let (operation, sess) = helpers::get_operation(sess, &governor, id).unwrap();
+        assert_eq!(operation, None);
+        let (fee, _sess) = helpers::get_fee_percentage(sess, &ctx.vault).unwrap();
+        assert_eq!(fee, 300);
+    }
+    #[test]
+    fn test_governor_update_agents_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (governor, ctx) = setup_governor(ctx).unwrap();
+
+        let (id, sess) = helpers::call_governor_schedule_update_agents(
+            ctx.sess,
+            &governor,
             &ctx.bob,
             vec![helpers::WeightUpdate {
                 agent: ctx.nominators[0].clone(),
                 weight: 50,
                 increase: false,
             }],
-        )
-            .unwrap();
-
-        // Request unlocks of all 5 million sA0
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1_000_000e12 as u128).unwrap();
-
-        // Fees accumulated for 5m AZERO staked for 2 days
-        let expected_fees = 5_000_000e12 as u128 * 2 * helpers::DAY as u128 / helpers::YEAR as u128 * 200 / helpers::BIPS;
-        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
-        assert_eq!(claimable_fees, expected_fees);
+        ).unwrap();
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_governor_execute(sess, &governor, &ctx.bob, id).unwrap();
 
-        let (expected_azero, sess) = helpers::get_azero_from_shares(sess, &ctx.vault, expected_fees).unwrap();
+        let (total_weight, agents, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(agents[0].weight, 50);
+        assert_eq!(total_weight, 100 * VALIDATOR_COUNT as u128 - 50);
+    }
+    #[test]
+    fn test_governor_execute_panic_because_delay_not_elapsed() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (governor, ctx) = setup_governor(ctx).unwrap();
 
-        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        let (stake2, _, _sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        assert_eq!(stake1, expected_azero * 50 / 150); // agent 0 fees
-        assert_eq!(stake2, expected_azero * 100 / 150 + 1); // agent 1 fees
+        let (id, sess) = helpers::call_governor_schedule_propose_fee(ctx.sess, &governor, &ctx.bob, 100).unwrap();
+        let sess = helpers::update_in_milliseconds(sess, helpers::DAY - 1);
+        match helpers::call_governor_execute(sess, &governor, &ctx.bob, id) {
+            Ok(_) => panic!("Should panic because operation is not ready"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_governor_execute_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (governor, ctx) = setup_governor(ctx).unwrap();
 
-        Ok(())
+        let (id, sess) = helpers::call_governor_schedule_propose_fee(ctx.sess, &governor, &ctx.bob, 100).unwrap();
+        let sess = helpers::update_days(sess, 1);
+        match helpers::call_governor_execute(sess, &governor, &ctx.charlie, id) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
     }
     #[test]
-    fn test_withdraw_all() -> Result<(), Box<dyn Error>> {
-        let ctx = setup(2 as usize).unwrap();
-        let sess = ctx.sess;
+    fn test_governor_schedule_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (governor, ctx) = setup_governor(ctx).unwrap();
 
-        // Stake 5 million AZERO
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1_000_000e12 as u128).unwrap();
+        match helpers::call_function(
+            ctx.sess,
+            &governor,
+            &ctx.charlie,
+            String::from("IGovernor::schedule_propose_fee"),
+            Some(vec![String::from("100")]),
+            None,
+            helpers::transcoder_governor(),
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_governor_cancel_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (governor, ctx) = setup_governor(ctx).unwrap();
 
-        // Allow fees to accumulate
-        let sess = helpers::update_days(sess, 2);
+        let (id, sess) = helpers::call_governor_schedule_propose_fee(ctx.sess, &governor, &ctx.bob, 100).unwrap();
 
-        // Update agent weight from 100/200 to 50/150
-        // Change weight -50 (from 100 to 50)
-        let sess = helpers::call_update_agents(
-            sess,
-            &ctx.registry,
-            &ctx.bob,
-            vec![helpers::WeightUpdate {
-                agent: ctx.nominators[0].clone(),
-                weight: 50,
-                increase: false,
-            }],
-        )
-            .unwrap();
+        // Only the guardian can cancel
+        let sess = helpers::call_governor_cancel(sess, &governor, &ctx.alice, id).unwrap();
+        let (operation, sess) = helpers::get_operation(sess, &governor, id).unwrap();
+        assert_eq!(operation, None);
 
-        // Request unlocking of 5 million AZERO
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1_000_000e12 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1_000_000e12 as u128).unwrap();
+        let sess = helpers::update_days(sess, 1);
 
-        // Wait for cooldown period
-        let sess = helpers::update_days(sess, 14);
+        match helpers::call_governor_execute(sess, &governor, &ctx.bob, id) {
+            Ok(_) => panic!("Should panic because operation was cancelled"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_governor_cancel_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (governor, ctx) = setup_governor(ctx).unwrap();
 
-        // Fees accumulated for 5m AZERO staked for 2 days
-        let expected_fees_a = 5_000_000e12 as u128 * 2 * helpers::DAY as u128 / helpers::YEAR as u128 * 200 / helpers::BIPS;
-        let expected_fees_b = expected_fees_a * 14 * helpers::DAY as u128 / helpers::YEAR as u128 * 200 / helpers::BIPS;
-        let expected_fees = expected_fees_a + expected_fees_b;
+        let (id, sess) = helpers::call_governor_schedule_propose_fee(ctx.sess, &governor, &ctx.bob, 100).unwrap();
+        match helpers::call_governor_cancel(sess, &governor, &ctx.bob, id) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    /// Deploys a 2-of-3 multisig owned by bob, charlie and dave
+    /// and hands it the AddAgent role on the registry
+    fn setup_multisig(ctx: TestContext) -> Result<(AccountId32, TestContext), Box<dyn Error>> {
+        let mut ctx = ctx;
+        let multisig = ctx.sess.deploy(
+            helpers::bytes_multisig(),
+            "new",
+            &[
+                format!("[{}, {}, {}]", ctx.bob, ctx.charlie, ctx.dave),
+                "2".to_string(),
+            ],
+            vec![1],
+            None,
+            &helpers::transcoder_multisig().unwrap(),
+        )?;
+        ctx.sess.set_transcoder(multisig.clone(), &helpers::transcoder_multisig().unwrap());
 
-        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
-        assert_eq!(claimable_fees, expected_fees);
+        let sess = helpers::propose_role(ctx.sess, &ctx.registry, &ctx.bob, &helpers::RoleType::AddAgent, &multisig)?;
+        let call_data = helpers::encode_call(
+            helpers::transcoder_registry(),
+            "IRegistry::accept_role",
+            &["AddAgent".to_string()],
+        );
+        let (id, sess) = helpers::call_multisig_propose(sess, &multisig, &ctx.bob, &ctx.registry, call_data, 0)?;
+        let sess = helpers::call_multisig_approve(sess, &multisig, &ctx.charlie, id)?;
+        let mut sess = helpers::call_multisig_execute(sess, &multisig, &ctx.bob, id)?;
 
-        let (expected_azero, sess) = helpers::get_azero_from_shares(sess, &ctx.vault, expected_fees).unwrap();
+        // Fund the creation bond of new agents
+        sess.chain_api().add_tokens(multisig.clone(), 1_000e12 as u128);
 
-        // Verify all AZERO is withdrawn except fees
-        let (stake, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        assert_eq!(stake, expected_azero * 50 / 150); // agent 0 weight
-        let (stake, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        assert_eq!(stake, expected_azero * 100 / 150 + 1); // agent 1 weight
+        ctx.sess = sess;
+        Ok((multisig, ctx))
+    }
+    fn add_agent_call_data(admin: &AccountId32, validator: &AccountId32) -> String {
+        helpers::encode_call(
+            helpers::transcoder_registry(),
+            "IRegistry::add_agent",
+            &[admin.to_string(), helpers::accounts_arg(&[validator.clone()]), String::from("false")],
+        )
+    }
+    #[test]
+    fn test_multisig_add_agent_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (multisig, ctx) = setup_multisig(ctx).unwrap();
 
-        let fee_split = expected_azero / 5;
-        let (redeemed, sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.alice, 0).unwrap();
-        assert_eq!(redeemed, 1_000_000e12 as u128 + 32 - fee_split - 10);
-        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.bob, 0).unwrap();
-        assert_eq!(redeemed, 1_000_000e12 as u128 + 32 - fee_split - 8);
-        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.charlie, 0).unwrap();
-        assert_eq!(redeemed, 1_000_000e12 as u128 + 32 - fee_split - 8);
-        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.dave, 0).unwrap();
-        assert_eq!(redeemed, 1_000_000e12 as u128 + 32 - fee_split - 8);
-        let (redeemed, mut sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.ed, 0).unwrap();
-        assert_eq!(redeemed, 1_000_000e12 as u128 + 32 - fee_split - 8);
+        let (role, sess) = helpers::get_role(ctx.sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
+        assert_eq!(role, multisig);
 
-        let vault_balance = sess.chain_api().balance(&ctx.vault);
-        assert_eq!(vault_balance, 1, "Vault should only have dust remaining");
+        let validator = AccountId32::new([200u8; 32]);
+        let call_data = add_agent_call_data(&ctx.bob, &validator);
+        let (id, sess) = helpers::call_multisig_propose(sess, &multisig, &ctx.bob, &ctx.registry, call_data, 100e12 as u128).unwrap();
+        let sess = helpers::call_multisig_approve(sess, &multisig, &ctx.charlie, id).unwrap();
+        let sess = helpers::call_multisig_execute(sess, &multisig, &ctx.dave, id).unwrap();
 
-        Ok(())
+        let (_, agents, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(agents.len(), VALIDATOR_COUNT + 1);
     }
     #[test]
-    fn test_token_transfer_from_panics_properly() {
+    fn test_multisig_execute_panic_because_threshold_not_met() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (multisig, ctx) = setup_multisig(ctx).unwrap();
 
-        // Bob stakes 1m AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
+        let validator = AccountId32::new([200u8; 32]);
+        let call_data = add_agent_call_data(&ctx.bob, &validator);
+        let (id, sess) = helpers::call_multisig_propose(ctx.sess, &multisig, &ctx.bob, &ctx.registry, call_data, 100e12 as u128).unwrap();
+        match helpers::call_multisig_execute(sess, &multisig, &ctx.bob, id) {
+            Ok(_) => panic!("Should panic because only 1 of 2 approvals"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_multisig_approve_panic_because_already_approved() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (multisig, ctx) = setup_multisig(ctx).unwrap();
 
-        // Ed attempts to transfer 1k of Bob's sA0
-        match helpers::call_function(
-            sess,
-            &ctx.share_token,
-            &ctx.ed, // not bob
-            String::from("PSP22::transfer_from"),
-            Some(vec![ctx.bob.to_string(), ctx.ed.to_string(), 1_000e12.to_string(), "[]".to_string()]),
-            None,
-            helpers::transcoder_share_token(),
-        )  {
-            Ok(_) => panic!("Should panic because Bob has not approved Ed to transfer sA0"),
-            Err(res) => println!("{:?}", res.to_string()),
+        let validator = AccountId32::new([200u8; 32]);
+        let call_data = add_agent_call_data(&ctx.bob, &validator);
+        let (id, sess) = helpers::call_multisig_propose(ctx.sess, &multisig, &ctx.bob, &ctx.registry, call_data, 100e12 as u128).unwrap();
+        match helpers::call_multisig_approve(sess, &multisig, &ctx.bob, id) {
+            Ok(_) => panic!("Should panic because proposer already approved"),
+            Err(_) => (),
         };
     }
     #[test]
-    fn test_token_transfer_from_works_normally() {
+    fn test_multisig_propose_panic_because_caller_restricted() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (multisig, ctx) = setup_multisig(ctx).unwrap();
 
-        // Bob stakes 1m AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e12 as u128).unwrap();
+        let validator = AccountId32::new([200u8; 32]);
+        let call_data = add_agent_call_data(&ctx.bob, &validator);
+        match helpers::call_multisig_propose(ctx.sess, &multisig, &ctx.ed, &ctx.registry, call_data, 100e12 as u128) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_multisig_add_agent_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (_, ctx) = setup_multisig(ctx).unwrap();
 
-        // Bob approves Ed to transfer 1k sA0
-        let sess = helpers::call_function(
-            sess,
-            &ctx.share_token,
+        // Former role holder can no longer add agents directly
+        match helpers::call_add_agent(
+            ctx.sess,
+            &ctx.registry,
             &ctx.bob,
-            String::from("PSP22::approve"),
-            Some(vec![ctx.ed.to_string(), 1_000e12.to_string()]),
-            None,
-            helpers::transcoder_share_token(),
-        ).unwrap();
+            &ctx.bob,
+            &AccountId32::new([200u8; 32]),
+            100e12 as u128,
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_multisig_threshold_change_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (multisig, ctx) = setup_multisig(ctx).unwrap();
 
-        // Ed transfers 1k of Bob's sA0
-        helpers::call_function(
-            sess,
-            &ctx.share_token,
-            &ctx.ed, // previously approved
-            String::from("PSP22::transfer_from"),
-            Some(vec![ctx.bob.to_string(), ctx.ed.to_string(), 1_000e12.to_string(), "[]".to_string()]),
+        let call_data = helpers::encode_call(
+            helpers::transcoder_multisig(),
+            "IMultisig::set_threshold",
+            &["3".to_string()],
+        );
+        let (id, sess) = helpers::call_multisig_propose(ctx.sess, &multisig, &ctx.bob, &multisig, call_data, 0).unwrap();
+        let sess = helpers::call_multisig_approve(sess, &multisig, &ctx.charlie, id).unwrap();
+        let sess = helpers::call_multisig_execute(sess, &multisig, &ctx.bob, id).unwrap();
+
+        let (threshold, sess) = helpers::get_multisig_threshold(sess, &multisig).unwrap();
+        assert_eq!(threshold, 3);
+
+        // Two approvals no longer suffice
+        let validator = AccountId32::new([200u8; 32]);
+        let call_data = add_agent_call_data(&ctx.bob, &validator);
+        let (id, sess) = helpers::call_multisig_propose(sess, &multisig, &ctx.bob, &ctx.registry, call_data, 100e12 as u128).unwrap();
+        let sess = helpers::call_multisig_approve(sess, &multisig, &ctx.charlie, id).unwrap();
+        match helpers::call_multisig_execute(sess, &multisig, &ctx.bob, id) {
+            Ok(_) => panic!("Should panic because only 2 of 3 approvals"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_multisig_execute_keeps_proposal_when_call_reverts() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (multisig, ctx) = setup_multisig(ctx).unwrap();
+
+        // Multisig rejects the call with InvalidThreshold as there are only 3 owners
+        let call_data = helpers::encode_call(
+            helpers::transcoder_multisig(),
+            "IMultisig::set_threshold",
+            &["4".to_string()],
+        );
+        let (id, sess) = helpers::call_multisig_propose(ctx.sess, &multisig, &ctx.bob, &multisig, call_data, 0).unwrap();
+        let mut sess = helpers::call_multisig_approve(sess, &multisig, &ctx.charlie, id).unwrap();
+        sess.set_actor(ctx.bob.clone());
+        assert!(sess.call_with_address(multisig.clone(), "IMultisig::execute", &[id.to_string()], None).is_err());
+
+        let (threshold, sess) = helpers::get_multisig_threshold(sess, &multisig).unwrap();
+        assert_eq!(threshold, 2);
+        let (approvals, _sess) = helpers::get_multisig_approval_count(sess, &multisig, id).unwrap();
+        assert_eq!(approvals, 2);
+    }
+    #[test]
+    fn test_multisig_set_threshold_panic_because_caller_restricted() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (multisig, ctx) = setup_multisig(ctx).unwrap();
+
+        match helpers::call_function(
+            ctx.sess,
+            &multisig,
+            &ctx.bob,
+            String::from("IMultisig::set_threshold"),
+            Some(vec![String::from("1")]),
             None,
-            helpers::transcoder_share_token(),
-        ).unwrap();
+            helpers::transcoder_multisig(),
+        ) {
+            Ok(_) => panic!("Should panic because caller is not the multisig"),
+            Err(_) => (),
+        };
     }
     #[test]
-    fn test_compound_call() -> Result<(), Box<dyn Error>> {
+    fn test_multisig_replace_owner_flow() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (multisig, ctx) = setup_multisig(ctx).unwrap();
+
+        // Dave approves an add_agent proposal before being rotated out
+        let validator = AccountId32::new([200u8; 32]);
+        let call_data = add_agent_call_data(&ctx.bob, &validator);
+        let (add_id, sess) = helpers::call_multisig_propose(ctx.sess, &multisig, &ctx.bob, &ctx.registry, call_data, 100e12 as u128).unwrap();
+        let sess = helpers::call_multisig_approve(sess, &multisig, &ctx.dave, add_id).unwrap();
+
+        let call_data = helpers::encode_call(
+            helpers::transcoder_multisig(),
+            "IMultisig::replace_owner",
+            &[ctx.dave.to_string(), ctx.ed.to_string()],
+        );
+        let (id, sess) = helpers::call_multisig_propose(sess, &multisig, &ctx.bob, &multisig, call_data, 0).unwrap();
+        let sess = helpers::call_multisig_approve(sess, &multisig, &ctx.charlie, id).unwrap();
+        let sess = helpers::call_multisig_execute(sess, &multisig, &ctx.bob, id).unwrap();
 
-        // Stake 1 AZERO
-        let deposit_amount = 1e12 as u128;
-        let (shares, mut sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
-        assert_eq!(shares, deposit_amount); // 1:1 ratio
+        let (owners, sess) = helpers::get_multisig_owners(sess, &multisig).unwrap();
+        assert_eq!(owners, vec![ctx.bob.clone(), ctx.charlie.clone(), ctx.ed.clone()]);
 
-        // Fund nominator agents to simulate AZERO being claimed
-        let mock_reward = 10_000;
-        sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
-        sess.chain_api().add_tokens(ctx.nominators[1].clone(), mock_reward);
+        // Approval from the removed owner no longer counts
+        let (approvals, sess) = helpers::get_multisig_approval_count(sess, &multisig, add_id).unwrap();
+        assert_eq!(approvals, 1);
 
-        // Compound
+        // New owner can approve in their place
+        let sess = helpers::call_multisig_approve(sess, &multisig, &ctx.ed, add_id).unwrap();
+        let sess = helpers::call_multisig_execute(sess, &multisig, &ctx.ed, add_id).unwrap();
+
+        let (_, agents, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(agents.len(), VALIDATOR_COUNT + 1);
+    }
+    /// Deploys weight voting with a maximum share of `max_share` bips,
+    /// hands it the UpdateAgents role and gives alice voting power for the next epoch
+    fn setup_weight_voting(ctx: TestContext, max_share: u16) -> Result<(AccountId32, TestContext), Box<dyn Error>> {
+        let mut ctx = ctx;
+        let weight_voting = ctx.sess.deploy(
+            helpers::bytes_weight_voting(),
+            "new",
+            &[
+                ctx.registry.to_string(),
+                ctx.share_token.to_string(),
+                ctx.bob.to_string(),
+                max_share.to_string(),
+            ],
+            vec![1],
+            None,
+            &helpers::transcoder_weight_voting().unwrap(),
+        )?;
+        ctx.sess.set_transcoder(weight_voting.clone(), &helpers::transcoder_weight_voting().unwrap());
+
+        let sess = helpers::propose_role(ctx.sess, &ctx.registry, &ctx.bob, &helpers::RoleType::UpdateAgents, &weight_voting)?;
         let sess = helpers::call_function(
             sess,
-            &ctx.vault,
+            &weight_voting,
             &ctx.bob,
-            String::from("IVault::compound"),
+            String::from("IWeightVoting::accept_role"),
             None,
             None,
-            helpers::transcoder_vault(),
-        )
-            .unwrap();
-
-        let (total_pooled, _sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
-        assert_eq!(total_pooled, deposit_amount + mock_reward * 2);
+            helpers::transcoder_weight_voting(),
+        )?;
 
-        Ok(())
+        // Shares count from the start of the following epoch
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128)?;
+        ctx.sess = helpers::update_days(sess, 7);
+        Ok((weight_voting, ctx))
     }
+    #[test]
+    fn test_weight_voting_finalize_flow() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (weight_voting, ctx) = setup_weight_voting(ctx, 5000).unwrap();
+
+        let (role, sess) = helpers::get_role(ctx.sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(role, weight_voting);
 
+        let sess = helpers::call_allocate(
+            sess,
+            &weight_voting,
+            &ctx.alice,
+            vec![
+                helpers::Allocation { agent: ctx.nominators[0].clone(), bips: 6000 },
+                helpers::Allocation { agent: ctx.nominators[1].clone(), bips: 4000 },
+            ],
+        ).unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_finalize(sess, &weight_voting, &ctx.charlie).unwrap();
+
+        // 60% is capped to 50% of the resulting total
+        let (total_weight, agents, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(agents[0].weight, 400e12 as u128);
+        assert_eq!(agents[1].weight, 400e12 as u128);
+        assert!(agents[2..].iter().all(|a| a.weight == 0));
+        assert_eq!(total_weight, 800e12 as u128);
+    }
     #[test]
-    fn test_compound_before_initial_stake() -> Result<(), Box<dyn Error>> {
+    fn test_weight_voting_reallocation_replaces_previous() {
         let ctx = setup(VALIDATOR_COUNT).unwrap();
-        let mut sess = ctx.sess;
+        let (weight_voting, ctx) = setup_weight_voting(ctx, 10000).unwrap();
 
-        // Send funds to an agent
-        sess.chain_api().add_tokens(ctx.nominators[0].clone(), 1000);
+        let sess = helpers::call_allocate(
+            ctx.sess,
+            &weight_voting,
+            &ctx.alice,
+            vec![helpers::Allocation { agent: ctx.nominators[0].clone(), bips: 10000 }],
+        ).unwrap();
+        let sess = helpers::call_allocate(
+            sess,
+            &weight_voting,
+            &ctx.alice,
+            vec![
+                helpers::Allocation { agent: ctx.nominators[1].clone(), bips: 5000 },
+                helpers::Allocation { agent: ctx.nominators[2].clone(), bips: 5000 },
+            ],
+        ).unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_finalize(sess, &weight_voting, &ctx.charlie).unwrap();
+
+        let (total_weight, agents, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(agents[0].weight, 0);
+        assert_eq!(agents[1].weight, 500e12 as u128);
+        assert_eq!(agents[2].weight, 500e12 as u128);
+        assert_eq!(total_weight, 1_000e12 as u128);
+    }
+    #[test]
+    fn test_weight_voting_allocate_panic_because_shares_received_after_snapshot() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (weight_voting, ctx) = setup_weight_voting(ctx, 5000).unwrap();
 
-        // Compound
+        // Shares moved during the epoch cannot be used to vote twice
         let sess = helpers::call_function(
-            sess,
-            &ctx.vault,
-            &ctx.bob,
-            String::from("IVault::compound"),
-            None,
+            ctx.sess,
+            &ctx.share_token,
+            &ctx.alice,
+            String::from("PSP22::transfer"),
+            Some(vec![ctx.charlie.to_string(), 100e12.to_string(), "[]".to_string()]),
             None,
-            helpers::transcoder_vault(),
-        )
-            .unwrap();
+            helpers::transcoder_share_token(),
+        ).unwrap();
+        match helpers::call_allocate(
+            sess,
+            &weight_voting,
+            &ctx.charlie,
+            vec![helpers::Allocation { agent: ctx.nominators[0].clone(), bips: 10000 }],
+        ) {
+            Ok(_) => panic!("Should panic because charlie held no shares at the snapshot"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_weight_voting_allocate_panic_because_bips_exceed_total() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (weight_voting, ctx) = setup_weight_voting(ctx, 5000).unwrap();
 
-        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
-        assert_eq!(total_pooled, 1000);
+        match helpers::call_allocate(
+            ctx.sess,
+            &weight_voting,
+            &ctx.alice,
+            vec![
+                helpers::Allocation { agent: ctx.nominators[0].clone(), bips: 6000 },
+                helpers::Allocation { agent: ctx.nominators[1].clone(), bips: 6000 },
+            ],
+        ) {
+            Ok(_) => panic!("Should panic because allocations exceed 100%"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_weight_voting_finalize_panic_because_already_finalized() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (weight_voting, ctx) = setup_weight_voting(ctx, 5000).unwrap();
 
-        let deposit_amount = 1e12 as u128;
-        let (shares, _sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
-        assert_eq!(shares, deposit_amount); // 1:1 ratio
+        let sess = helpers::call_allocate(
+            ctx.sess,
+            &weight_voting,
+            &ctx.alice,
+            vec![
+                helpers::Allocation { agent: ctx.nominators[0].clone(), bips: 5000 },
+                helpers::Allocation { agent: ctx.nominators[1].clone(), bips: 5000 },
+            ],
+        ).unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_finalize(sess, &weight_voting, &ctx.charlie).unwrap();
+        match helpers::call_finalize(sess, &weight_voting, &ctx.charlie) {
+            Ok(_) => panic!("Should panic because the epoch was already finalized"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_weight_voting_finalize_panic_because_cap_unreachable() {
+        let ctx = setup(VALIDATOR_COUNT).unwrap();
+        let (weight_voting, ctx) = setup_weight_voting(ctx, 5000).unwrap();
 
-        Ok(())
+        // A single agent cannot hold at most 50% of the total
+        let sess = helpers::call_allocate(
+            ctx.sess,
+            &weight_voting,
+            &ctx.alice,
+            vec![helpers::Allocation { agent: ctx.nominators[0].clone(), bips: 10000 }],
+        ).unwrap();
+        let sess = helpers::update_days(sess, 7);
+        match helpers::call_finalize(sess, &weight_voting, &ctx.charlie) {
+            Ok(_) => panic!("Should panic because the cap cannot be respected"),
+            Err(_) => (),
+        };
     }
 }
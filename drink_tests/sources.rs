@@ -22,6 +22,11 @@ pub fn hash_registry() -> String {
     let artifact: Artifact = from_str(&json).unwrap();
     artifact.source.hash
 }
+pub fn hash_vault() -> String {
+    let json = read_to_string("../deployments/development/vault/vault.json").unwrap();
+    let artifact: Artifact = from_str(&json).expect("Should extract hash from vault.json");
+    artifact.source.hash
+}
 pub fn hash_share_token() -> String {
     let json = read_to_string("../deployments/development/share_token/share_token.json").unwrap();
     let artifact: Artifact = from_str(&json).expect("Should extract hash from share_token.json");
@@ -33,6 +38,26 @@ pub fn hash_nominator() -> String {
     let artifact: Artifact = from_str(&json).expect("Should extract hash from mock_nominator.json");
     artifact.source.hash
 }
+pub fn hash_mock_vault_v2() -> String {
+    let json = read_to_string("../deployments/development/mock_vault_v2/mock_vault_v2.json").unwrap();
+    let artifact: Artifact = from_str(&json).expect("Should extract hash from mock_vault_v2.json");
+    artifact.source.hash
+}
+pub fn hash_governor() -> String {
+    let json = read_to_string("../deployments/development/governor/governor.json").unwrap();
+    let artifact: Artifact = from_str(&json).expect("Should extract hash from governor.json");
+    artifact.source.hash
+}
+pub fn hash_multisig() -> String {
+    let json = read_to_string("../deployments/development/multisig/multisig.json").unwrap();
+    let artifact: Artifact = from_str(&json).expect("Should extract hash from multisig.json");
+    artifact.source.hash
+}
+pub fn hash_weight_voting() -> String {
+    let json = read_to_string("../deployments/development/weight_voting/weight_voting.json").unwrap();
+    let artifact: Artifact = from_str(&json).expect("Should extract hash from weight_voting.json");
+    artifact.source.hash
+}
 
 // Transcoders for making contract calls
 
@@ -68,6 +93,54 @@ pub fn transcoder_nominator() -> Option<Rc<ContractMessageTranscoder>> {
             .expect("Failed to create transcoder"),
     ))
 }
+pub fn transcoder_mock_vault_v2() -> Option<Rc<ContractMessageTranscoder>> {
+    Some(Rc::new(
+        ContractMessageTranscoder::load(PathBuf::from(
+            "../deployments/development/mock_vault_v2/mock_vault_v2.json",
+        ))
+            .expect("Failed to create transcoder"),
+    ))
+}
+pub fn transcoder_mock_legacy_vault() -> Option<Rc<ContractMessageTranscoder>> {
+    Some(Rc::new(
+        ContractMessageTranscoder::load(PathBuf::from(
+            "../deployments/development/mock_legacy_vault/mock_legacy_vault.json",
+        ))
+            .expect("Failed to create transcoder"),
+    ))
+}
+pub fn transcoder_mock_legacy_registry() -> Option<Rc<ContractMessageTranscoder>> {
+    Some(Rc::new(
+        ContractMessageTranscoder::load(PathBuf::from(
+            "../deployments/development/mock_legacy_registry/mock_legacy_registry.json",
+        ))
+            .expect("Failed to create transcoder"),
+    ))
+}
+pub fn transcoder_governor() -> Option<Rc<ContractMessageTranscoder>> {
+    Some(Rc::new(
+        ContractMessageTranscoder::load(PathBuf::from(
+            "../deployments/development/governor/governor.json",
+        ))
+            .expect("Failed to create transcoder"),
+    ))
+}
+pub fn transcoder_multisig() -> Option<Rc<ContractMessageTranscoder>> {
+    Some(Rc::new(
+        ContractMessageTranscoder::load(PathBuf::from(
+            "../deployments/development/multisig/multisig.json",
+        ))
+            .expect("Failed to create transcoder"),
+    ))
+}
+pub fn transcoder_weight_voting() -> Option<Rc<ContractMessageTranscoder>> {
+    Some(Rc::new(
+        ContractMessageTranscoder::load(PathBuf::from(
+            "../deployments/development/weight_voting/weight_voting.json",
+        ))
+            .expect("Failed to create transcoder"),
+    ))
+}
 
 // Bytes for instantiating contracts
 
@@ -87,3 +160,27 @@ pub fn bytes_nominator() -> Vec<u8> {
     read("../deployments/development/mock_nominator/mock_nominator.wasm")
         .expect("Failed to find or read contract file")
 }
+pub fn bytes_mock_vault_v2() -> Vec<u8> {
+    read("../deployments/development/mock_vault_v2/mock_vault_v2.wasm")
+        .expect("Failed to find or read contract file")
+}
+pub fn bytes_mock_legacy_vault() -> Vec<u8> {
+    read("../deployments/development/mock_legacy_vault/mock_legacy_vault.wasm")
+        .expect("Failed to find or read contract file")
+}
+pub fn bytes_mock_legacy_registry() -> Vec<u8> {
+    read("../deployments/development/mock_legacy_registry/mock_legacy_registry.wasm")
+        .expect("Failed to find or read contract file")
+}
+pub fn bytes_governor() -> Vec<u8> {
+    read("../deployments/development/governor/governor.wasm")
+        .expect("Failed to find or read contract file")
+}
+pub fn bytes_multisig() -> Vec<u8> {
+    read("../deployments/development/multisig/multisig.wasm")
+        .expect("Failed to find or read contract file")
+}
+pub fn bytes_weight_voting() -> Vec<u8> {
+    read("../deployments/development/weight_voting/weight_voting.wasm")
+        .expect("Failed to find or read contract file")
+}
@@ -16,29 +16,29 @@ async function main() {
   const initParams = await initPolkadotJs()
   const {api, chain, account} = initParams
 
-  const nomination_agent_data = await getDeploymentData('nomination_agent', chainId)
   const registry_data = await getDeploymentData('registry', chainId)
   const registry = new ContractPromise(api, registry_data.abi, registry_data.address)
 
-  const [totalWeight, agents] = await contractQueryAndDecode(api, registry, 'iRegistry::getAgents')
+  const overview = await contractQueryAndDecode(api, registry, 'iRegistry::getAgentsOverview')
 
   const table = []
-  for (const agent of agents) {
-    const agent_contract = new ContractPromise(api, nomination_agent_data.abi, agent.address)
+  for (const agent of overview) {
+    const [, metadata] = await contractQueryAndDecode(api, registry, 'iRegistry::getAgent', [agent.address])
 
-    const stakedValue = await contractQueryAndDecode(api, agent_contract, 'iNominationAgent::getStakedValue')
-    const unbondingValue = await contractQueryAndDecode(api, agent_contract, 'iNominationAgent::getUnbondingValue')
-    const validatorAddress = await contractQueryAndDecode(api, agent_contract, 'iNominationAgent::getValidator')
-    const validatorCommission = (await api.query.staking.validators(validatorAddress)).toHuman()['commission']
+    for (const validator of metadata.validators) {
+      const validatorCommission = (await api.query.staking.validators(validator)).toHuman()['commission']
 
-    table.push({
-      agent: agent.address,
-      validator: validatorAddress,
-      commission: validatorCommission,
-      weight: agent.weight,
-      stakedValue,
-      unbondingValue,
-    })
+      table.push({
+        agent: agent.address,
+        label: metadata.label,
+        validator,
+        commission: validatorCommission,
+        weight: agent.weight,
+        disabled: agent.disabled,
+        stakedValue: agent.staked,
+        unbondingValue: agent.unbonding,
+      })
+    }
   }
   console.table(table)
 
@@ -49,12 +49,15 @@ async function contractQueryAndDecode(
   api: ApiPromise,
   instance: ContractPromise,
   methodName: string,
+  args: unknown[] = [],
 ) {
   const result: ContractCallOutcome = await contractQuery(
     api,
     '',
     instance,
     methodName,
+    {},
+    args,
   )
   return decodeOutput(result, instance, methodName).output
 }
@@ -15,6 +15,16 @@ dotenv.config({
 
 /**
  * Deploys and configures contracts
+ *
+ * The share token has no `set_code` message and is instantiated by the vault, which holds its minting rights.
+ * Tokens deployed before balance snapshots were added cannot serve `snapshot_balance_of`, which `weight_voting`
+ * reads voting power from, so such deployments move to a new set of contracts deployed by this script:
+ *   1. Run this script to deploy a new vault, registry, share token and agents
+ *   2. Instantiate `weight_voting` against the new registry and share token, granting it the UpdateAgents role
+ *   3. Holders call `request_unlock` on the old vault and `redeem` once the cooldown period has passed
+ *   4. Holders stake the redeemed AZERO into the new vault, receiving snapshotted shares
+ * Voting power of migrated holders counts from the snapshot period following their stake.
+ * Old agents are disabled, drained and removed once their stake has been withdrawn.
  */
 const main = async (validators: string[]) => {
   if (!validators || validators.length === 0) {
@@ -121,7 +131,7 @@ const main = async (validators: string[]) => {
       {
         value: minNominatorBond,
       },
-      [account.address, validator],
+      [account.address, [validator], false],
     )
   }
 
@@ -0,0 +1,85 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Dispatches pre-encoded calls queued by the governor and multisig
+
+use ink::env::{
+    call::{build_call, ExecutionInput, Selector},
+    CallFlags,
+    DefaultEnvironment,
+    Environment,
+};
+
+type AccountId = <DefaultEnvironment as Environment>::AccountId;
+type Balance = <DefaultEnvironment as Environment>::Balance;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CallError {
+    /// Call data is too short to hold a message selector
+    InvalidInput,
+    /// Called contract trapped or could not be reached
+    Failed,
+    /// Called message returned an error, its state changes were reverted
+    Reverted,
+}
+
+/// Writes pre-encoded arguments as is, without a length prefix
+struct CallInput<'a>(&'a [u8]);
+
+impl<'a> scale::Encode for CallInput<'a> {
+    fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(self.0);
+    }
+}
+
+/// Whether the called message succeeded
+///
+/// Messages returning `Result` lead their output with its variant index, 0 for `Ok` and 1 for `Err`
+/// Messages without a return value leave the output empty, which counts as a success
+/// The remainder of the output is discarded
+struct CallOutput(bool);
+
+impl scale::Decode for CallOutput {
+    fn decode<I: scale::Input>(input: &mut I) -> Result<Self, scale::Error> {
+        let succeeded = match input.read_byte() {
+            Ok(0) | Err(_) => true,
+            Ok(1) => false,
+            Ok(_) => return Err("Invalid result variant".into()),
+        };
+        while input.read_byte().is_ok() {}
+        Ok(CallOutput(succeeded))
+    }
+}
+
+/// Calls `target` with `call_data`, the message selector followed by its SCALE encoded arguments
+///
+/// The called message must return a `Result` or nothing
+/// A reverted callee is still decoded by `ink_env`, so its returned variant is checked here
+pub fn invoke(
+    target: AccountId,
+    call_data: &[u8],
+    transferred_value: Balance,
+    call_flags: CallFlags,
+) -> Result<(), CallError> {
+    if call_data.len() < 4 {
+        return Err(CallError::InvalidInput);
+    }
+
+    let (selector, input) = call_data.split_at(4);
+    let output = build_call::<DefaultEnvironment>()
+        .call(target)
+        .transferred_value(transferred_value)
+        .call_flags(call_flags)
+        .exec_input(
+            ExecutionInput::new(Selector::new([selector[0], selector[1], selector[2], selector[3]]))
+                .push_arg(CallInput(input)),
+        )
+        .returns::<CallOutput>()
+        .try_invoke()
+        .map_err(|_| CallError::Failed)?
+        .map_err(|_| CallError::Failed)?;
+
+    if !output.0 {
+        return Err(CallError::Reverted);
+    }
+    Ok(())
+}
@@ -0,0 +1,14 @@
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum GovernorError {
+    InvalidPermissions,
+    InvalidInput,
+    NoChange,
+    OperationNotFound,
+    /// Operation delay has not elapsed
+    OperationNotReady,
+    /// Called message returned an error, trapped or could not be reached
+    CallFailed,
+    /// Arithmetic operation would exceed the bounds of its type
+    Overflow,
+}
@@ -0,0 +1,427 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub mod errors;
+pub mod traits;
+
+#[ink::contract]
+pub mod governor {
+    use crate::errors::GovernorError;
+    use crate::traits::IGovernor;
+    use ink::{
+        codegen::EmitEvent,
+        reflect::ContractEventBase,
+        env::CallFlags,
+        prelude::vec::Vec,
+        storage::{Lazy, Mapping},
+    };
+    use registry::registry::WeightUpdate;
+    use scale::Encode;
+
+    type Event = <Governor as ContractEventBase>::Type;
+
+    pub const DAY: u64 = 86400 * 1000;
+
+    // Selectors of the messages targeted by the typed helpers
+    const UPDATE_AGENTS_SELECTOR: [u8; 4] = [0, 0, 0, 2];
+    const PROPOSE_FEE_SELECTOR: [u8; 4] = ink::selector_bytes!("IVault::propose_fee");
+    const EXECUTE_FEE_SELECTOR: [u8; 4] = ink::selector_bytes!("IVault::execute_fee");
+    const VAULT_ANNOUNCE_CODE_SELECTOR: [u8; 4] = ink::selector_bytes!("IVault::announce_code");
+    const VAULT_APPLY_CODE_SELECTOR: [u8; 4] = ink::selector_bytes!("IVault::apply_code");
+    const REGISTRY_ANNOUNCE_CODE_SELECTOR: [u8; 4] = ink::selector_bytes!("IRegistry::announce_code");
+    const REGISTRY_APPLY_CODE_SELECTOR: [u8; 4] = ink::selector_bytes!("IRegistry::apply_code");
+    const AGENT_ANNOUNCE_CODE_SELECTOR: [u8; 4] = ink::selector_bytes!("IRegistry::announce_agent_code");
+    const AGENT_APPLY_CODE_SELECTOR: [u8; 4] = ink::selector_bytes!("IRegistry::apply_agent_code");
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Operation {
+        pub target: AccountId,
+        // Message selector followed by its SCALE encoded arguments
+        pub call_data: Vec<u8>,
+        pub transferred_value: Balance,
+        // Earliest time the operation can be executed
+        pub ready_at: Timestamp,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum UpgradeTarget {
+        Vault,
+        Registry,
+        // All nomination agents, upgraded through the registry
+        Agents,
+    }
+
+    #[ink(event)]
+    pub struct CallScheduled {
+        #[ink(topic)]
+        id: u32,
+        target: AccountId,
+        call_data: Vec<u8>,
+        transferred_value: Balance,
+        ready_at: Timestamp,
+    }
+    #[ink(event)]
+    pub struct CallExecuted {
+        #[ink(topic)]
+        id: u32,
+    }
+    #[ink(event)]
+    pub struct CallCancelled {
+        #[ink(topic)]
+        id: u32,
+    }
+    #[ink(event)]
+    pub struct MinDelayChanged {
+        new_delay: u64,
+    }
+    #[ink(event)]
+    pub struct ProposersChanged {
+        proposers: Vec<AccountId>,
+    }
+    #[ink(event)]
+    pub struct ExecutorsChanged {
+        executors: Vec<AccountId>,
+    }
+    #[ink(event)]
+    pub struct GuardianChanged {
+        new_account: AccountId,
+    }
+
+    #[ink(storage)]
+    pub struct Governor {
+        // Queued operations indexed by id
+        pub operations: Mapping<u32, Operation>,
+        pub next_operation_id: u32,
+        // Contracts targeted by the typed helpers
+        pub vault: AccountId,
+        pub registry: AccountId,
+        // Governance settings are only updated through the timelock itself
+        // Kept outside of the packed fields so that updates made by reentrant calls are not overwritten
+        pub min_delay: Lazy<u64>,
+        // Accounts that can queue operations
+        pub proposers: Lazy<Vec<AccountId>>,
+        // Accounts that can execute operations once ready
+        pub executors: Lazy<Vec<AccountId>>,
+        // Account that can cancel queued operations
+        pub guardian: Lazy<AccountId>,
+    }
+
+    impl Governor {
+        #[ink(constructor)]
+        pub fn new(
+            vault: AccountId,
+            registry: AccountId,
+            proposers: Vec<AccountId>,
+            executors: Vec<AccountId>,
+            guardian: AccountId,
+            min_delay: u64,
+        ) -> Self {
+            let mut governor = Self {
+                operations: Mapping::default(),
+                next_operation_id: 0,
+                vault,
+                registry,
+                min_delay: Lazy::new(),
+                proposers: Lazy::new(),
+                executors: Lazy::new(),
+                guardian: Lazy::new(),
+            };
+            governor.min_delay.set(&min_delay);
+            governor.proposers.set(&proposers);
+            governor.executors.set(&executors);
+            governor.guardian.set(&guardian);
+            governor
+        }
+
+        fn emit_event<EE>(emitter: EE, event: Event)
+        where
+            EE: EmitEvent<Governor>,
+        {
+            emitter.emit_event(event);
+        }
+
+        fn ensure_self_call(&self) -> Result<(), GovernorError> {
+            if Self::env().caller() != Self::env().account_id() {
+                return Err(GovernorError::InvalidPermissions);
+            }
+            Ok(())
+        }
+
+        fn schedule_call(
+            &mut self,
+            target: AccountId,
+            call_data: Vec<u8>,
+            transferred_value: Balance,
+        ) -> Result<u32, GovernorError> {
+            let caller = Self::env().caller();
+
+            if !self.proposers.get_or_default().contains(&caller) {
+                return Err(GovernorError::InvalidPermissions);
+            }
+            if call_data.len() < 4 {
+                return Err(GovernorError::InvalidInput);
+            }
+
+            let id = self.next_operation_id;
+            let ready_at = Self::env()
+                .block_timestamp()
+                .checked_add(self.min_delay.get_or_default())
+                .ok_or(GovernorError::Overflow)?;
+
+            self.operations.insert(
+                id,
+                &Operation {
+                    target,
+                    call_data: call_data.clone(),
+                    transferred_value,
+                    ready_at,
+                },
+            );
+            self.next_operation_id = id.checked_add(1).ok_or(GovernorError::Overflow)?;
+
+            Self::emit_event(Self::env(), Event::CallScheduled(CallScheduled {
+                id,
+                target,
+                call_data,
+                transferred_value,
+                ready_at,
+            }));
+
+            Ok(id)
+        }
+
+        fn encode_call<T: Encode>(selector: [u8; 4], args: &T) -> Vec<u8> {
+            let mut call_data = selector.to_vec();
+            args.encode_to(&mut call_data);
+            call_data
+        }
+    }
+
+    impl IGovernor for Governor {
+        /// Queues a call which can be executed after `min_delay`
+        ///
+        /// `call_data` is the message selector followed by its SCALE encoded arguments
+        /// Caller must be a proposer
+        #[ink(message)]
+        fn schedule(
+            &mut self,
+            target: AccountId,
+            call_data: Vec<u8>,
+            transferred_value: Balance,
+        ) -> Result<u32, GovernorError> {
+            self.schedule_call(target, call_data, transferred_value)
+        }
+
+        /// Executes a queued call once its delay has elapsed
+        /// The called message must return a `Result`, an `Err` fails the execution
+        ///
+        /// Caller must be an executor
+        /// Transferred value is taken from the governor balance
+        #[ink(message, payable)]
+        fn execute(&mut self, id: u32) -> Result<(), GovernorError> {
+            let caller = Self::env().caller();
+
+            if !self.executors.get_or_default().contains(&caller) {
+                return Err(GovernorError::InvalidPermissions);
+            }
+
+            let operation = self.operations.take(id).ok_or(GovernorError::OperationNotFound)?;
+
+            if Self::env().block_timestamp() < operation.ready_at {
+                return Err(GovernorError::OperationNotReady);
+            }
+
+            // Operations targeting the governor itself update its settings
+            let call_flags = if operation.target == Self::env().account_id() {
+                CallFlags::default().set_allow_reentry(true)
+            } else {
+                CallFlags::default()
+            };
+
+            // Failing calls revert the whole execution, keeping the operation queued
+            call_utils::invoke(operation.target, &operation.call_data, operation.transferred_value, call_flags)
+                .map_err(|_| GovernorError::CallFailed)?;
+
+            Self::emit_event(Self::env(), Event::CallExecuted(CallExecuted { id }));
+
+            Ok(())
+        }
+
+        /// Discards a queued call
+        ///
+        /// Caller must be the guardian
+        #[ink(message)]
+        fn cancel(&mut self, id: u32) -> Result<(), GovernorError> {
+            if Some(Self::env().caller()) != self.guardian.get() {
+                return Err(GovernorError::InvalidPermissions);
+            }
+
+            self.operations.take(id).ok_or(GovernorError::OperationNotFound)?;
+
+            Self::emit_event(Self::env(), Event::CallCancelled(CallCancelled { id }));
+
+            Ok(())
+        }
+
+        /// Queues `IRegistry::update_agents` on the registry
+        ///
+        /// Caller must be a proposer
+        #[ink(message)]
+        fn schedule_update_agents(&mut self, updates: Vec<WeightUpdate>) -> Result<u32, GovernorError> {
+            let call_data = Self::encode_call(UPDATE_AGENTS_SELECTOR, &updates);
+            self.schedule_call(self.registry, call_data, 0)
+        }
+
+        /// Queues `IVault::propose_fee` on the vault
+        /// The proposed fee is applied by a second operation (see `schedule_execute_fee`)
+        ///
+        /// Caller must be a proposer
+        #[ink(message)]
+        fn schedule_propose_fee(&mut self, new_fee: u16) -> Result<u32, GovernorError> {
+            let call_data = Self::encode_call(PROPOSE_FEE_SELECTOR, &new_fee);
+            self.schedule_call(self.vault, call_data, 0)
+        }
+
+        /// Queues `IVault::execute_fee` on the vault
+        ///
+        /// Caller must be a proposer
+        #[ink(message)]
+        fn schedule_execute_fee(&mut self) -> Result<u32, GovernorError> {
+            self.schedule_call(self.vault, EXECUTE_FEE_SELECTOR.to_vec(), 0)
+        }
+
+        /// Queues the announcement of a new code hash for the vault, registry or agents
+        /// The announced code hash is applied by a second operation (see `schedule_apply_code`)
+        ///
+        /// Caller must be a proposer
+        #[ink(message)]
+        fn schedule_set_code(&mut self, upgrade_target: UpgradeTarget, code_hash: [u8; 32]) -> Result<u32, GovernorError> {
+            let (target, selector) = match upgrade_target {
+                UpgradeTarget::Vault => (self.vault, VAULT_ANNOUNCE_CODE_SELECTOR),
+                UpgradeTarget::Registry => (self.registry, REGISTRY_ANNOUNCE_CODE_SELECTOR),
+                UpgradeTarget::Agents => (self.registry, AGENT_ANNOUNCE_CODE_SELECTOR),
+            };
+            let call_data = Self::encode_call(selector, &code_hash);
+            self.schedule_call(target, call_data, 0)
+        }
+
+        /// Queues applying the announced code hash for the vault, registry or agents
+        ///
+        /// Caller must be a proposer
+        #[ink(message)]
+        fn schedule_apply_code(&mut self, upgrade_target: UpgradeTarget) -> Result<u32, GovernorError> {
+            let (target, selector) = match upgrade_target {
+                UpgradeTarget::Vault => (self.vault, VAULT_APPLY_CODE_SELECTOR),
+                UpgradeTarget::Registry => (self.registry, REGISTRY_APPLY_CODE_SELECTOR),
+                UpgradeTarget::Agents => (self.registry, AGENT_APPLY_CODE_SELECTOR),
+            };
+            self.schedule_call(target, selector.to_vec(), 0)
+        }
+
+        /// Update the delay applied to newly queued operations
+        ///
+        /// Caller must be the governor itself
+        #[ink(message)]
+        fn set_min_delay(&mut self, new_delay: u64) -> Result<(), GovernorError> {
+            self.ensure_self_call()?;
+
+            if self.min_delay.get_or_default() == new_delay {
+                return Err(GovernorError::NoChange);
+            }
+
+            self.min_delay.set(&new_delay);
+
+            Self::emit_event(Self::env(), Event::MinDelayChanged(MinDelayChanged { new_delay }));
+
+            Ok(())
+        }
+
+        /// Replaces the accounts that can queue operations
+        ///
+        /// Caller must be the governor itself
+        #[ink(message)]
+        fn set_proposers(&mut self, proposers: Vec<AccountId>) -> Result<(), GovernorError> {
+            self.ensure_self_call()?;
+
+            self.proposers.set(&proposers);
+
+            Self::emit_event(Self::env(), Event::ProposersChanged(ProposersChanged { proposers }));
+
+            Ok(())
+        }
+
+        /// Replaces the accounts that can execute operations
+        ///
+        /// Caller must be the governor itself
+        #[ink(message)]
+        fn set_executors(&mut self, executors: Vec<AccountId>) -> Result<(), GovernorError> {
+            self.ensure_self_call()?;
+
+            self.executors.set(&executors);
+
+            Self::emit_event(Self::env(), Event::ExecutorsChanged(ExecutorsChanged { executors }));
+
+            Ok(())
+        }
+
+        /// Replaces the account that can cancel operations
+        ///
+        /// Caller must be the governor itself
+        #[ink(message)]
+        fn set_guardian(&mut self, guardian: AccountId) -> Result<(), GovernorError> {
+            self.ensure_self_call()?;
+
+            if self.guardian.get() == Some(guardian) {
+                return Err(GovernorError::NoChange);
+            }
+
+            self.guardian.set(&guardian);
+
+            Self::emit_event(Self::env(), Event::GuardianChanged(GuardianChanged { new_account: guardian }));
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_operation(&self, id: u32) -> Option<Operation> {
+            self.operations.get(id)
+        }
+
+        #[ink(message)]
+        fn get_min_delay(&self) -> u64 {
+            self.min_delay.get_or_default()
+        }
+
+        #[ink(message)]
+        fn get_proposers(&self) -> Vec<AccountId> {
+            self.proposers.get_or_default()
+        }
+
+        #[ink(message)]
+        fn get_executors(&self) -> Vec<AccountId> {
+            self.executors.get_or_default()
+        }
+
+        #[ink(message)]
+        fn get_guardian(&self) -> AccountId {
+            self.guardian.get().unwrap()
+        }
+
+        #[ink(message)]
+        fn get_vault(&self) -> AccountId {
+            self.vault
+        }
+
+        #[ink(message)]
+        fn get_registry(&self) -> AccountId {
+            self.registry
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::errors::GovernorError;
+use crate::governor::{Operation, UpgradeTarget};
+use ink::{primitives::AccountId, prelude::vec::Vec};
+use registry::registry::WeightUpdate;
+
+#[ink::trait_definition]
+pub trait IGovernor {
+    #[ink(message)]
+    fn schedule(
+        &mut self,
+        target: AccountId,
+        call_data: Vec<u8>,
+        transferred_value: u128,
+    ) -> Result<u32, GovernorError>;
+
+    #[ink(message, payable)]
+    fn execute(&mut self, id: u32) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn cancel(&mut self, id: u32) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn schedule_update_agents(&mut self, updates: Vec<WeightUpdate>) -> Result<u32, GovernorError>;
+
+    #[ink(message)]
+    fn schedule_propose_fee(&mut self, new_fee: u16) -> Result<u32, GovernorError>;
+
+    #[ink(message)]
+    fn schedule_execute_fee(&mut self) -> Result<u32, GovernorError>;
+
+    #[ink(message)]
+    fn schedule_set_code(&mut self, upgrade_target: UpgradeTarget, code_hash: [u8; 32]) -> Result<u32, GovernorError>;
+
+    #[ink(message)]
+    fn schedule_apply_code(&mut self, upgrade_target: UpgradeTarget) -> Result<u32, GovernorError>;
+
+    #[ink(message)]
+    fn set_min_delay(&mut self, new_delay: u64) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn set_proposers(&mut self, proposers: Vec<AccountId>) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn set_executors(&mut self, executors: Vec<AccountId>) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn set_guardian(&mut self, guardian: AccountId) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn get_operation(&self, id: u32) -> Option<Operation>;
+
+    #[ink(message)]
+    fn get_min_delay(&self) -> u64;
+
+    #[ink(message)]
+    fn get_proposers(&self) -> Vec<AccountId>;
+
+    #[ink(message)]
+    fn get_executors(&self) -> Vec<AccountId>;
+
+    #[ink(message)]
+    fn get_guardian(&self) -> AccountId;
+
+    #[ink(message)]
+    fn get_vault(&self) -> AccountId;
+
+    #[ink(message)]
+    fn get_registry(&self) -> AccountId;
+}
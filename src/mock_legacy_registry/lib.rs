@@ -0,0 +1,95 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Test contract standing in for the original Registry release
+///
+/// Writes the Registry storage layout which predates storage versioning and upgrades itself
+/// to the current Registry code hash so that `IRegistry::migrate` can be exercised against it.
+#[ink::contract]
+mod mock_legacy_registry {
+    use ink::{
+        prelude::vec::Vec,
+        storage::Mapping,
+    };
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Role {
+        admin: AccountId,
+        account: AccountId,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum RoleType {
+        AddAgent,
+        UpdateAgents,
+        DisableAgent,
+        RemoveAgent,
+        SetCodeHash,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Agent {
+        pub address: AccountId,
+        pub weight: u128,
+        pub disabled: bool,
+    }
+
+    /// Registry storage as originally deployed
+    /// Shares the struct and field names with `registry::registry::Registry` so that storage keys match
+    #[ink(storage)]
+    pub struct Registry {
+        pub agents: Vec<Agent>,
+        pub total_weight: u128,
+        pub roles: Mapping<RoleType, Role>,
+        pub vault: AccountId,
+        pub nomination_agent_hash: Hash,
+        pub nomination_agent_counter: u128,
+    }
+
+    impl Registry {
+        /// Caller holds every role and administers it
+        #[ink(constructor)]
+        pub fn new(vault: AccountId, nomination_agent_hash: Hash) -> Self {
+            let caller = Self::env().caller();
+
+            let mut roles = Mapping::default();
+            for role_type in [
+                RoleType::AddAgent,
+                RoleType::UpdateAgents,
+                RoleType::DisableAgent,
+                RoleType::RemoveAgent,
+                RoleType::SetCodeHash,
+            ] {
+                roles.insert(role_type, &Role { admin: caller, account: caller });
+            }
+
+            Self {
+                agents: Vec::new(),
+                total_weight: 0,
+                roles,
+                vault,
+                nomination_agent_hash,
+                nomination_agent_counter: 0,
+            }
+        }
+
+        /// Replaces the code of this contract, keeping its storage
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: [u8; 32]) {
+            ink::env::set_code_hash(&code_hash).unwrap_or_else(|err| {
+                panic!("Failed to set code hash to {:?} due to {:?}", code_hash, err)
+            });
+        }
+    }
+}
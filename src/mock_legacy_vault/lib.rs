@@ -0,0 +1,137 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Test contract standing in for earlier Vault releases
+///
+/// Writes the Vault storage layout of a previous storage version and upgrades itself to
+/// the current Vault code hash so that `IVault::migrate` can be exercised against it.
+#[ink::contract]
+mod mock_legacy_vault {
+    use ink::{
+        prelude::vec::Vec,
+        storage::{Lazy, Mapping},
+    };
+    use vault::data::{CodeHashAnnouncement, FeeModel, FeeProposal, FeeRecipient, UnlockRequest, BIPS};
+
+    /// Vault storage as laid out up to storage version 1
+    /// Shares the struct and field names with `vault::data::VaultData` so that storage keys match
+    #[ink::storage_item]
+    #[derive(Debug)]
+    pub struct VaultData {
+        pub role_adjust_fee: AccountId,
+        pub role_fee_to: AccountId,
+        pub role_set_code: Option<AccountId>,
+        pub legacy_role_guardian: Lazy<AccountId>,
+
+        pub total_pooled: Balance,
+        pub total_shares_minted: u128,
+        pub total_shares_virtual: u128,
+
+        pub user_unlock_requests: Mapping<AccountId, Vec<UnlockRequest>>,
+
+        pub cooldown_period: u64,
+
+        pub pending_code: Lazy<Option<CodeHashAnnouncement>>,
+        pub set_code_delay: Lazy<u64>,
+
+        pub last_fee_update: Timestamp,
+        pub fee_percentage: u16,
+        pub pending_fee: Lazy<Option<FeeProposal>>,
+        pub fee_model: Lazy<FeeModel>,
+        pub performance_fee_percentage: Lazy<u16>,
+        pub total_performance_fee_shares: Lazy<u128>,
+        pub fee_recipients: Lazy<Vec<FeeRecipient>>,
+
+        pub locked_rewards: Lazy<Balance>,
+        pub last_reward_lock: Lazy<Timestamp>,
+        pub reward_vesting_period: Lazy<u64>,
+
+        pub shares_contract: AccountId,
+        pub registry_contract: AccountId,
+
+        pub storage_version: Lazy<u16>,
+    }
+
+    #[ink(storage)]
+    pub struct MockLegacyVault {
+        data: VaultData,
+    }
+
+    impl MockLegacyVault {
+        /// Writes the storage of a deployment which predates storage versioning
+        /// Caller holds the adjust fee and set code roles
+        #[ink(constructor)]
+        pub fn new_v0(
+            role_fee_to: AccountId,
+            shares_contract: AccountId,
+            registry_contract: AccountId,
+            total_pooled: Balance,
+            era: u64,
+        ) -> Self {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
+
+            Self {
+                data: VaultData {
+                    role_adjust_fee: caller,
+                    role_fee_to,
+                    role_set_code: Some(caller),
+                    legacy_role_guardian: Lazy::new(),
+                    total_pooled,
+                    total_shares_minted: total_pooled,
+                    total_shares_virtual: 0,
+                    user_unlock_requests: Mapping::default(),
+                    cooldown_period: era * 14,
+                    pending_code: Lazy::new(),
+                    set_code_delay: Lazy::new(),
+                    last_fee_update: now,
+                    fee_percentage: 2_00, // 2.00%
+                    pending_fee: Lazy::new(),
+                    fee_model: Lazy::new(),
+                    performance_fee_percentage: Lazy::new(),
+                    total_performance_fee_shares: Lazy::new(),
+                    fee_recipients: Lazy::new(),
+                    locked_rewards: Lazy::new(),
+                    last_reward_lock: Lazy::new(),
+                    reward_vesting_period: Lazy::new(),
+                    shares_contract,
+                    registry_contract,
+                    storage_version: Lazy::new(),
+                },
+            }
+        }
+
+        /// Writes the storage of a deployment on storage version 1
+        /// Caller holds the adjust fee and set code roles
+        #[ink(constructor)]
+        pub fn new_v1(
+            role_fee_to: AccountId,
+            role_guardian: AccountId,
+            shares_contract: AccountId,
+            registry_contract: AccountId,
+            total_pooled: Balance,
+            era: u64,
+        ) -> Self {
+            let now = Self::env().block_timestamp();
+
+            let mut instance = Self::new_v0(role_fee_to, shares_contract, registry_contract, total_pooled, era);
+            let data = &mut instance.data;
+            data.legacy_role_guardian.set(&role_guardian);
+            data.set_code_delay.set(&(era * 14));
+            data.fee_model.set(&FeeModel::Management);
+            data.performance_fee_percentage.set(&10_00); // 10.00%
+            data.fee_recipients.set(&[FeeRecipient { account: role_fee_to, bips: BIPS }].to_vec());
+            data.last_reward_lock.set(&now);
+            data.reward_vesting_period.set(&era);
+            data.storage_version.set(&1);
+            instance
+        }
+
+        /// Replaces the code of this contract, keeping its storage
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: [u8; 32]) {
+            ink::env::set_code_hash(&code_hash).unwrap_or_else(|err| {
+                panic!("Failed to set code hash to {:?} due to {:?}", code_hash, err)
+            });
+        }
+    }
+}
@@ -4,4 +4,8 @@ pub enum RuntimeError {
     CallRuntimeFailed,
     Unauthorized,
     Active,
+    Overflow,
+    Underflow,
+    NoChange,
+    InvalidInput,
 }
@@ -7,7 +7,22 @@ pub mod traits;
 mod mock_nominator {
     use crate::errors::RuntimeError;
     use crate::traits::INominationAgent;
-    use ink::env::Error as EnvError;
+    use ink::{env::Error as EnvError, prelude::vec::Vec, storage::Lazy};
+
+    pub const STORAGE_VERSION: u16 = 2;
+
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum AgentStatus {
+        Active,
+        Disabled,
+        Draining,
+        Destroying,
+        Destroyed,
+    }
 
     #[ink(storage)]
     pub struct NominationAgent {
@@ -18,6 +33,9 @@ mod mock_nominator {
         staked: u128,
         unbonding: u128,
         creation_bond: u128,
+        storage_version: Lazy<u16>,
+        validators: Lazy<Vec<AccountId>>,
+        status: Lazy<(AgentStatus, Timestamp)>,
     }
 
     impl From<EnvError> for RuntimeError {
@@ -34,9 +52,10 @@ mod mock_nominator {
         pub fn new(
             vault: AccountId,
             admin: AccountId,
-            validator: AccountId,
+            validators: Vec<AccountId>,
         ) -> Self {
             let creation_bond = Self::env().transferred_value();
+            let validator = *validators.first().expect("At least one validator");
 
             // Mock spending AZERO to create agent
             Self::env().transfer(
@@ -44,6 +63,12 @@ mod mock_nominator {
                 creation_bond,
             ).unwrap();
 
+            let mut storage_version = Lazy::new();
+            storage_version.set(&STORAGE_VERSION);
+
+            let mut nominated = Lazy::new();
+            nominated.set(&validators);
+
             Self {
                 vault,
                 registry: Self::env().caller(),
@@ -52,6 +77,9 @@ mod mock_nominator {
                 staked: 0,
                 unbonding: 0,
                 creation_bond,
+                storage_version,
+                validators: nominated,
+                status: Lazy::new(),
             }
         }
     }
@@ -62,7 +90,9 @@ mod mock_nominator {
             if Self::env().caller() != self.vault {
                 return Err(RuntimeError::Unauthorized);
             }
-            self.staked += Self::env().transferred_value();
+            self.staked = self.staked
+                .checked_add(Self::env().transferred_value())
+                .ok_or(RuntimeError::Overflow)?;
             return Ok(());
         }
 
@@ -71,8 +101,12 @@ mod mock_nominator {
             if Self::env().caller() != self.vault {
                 return Err(RuntimeError::Unauthorized);
             }
-            self.staked -= amount;
-            self.unbonding += amount;
+            self.staked = self.staked
+                .checked_sub(amount)
+                .ok_or(RuntimeError::Underflow)?;
+            self.unbonding = self.unbonding
+                .checked_add(amount)
+                .ok_or(RuntimeError::Overflow)?;
             return Ok(());
         }
 
@@ -96,7 +130,10 @@ mod mock_nominator {
                 return Err(RuntimeError::Unauthorized);
             }
 
-            let compound_amount = Self::env().balance() - self.staked - self.unbonding;
+            let compound_amount = Self::env().balance()
+                .checked_sub(self.staked)
+                .and_then(|b| b.checked_sub(self.unbonding))
+                .ok_or(RuntimeError::Underflow)?;
 
             // Gracefully return when nomination agent has no rewards
             if compound_amount == 0 {
@@ -104,7 +141,9 @@ mod mock_nominator {
             }
 
             if compound_amount > 0 {
-                self.staked += compound_amount;
+                self.staked = self.staked
+                    .checked_add(compound_amount)
+                    .ok_or(RuntimeError::Overflow)?;
             }
 
             Ok(compound_amount)
@@ -135,6 +174,32 @@ mod mock_nominator {
             self.validator
         }
 
+        #[ink(message)]
+        fn get_validators(&self) -> Vec<AccountId> {
+            self.validators.get_or_default()
+        }
+
+        #[ink(message)]
+        fn get_status(&self) -> (AgentStatus, Timestamp) {
+            self.status.get().unwrap_or((AgentStatus::Active, 0))
+        }
+
+        #[ink(message)]
+        fn nominate(&mut self, validators: Vec<AccountId>) -> Result<(), RuntimeError> {
+            if Self::env().caller() != self.registry {
+                return Err(RuntimeError::Unauthorized);
+            }
+            if validators.is_empty() {
+                return Err(RuntimeError::InvalidInput);
+            }
+            if self.validators.get_or_default() == validators {
+                return Err(RuntimeError::NoChange);
+            }
+            self.validator = validators[0];
+            self.validators.set(&validators);
+            Ok(())
+        }
+
         #[ink(message, selector = 101)]
         fn destroy(&mut self) -> Result<(), RuntimeError> {
             if Self::env().caller() != self.registry {
@@ -144,6 +209,7 @@ mod mock_nominator {
                 return Err(RuntimeError::Active);
             }
             self.creation_bond = 0;
+            self.status.set(&(AgentStatus::Destroying, Self::env().block_timestamp()));
             Ok(())
         }
 
@@ -158,6 +224,7 @@ mod mock_nominator {
             // Requires funds are sent via test environment to succeed
             let balance = Self::env().balance();
             Self::env().transfer(to, balance).unwrap();
+            self.status.set(&(AgentStatus::Destroyed, Self::env().block_timestamp()));
             Ok(balance)
         }
 
@@ -169,5 +236,30 @@ mod mock_nominator {
             ink::env::set_code_hash(&code_hash)?;
             Ok(())
         }
+
+        #[ink(message, selector = 1000)]
+        fn migrate(&mut self) -> Result<(), RuntimeError> {
+            if Self::env().caller() != self.registry {
+                return Err(RuntimeError::Unauthorized);
+            }
+
+            let from_version = self.storage_version.get().unwrap_or(0);
+            if from_version >= STORAGE_VERSION {
+                return Err(RuntimeError::NoChange);
+            }
+
+            if from_version < 2 {
+                self.validators.set(&[self.validator].to_vec());
+            }
+
+            self.storage_version.set(&STORAGE_VERSION);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_storage_version(&self) -> u16 {
+            self.storage_version.get().unwrap_or(0)
+        }
     }
 }
@@ -1,5 +1,6 @@
 use crate::errors::RuntimeError;
-use ink::primitives::AccountId;
+use crate::mock_nominator::AgentStatus;
+use ink::{primitives::AccountId, prelude::vec::Vec};
 
 #[ink::trait_definition]
 pub trait INominationAgent {
@@ -30,6 +31,15 @@ pub trait INominationAgent {
     #[ink(message)]
     fn get_validator(&self) -> AccountId;
 
+    #[ink(message)]
+    fn get_validators(&self) -> Vec<AccountId>;
+
+    #[ink(message)]
+    fn get_status(&self) -> (AgentStatus, u64);
+
+    #[ink(message)]
+    fn nominate(&mut self, validators: Vec<AccountId>) -> Result<(), RuntimeError>;
+
     #[ink(message, selector = 101)]
     fn destroy(&mut self) -> Result<(), RuntimeError>;
 
@@ -38,4 +48,10 @@ pub trait INominationAgent {
 
     #[ink(message, selector = 999)]
     fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), RuntimeError>;
+
+    #[ink(message, selector = 1000)]
+    fn migrate(&mut self) -> Result<(), RuntimeError>;
+
+    #[ink(message)]
+    fn get_storage_version(&self) -> u16;
 }
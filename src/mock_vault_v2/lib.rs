@@ -0,0 +1,71 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub mod traits;
+
+/// Test contract standing in for the next Vault release
+///
+/// Reads the Vault storage layout and bumps the storage version so that upgrades
+/// via `IVault::apply_code` followed by `IVault::migrate` can be exercised.
+#[ink::contract]
+mod mock_vault_v2 {
+    use crate::traits::IVault;
+    use ink::prelude::vec::Vec;
+    use vault::{
+        data::{UnlockRequest, VaultData, VaultRoleType, STORAGE_VERSION as VAULT_STORAGE_VERSION},
+        errors::VaultError,
+    };
+
+    pub const STORAGE_VERSION: u16 = VAULT_STORAGE_VERSION + 1;
+
+    #[ink(storage)]
+    pub struct MockVaultV2 {
+        data: VaultData,
+    }
+
+    impl MockVaultV2 {
+        /// Only reachable by upgrading an existing Vault
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            panic!("Deploy the Vault and upgrade it to this code hash")
+        }
+    }
+
+    impl IVault for MockVaultV2 {
+        #[ink(message)]
+        fn migrate(&mut self) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            if Some(caller) != self.data.get_role(VaultRoleType::SetCode) {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            let from_version = self.data.get_storage_version();
+            if from_version >= STORAGE_VERSION {
+                return Err(VaultError::NoChange);
+            }
+
+            self.data.storage_version.set(&STORAGE_VERSION);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_storage_version(&self) -> u16 {
+            self.data.get_storage_version()
+        }
+
+        #[ink(message)]
+        fn get_role(&self, role_type: VaultRoleType) -> Option<AccountId> {
+            self.data.get_role(role_type)
+        }
+
+        #[ink(message)]
+        fn get_role_admin(&self, role_type: VaultRoleType) -> Option<AccountId> {
+            self.data.roles.get(role_type).map(|role| role.admin)
+        }
+
+        #[ink(message)]
+        fn get_unlock_requests(&self, user: AccountId) -> Vec<UnlockRequest> {
+            self.data.user_unlock_requests.get(user).unwrap_or_default()
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use ink::{
+    primitives::AccountId,
+    prelude::vec::Vec,
+};
+use vault::{
+    data::{UnlockRequest, VaultRoleType},
+    errors::VaultError,
+};
+
+/// Subset of the Vault interface served after an upgrade
+///
+/// Shares the trait name with `vault::traits::IVault` so that message selectors match.
+#[ink::trait_definition]
+pub trait IVault {
+    #[ink(message)]
+    fn migrate(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_storage_version(&self) -> u16;
+
+    #[ink(message)]
+    fn get_role(&self, role_type: VaultRoleType) -> Option<AccountId>;
+
+    #[ink(message)]
+    fn get_role_admin(&self, role_type: VaultRoleType) -> Option<AccountId>;
+
+    #[ink(message)]
+    fn get_unlock_requests(&self, user: AccountId) -> Vec<UnlockRequest>;
+}
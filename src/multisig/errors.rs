@@ -0,0 +1,20 @@
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum MultisigError {
+    InvalidPermissions,
+    InvalidInput,
+    NoChange,
+    ProposalNotFound,
+    /// Caller has already approved the proposal
+    AlreadyApproved,
+    /// Caller has not approved the proposal
+    NotApproved,
+    /// Proposal does not have enough approvals from current owners
+    ThresholdNotMet,
+    /// Threshold must be non-zero and cannot exceed the number of owners
+    InvalidThreshold,
+    /// Called message returned an error, trapped or could not be reached
+    CallFailed,
+    /// Arithmetic operation would exceed the bounds of its type
+    Overflow,
+}
@@ -0,0 +1,383 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub mod errors;
+pub mod traits;
+
+#[ink::contract]
+pub mod multisig {
+    use crate::errors::MultisigError;
+    use crate::traits::IMultisig;
+    use ink::{
+        env::CallFlags,
+        prelude::vec::Vec,
+        storage::{Lazy, Mapping},
+    };
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Proposal {
+        pub proposer: AccountId,
+        pub target: AccountId,
+        // Message selector followed by its SCALE encoded arguments
+        pub call_data: Vec<u8>,
+        pub transferred_value: Balance,
+        // Owners who approved, only those still owners count towards the threshold
+        pub approvals: Vec<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct ProposalCreated {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        proposer: AccountId,
+        target: AccountId,
+        call_data: Vec<u8>,
+        transferred_value: Balance,
+    }
+    #[ink(event)]
+    pub struct ProposalApproved {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+    }
+    #[ink(event)]
+    pub struct ApprovalRevoked {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+    }
+    #[ink(event)]
+    pub struct ProposalExecuted {
+        #[ink(topic)]
+        id: u32,
+    }
+    #[ink(event)]
+    pub struct ProposalCancelled {
+        #[ink(topic)]
+        id: u32,
+    }
+    #[ink(event)]
+    pub struct ThresholdChanged {
+        new_threshold: u16,
+    }
+    #[ink(event)]
+    pub struct OwnerAdded {
+        #[ink(topic)]
+        owner: AccountId,
+    }
+    #[ink(event)]
+    pub struct OwnerRemoved {
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    #[ink(storage)]
+    pub struct Multisig {
+        // Open proposals indexed by id
+        pub proposals: Mapping<u32, Proposal>,
+        pub next_proposal_id: u32,
+        // Owners and threshold are only updated through an approved proposal targeting the multisig itself
+        // The reentrant call writes them directly, the packed fields are written back when execute returns
+        pub owners: Lazy<Vec<AccountId>>,
+        pub threshold: Lazy<u16>,
+    }
+
+    impl Multisig {
+        #[ink(constructor)]
+        pub fn new(owners: Vec<AccountId>, threshold: u16) -> Self {
+            for (i, owner) in owners.iter().enumerate() {
+                if owners[..i].contains(owner) {
+                    panic!("Duplicate owner");
+                }
+            }
+            if threshold == 0 || threshold as usize > owners.len() {
+                panic!("Invalid threshold");
+            }
+
+            let mut multisig = Self {
+                proposals: Mapping::default(),
+                next_proposal_id: 0,
+                owners: Lazy::new(),
+                threshold: Lazy::new(),
+            };
+            multisig.owners.set(&owners);
+            multisig.threshold.set(&threshold);
+            multisig
+        }
+
+        fn ensure_owner(&self) -> Result<AccountId, MultisigError> {
+            let caller = Self::env().caller();
+            if !self.owners.get_or_default().contains(&caller) {
+                return Err(MultisigError::InvalidPermissions);
+            }
+            Ok(caller)
+        }
+
+        fn ensure_self_call(&self) -> Result<(), MultisigError> {
+            if Self::env().caller() != Self::env().account_id() {
+                return Err(MultisigError::InvalidPermissions);
+            }
+            Ok(())
+        }
+
+        fn count_approvals(&self, proposal: &Proposal) -> u16 {
+            let owners = self.owners.get_or_default();
+            proposal.approvals.iter().filter(|a| owners.contains(a)).count() as u16
+        }
+    }
+
+    impl IMultisig for Multisig {
+        /// Opens a proposal to call `target` with `call_data`, approved by the proposer
+        ///
+        /// `call_data` is the message selector followed by its SCALE encoded arguments
+        /// Caller must be an owner
+        #[ink(message)]
+        fn propose(
+            &mut self,
+            target: AccountId,
+            call_data: Vec<u8>,
+            transferred_value: Balance,
+        ) -> Result<u32, MultisigError> {
+            let caller = self.ensure_owner()?;
+
+            if call_data.len() < 4 {
+                return Err(MultisigError::InvalidInput);
+            }
+
+            let id = self.next_proposal_id;
+
+            self.proposals.insert(
+                id,
+                &Proposal {
+                    proposer: caller,
+                    target,
+                    call_data: call_data.clone(),
+                    transferred_value,
+                    approvals: [caller].to_vec(),
+                },
+            );
+            self.next_proposal_id = id.checked_add(1).ok_or(MultisigError::Overflow)?;
+
+            Self::env().emit_event(ProposalCreated {
+                id,
+                proposer: caller,
+                target,
+                call_data,
+                transferred_value,
+            });
+            Self::env().emit_event(ProposalApproved {
+                id,
+                owner: caller,
+            });
+
+            Ok(id)
+        }
+
+        /// Caller must be an owner
+        #[ink(message)]
+        fn approve(&mut self, id: u32) -> Result<(), MultisigError> {
+            let caller = self.ensure_owner()?;
+
+            let mut proposal = self.proposals.get(id).ok_or(MultisigError::ProposalNotFound)?;
+
+            if proposal.approvals.contains(&caller) {
+                return Err(MultisigError::AlreadyApproved);
+            }
+
+            proposal.approvals.push(caller);
+            self.proposals.insert(id, &proposal);
+
+            Self::env().emit_event(ProposalApproved {
+                id,
+                owner: caller,
+            });
+
+            Ok(())
+        }
+
+        /// Caller must be an owner
+        #[ink(message)]
+        fn revoke_approval(&mut self, id: u32) -> Result<(), MultisigError> {
+            let caller = self.ensure_owner()?;
+
+            let mut proposal = self.proposals.get(id).ok_or(MultisigError::ProposalNotFound)?;
+
+            let index = proposal.approvals
+                .iter()
+                .position(|a| *a == caller)
+                .ok_or(MultisigError::NotApproved)?;
+
+            proposal.approvals.remove(index);
+            self.proposals.insert(id, &proposal);
+
+            Self::env().emit_event(ApprovalRevoked {
+                id,
+                owner: caller,
+            });
+
+            Ok(())
+        }
+
+        /// Runs a proposal once approved by at least `threshold` current owners
+        /// The called message must return a `Result`, an `Err` fails the execution
+        ///
+        /// Caller must be an owner
+        /// Transferred value is taken from the multisig balance
+        #[ink(message, payable)]
+        fn execute(&mut self, id: u32) -> Result<(), MultisigError> {
+            self.ensure_owner()?;
+
+            let proposal = self.proposals.take(id).ok_or(MultisigError::ProposalNotFound)?;
+
+            if self.count_approvals(&proposal) < self.threshold.get_or_default() {
+                return Err(MultisigError::ThresholdNotMet);
+            }
+
+            // Proposals targeting the multisig itself update its owners or threshold
+            let call_flags = if proposal.target == Self::env().account_id() {
+                CallFlags::default().set_allow_reentry(true)
+            } else {
+                CallFlags::default()
+            };
+
+            // Failing calls revert the whole execution, keeping the proposal and its approvals
+            call_utils::invoke(proposal.target, &proposal.call_data, proposal.transferred_value, call_flags)
+                .map_err(|_| MultisigError::CallFailed)?;
+
+            Self::env().emit_event(ProposalExecuted { id });
+
+            Ok(())
+        }
+
+        /// Discards a proposal
+        ///
+        /// Caller must be the proposer
+        #[ink(message)]
+        fn cancel(&mut self, id: u32) -> Result<(), MultisigError> {
+            let proposal = self.proposals.get(id).ok_or(MultisigError::ProposalNotFound)?;
+
+            if Self::env().caller() != proposal.proposer {
+                return Err(MultisigError::InvalidPermissions);
+            }
+
+            self.proposals.remove(id);
+
+            Self::env().emit_event(ProposalCancelled { id });
+
+            Ok(())
+        }
+
+        /// Caller must be the multisig itself
+        #[ink(message)]
+        fn set_threshold(&mut self, new_threshold: u16) -> Result<(), MultisigError> {
+            self.ensure_self_call()?;
+
+            if self.threshold.get_or_default() == new_threshold {
+                return Err(MultisigError::NoChange);
+            }
+            if new_threshold == 0 || new_threshold as usize > self.owners.get_or_default().len() {
+                return Err(MultisigError::InvalidThreshold);
+            }
+
+            self.threshold.set(&new_threshold);
+
+            Self::env().emit_event(ThresholdChanged { new_threshold });
+
+            Ok(())
+        }
+
+        /// Caller must be the multisig itself
+        #[ink(message)]
+        fn add_owner(&mut self, owner: AccountId) -> Result<(), MultisigError> {
+            self.ensure_self_call()?;
+
+            let mut owners = self.owners.get_or_default();
+
+            if owners.contains(&owner) {
+                return Err(MultisigError::NoChange);
+            }
+
+            owners.push(owner);
+            self.owners.set(&owners);
+
+            Self::env().emit_event(OwnerAdded { owner });
+
+            Ok(())
+        }
+
+        /// Remaining owners must still be able to meet the threshold
+        ///
+        /// Caller must be the multisig itself
+        #[ink(message)]
+        fn remove_owner(&mut self, owner: AccountId) -> Result<(), MultisigError> {
+            self.ensure_self_call()?;
+
+            let mut owners = self.owners.get_or_default();
+
+            let index = owners.iter().position(|o| *o == owner).ok_or(MultisigError::NoChange)?;
+            if owners.len() <= self.threshold.get_or_default() as usize {
+                return Err(MultisigError::InvalidThreshold);
+            }
+
+            owners.remove(index);
+            self.owners.set(&owners);
+
+            Self::env().emit_event(OwnerRemoved { owner });
+
+            Ok(())
+        }
+
+        /// Swaps an owner for a new account in place, keeping the threshold unchanged
+        ///
+        /// Caller must be the multisig itself
+        #[ink(message)]
+        fn replace_owner(&mut self, old_owner: AccountId, new_owner: AccountId) -> Result<(), MultisigError> {
+            self.ensure_self_call()?;
+
+            let mut owners = self.owners.get_or_default();
+
+            if owners.contains(&new_owner) {
+                return Err(MultisigError::InvalidInput);
+            }
+            let index = owners.iter().position(|o| *o == old_owner).ok_or(MultisigError::NoChange)?;
+
+            owners[index] = new_owner;
+            self.owners.set(&owners);
+
+            Self::env().emit_event(OwnerRemoved { owner: old_owner });
+            Self::env().emit_event(OwnerAdded { owner: new_owner });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_proposal(&self, id: u32) -> Option<Proposal> {
+            self.proposals.get(id)
+        }
+
+        /// Number of approvals from current owners
+        #[ink(message)]
+        fn get_approval_count(&self, id: u32) -> u16 {
+            match self.proposals.get(id) {
+                Some(proposal) => self.count_approvals(&proposal),
+                None => 0,
+            }
+        }
+
+        #[ink(message)]
+        fn get_owners(&self) -> Vec<AccountId> {
+            self.owners.get_or_default()
+        }
+
+        #[ink(message)]
+        fn get_threshold(&self) -> u16 {
+            self.threshold.get_or_default()
+        }
+    }
+}
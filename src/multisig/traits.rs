@@ -0,0 +1,50 @@
+use crate::errors::MultisigError;
+use crate::multisig::Proposal;
+use ink::{primitives::AccountId, prelude::vec::Vec};
+
+#[ink::trait_definition]
+pub trait IMultisig {
+    #[ink(message)]
+    fn propose(
+        &mut self,
+        target: AccountId,
+        call_data: Vec<u8>,
+        transferred_value: u128,
+    ) -> Result<u32, MultisigError>;
+
+    #[ink(message)]
+    fn approve(&mut self, id: u32) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn revoke_approval(&mut self, id: u32) -> Result<(), MultisigError>;
+
+    #[ink(message, payable)]
+    fn execute(&mut self, id: u32) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn cancel(&mut self, id: u32) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn set_threshold(&mut self, new_threshold: u16) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn add_owner(&mut self, owner: AccountId) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn remove_owner(&mut self, owner: AccountId) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn replace_owner(&mut self, old_owner: AccountId, new_owner: AccountId) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn get_proposal(&self, id: u32) -> Option<Proposal>;
+
+    #[ink(message)]
+    fn get_approval_count(&self, id: u32) -> u16;
+
+    #[ink(message)]
+    fn get_owners(&self) -> Vec<AccountId>;
+
+    #[ink(message)]
+    fn get_threshold(&self) -> u16;
+}
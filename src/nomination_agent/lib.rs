@@ -9,17 +9,45 @@ pub mod nomination_agent {
     use crate::data::{MultiAddress, RewardDestination, RuntimeCall, StakingCall};
     use crate::errors::RuntimeError;
     use crate::traits::INominationAgent;
-    use ink::env::Error as EnvError;
+    use ink::{env::Error as EnvError, prelude::vec::Vec, storage::Lazy};
+
+    // Version of the storage layout defined by this code, bumped whenever a migration is required
+    pub const STORAGE_VERSION: u16 = 2;
+
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum AgentStatus {
+        // Nominating and accepting deposits
+        Active,
+        // Weight removed by the registry, no new deposits
+        Disabled,
+        // Disabled and awaiting removal until its stake is fully withdrawn
+        Draining,
+        // Removed from the registry with the creation bond unbonding
+        Destroying,
+        // Creation bond withdrawn by the admin
+        Destroyed,
+    }
 
     #[ink(storage)]
     pub struct NominationAgent {
         vault: AccountId,
         registry: AccountId,
         admin: AccountId,
+        // First of the nominated validators
         validator: AccountId,
         staked: u128,
         unbonding: u128,
         creation_bond: u128,
+        // Kept outside of the packed fields so that it can be read from any layout
+        storage_version: Lazy<u16>,
+        // Currently nominated validators
+        validators: Lazy<Vec<AccountId>>,
+        // Lifecycle status and the time it was entered, Active when unset
+        status: Lazy<(AgentStatus, Timestamp)>,
     }
 
     impl From<EnvError> for RuntimeError {
@@ -36,9 +64,16 @@ pub mod nomination_agent {
         pub fn new(
             vault: AccountId,
             admin: AccountId,
-            validator: AccountId,
+            validators: Vec<AccountId>,
         ) -> Self {
             let creation_bond = Self::env().transferred_value();
+            let validator = *validators.first().expect("At least one validator");
+
+            let mut storage_version = Lazy::new();
+            storage_version.set(&STORAGE_VERSION);
+
+            let mut nominated = Lazy::new();
+            nominated.set(&validators);
 
             let nomination_agent = NominationAgent {
                 vault,
@@ -48,6 +83,9 @@ pub mod nomination_agent {
                 staked: 0,
                 unbonding: 0,
                 creation_bond,
+                storage_version,
+                validators: nominated,
+                status: Lazy::new(),
             };
 
             nomination_agent
@@ -61,7 +99,7 @@ pub mod nomination_agent {
             nomination_agent
                 .env()
                 .call_runtime(&RuntimeCall::Staking(StakingCall::Nominate {
-                    targets: [MultiAddress::Id(validator)].to_vec(),
+                    targets: validators.iter().map(|v| MultiAddress::Id(*v)).collect(),
                 }))
                 .unwrap();
 
@@ -79,7 +117,9 @@ pub mod nomination_agent {
                 return Err(RuntimeError::Unauthorized);
             }
 
-            self.staked += deposit_amount;
+            self.staked = self.staked
+                .checked_add(deposit_amount)
+                .ok_or(RuntimeError::Overflow)?;
 
             // Bond extra AZERO
             self.env()
@@ -97,8 +137,12 @@ pub mod nomination_agent {
                 return Err(RuntimeError::Unauthorized);
             }
 
-            self.staked -= amount;
-            self.unbonding += amount;
+            self.staked = self.staked
+                .checked_sub(amount)
+                .ok_or(RuntimeError::Underflow)?;
+            self.unbonding = self.unbonding
+                .checked_add(amount)
+                .ok_or(RuntimeError::Overflow)?;
 
             let balance_before = Self::env().balance();
 
@@ -108,13 +152,17 @@ pub mod nomination_agent {
                     value: amount,
                 }))?;
 
-            let withdrawn = Self::env().balance() - balance_before;
+            let withdrawn = Self::env().balance()
+                .checked_sub(balance_before)
+                .ok_or(RuntimeError::Underflow)?;
 
             if withdrawn > 0 {
                 // Typically this should be 0
                 // If unlocking requests equal `staking.maxUnlockingChunks`, some might be withdrawn
                 ink::env::debug_println!("Withdrawn {:?} AZERO", withdrawn);
-                self.unbonding -= withdrawn;
+                self.unbonding = self.unbonding
+                    .checked_sub(withdrawn)
+                    .ok_or(RuntimeError::Underflow)?;
                 Self::env().transfer(self.vault, withdrawn)?;
             }
 
@@ -141,12 +189,16 @@ pub mod nomination_agent {
                 return Ok(());
             };
 
-            let withdrawn = Self::env().balance() - balance_before;
+            let withdrawn = Self::env().balance()
+                .checked_sub(balance_before)
+                .ok_or(RuntimeError::Underflow)?;
             ink::env::debug_println!("Withdrawn {:?} AZERO", withdrawn);
 
             // Transfer withdrawn AZERO to vault
             if withdrawn > 0 {
-                self.unbonding -= withdrawn;
+                self.unbonding = self.unbonding
+                    .checked_sub(withdrawn)
+                    .ok_or(RuntimeError::Underflow)?;
                 Self::env().transfer(vault, withdrawn)?;
             }
 
@@ -174,10 +226,14 @@ pub mod nomination_agent {
                 }))
                 .ok();
 
-            let compounded = balance_before - Self::env().balance();
+            let compounded = balance_before
+                .checked_sub(Self::env().balance())
+                .ok_or(RuntimeError::Underflow)?;
 
             if compounded > 0 {
-                self.staked += compounded;
+                self.staked = self.staked
+                    .checked_add(compounded)
+                    .ok_or(RuntimeError::Overflow)?;
             }
 
             Ok(compounded)
@@ -203,11 +259,53 @@ pub mod nomination_agent {
             self.admin
         }
 
+        /// Deprecated in favour of `get_validators`, returns the first nominated validator
         #[ink(message)]
         fn get_validator(&self) -> AccountId {
             self.validator
         }
 
+        #[ink(message)]
+        fn get_validators(&self) -> Vec<AccountId> {
+            self.validators.get_or_default()
+        }
+
+        /// Lifecycle status as known to the agent and the time it was entered
+        /// Disabled and Draining are only tracked by the registry
+        #[ink(message)]
+        fn get_status(&self) -> (AgentStatus, Timestamp) {
+            self.status.get().unwrap_or((AgentStatus::Active, 0))
+        }
+
+        /// Replaces the current nominations with `validators`
+        /// Bonded AZERO moves to the new validators from the next era
+        ///
+        /// Can only be called by registry
+        #[ink(message)]
+        fn nominate(&mut self, validators: Vec<AccountId>) -> Result<(), RuntimeError> {
+            // Restricted to registry
+            if Self::env().caller() != self.registry {
+                return Err(RuntimeError::Unauthorized);
+            }
+
+            if validators.is_empty() {
+                return Err(RuntimeError::InvalidInput);
+            }
+            if self.validators.get_or_default() == validators {
+                return Err(RuntimeError::NoChange);
+            }
+
+            self.env()
+                .call_runtime(&RuntimeCall::Staking(StakingCall::Nominate {
+                    targets: validators.iter().map(|v| MultiAddress::Id(*v)).collect(),
+                }))?;
+
+            self.validator = validators[0];
+            self.validators.set(&validators);
+
+            Ok(())
+        }
+
         /// Step 1 of 2 in finalizing the agent's lifecycle
         /// Performs the following actions:
         ///     1) Removes the validator nomination
@@ -238,6 +336,7 @@ pub mod nomination_agent {
                 }))?;
 
             self.creation_bond = 0;
+            self.status.set(&(AgentStatus::Destroying, Self::env().block_timestamp()));
 
             Ok(())
         }
@@ -270,6 +369,8 @@ pub mod nomination_agent {
 
             Self::env().transfer(to, balance)?;
 
+            self.status.set(&(AgentStatus::Destroyed, Self::env().block_timestamp()));
+
             Ok(balance)
         }
 
@@ -287,5 +388,33 @@ pub mod nomination_agent {
 
             Ok(())
         }
+
+        #[ink(message, selector = 1000)]
+        fn migrate(&mut self) -> Result<(), RuntimeError> {
+            // Restricted to registry
+            if Self::env().caller() != self.registry {
+                return Err(RuntimeError::Unauthorized);
+            }
+
+            let from_version = self.storage_version.get().unwrap_or(0);
+            if from_version >= STORAGE_VERSION {
+                return Err(RuntimeError::NoChange);
+            }
+
+            // Migration steps are applied in order
+            if from_version < 2 {
+                // Validator list introduced in version 2
+                self.validators.set(&[self.validator].to_vec());
+            }
+
+            self.storage_version.set(&STORAGE_VERSION);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_storage_version(&self) -> u16 {
+            self.storage_version.get().unwrap_or(0)
+        }
     }
 }
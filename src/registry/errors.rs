@@ -1,18 +1,46 @@
 use ink::prelude::string::String;
+use nomination_agent::errors::RuntimeError;
 
 #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum RegistryError {
     InvalidInput,
     TooManyAgents,
+    TooManyValidators,
     AgentNotFound,
     AgentDisabled,
     ActiveAgent,
     InvalidPermissions,
     InvalidRole,
+    /// Maximum number of accounts holding the role reached
+    TooManyRoleMembers,
     NoChange,
+    CodeTimelocked,
+    NoPendingCode,
+    /// Same agent appears more than once in a batch
+    DuplicateAgent,
+    /// Validator is already nominated by another agent
+    DuplicateValidator,
+    /// Agent must be drained with no weight before removal
+    AgentNotDraining,
+    /// Admin has not yet withdrawn the agent's creation bond
+    BondNotReclaimed,
+    /// Maximum number of scheduled weight updates reached
+    TooManyPendingUpdates,
+    /// No pending scheduled weight update with the given id
+    WeightUpdateNotFound,
+    /// Total weight differs from the expected value
+    TotalWeightMismatch,
+    /// Agent would exceed the maximum share of the total weight
+    ConcentrationLimit,
+    /// Arithmetic operation would exceed the bounds of its type
+    Overflow,
+    /// Arithmetic operation would drop below zero
+    Underflow,
     /// An interaction with ink! environment has failed
     // NOTE: We're representing the `ink::env::Error` as `String` b/c the
     // type does not have Encode/Decode implemented.
     InkEnvError(String),
+    /// A nomination agent returned an error
+    AgentError(RuntimeError),
 }
\ No newline at end of file
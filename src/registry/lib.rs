@@ -9,13 +9,18 @@ pub mod registry {
     use crate::errors::RegistryError;
     use crate::traits::IRegistry;
     use ink::{
+        codegen::TraitCallBuilder,
         contract_ref,
         env::Error as InkEnvError,
-        prelude::{format, vec::Vec},
-        storage::Mapping,
+        prelude::{format, string::String, vec::Vec},
+        storage::{Lazy, Mapping},
         ToAccountId,
     };
-    use nomination_agent::{nomination_agent::NominationAgentRef, traits::INominationAgent};
+    use nomination_agent::{
+        errors::RuntimeError,
+        nomination_agent::{AgentStatus, NominationAgentRef},
+        traits::INominationAgent,
+    };
 
     impl From<InkEnvError> for RegistryError {
         fn from(e: InkEnvError) -> Self {
@@ -23,7 +28,24 @@ pub mod registry {
         }
     }
 
-    pub const MAX_AGENTS: usize = 50;
+    // Default maximum number of agents, adjustable by the SetLimits role
+    pub const MAX_AGENTS: u32 = 50;
+    // Matches `MaxNominations` of the staking pallet
+    pub const MAX_VALIDATORS: usize = 16;
+    // Maximum length of an agent label in bytes
+    pub const MAX_LABEL_LENGTH: usize = 32;
+    // Maximum number of scheduled weight update batches awaiting activation
+    pub const MAX_PENDING_WEIGHT_UPDATES: usize = 10;
+    // Number of most recent history entries kept in storage, older entries are pruned
+    pub const MAX_HISTORY_ENTRIES: u32 = 1000;
+    // Maximum number of accounts holding a single role
+    pub const MAX_ROLE_MEMBERS: usize = 10;
+    pub const DAY: u64 = 86400 * 1000;
+    // Minimum time between announcing and applying a code hash
+    pub const MIN_CODE_DELAY: u64 = DAY * 14;
+    // Version of the storage layout defined by this code, bumped whenever a migration is required
+    pub const STORAGE_VERSION: u16 = 1;
+    pub const BIPS: u16 = 10000;
 
     #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
     #[cfg_attr(
@@ -51,6 +73,10 @@ pub mod registry {
         RemoveAgent,
         // Permission to set code hash aka "upgrade" logic
         SetCodeHash,
+        // Permission to set limits such as the maximum agent share
+        SetLimits,
+        // Permission to re-enable disabled agents
+        EnableAgent,
     }
 
     #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
@@ -64,6 +90,43 @@ pub mod registry {
         pub disabled: bool,
     }
 
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct AgentMetadata {
+        pub label: String,
+        pub validators: Vec<AccountId>,
+        // Unknown (0) for agents added before metadata was recorded
+        pub created_at: Timestamp,
+        pub creation_bond: Balance,
+        pub admin: AccountId,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct AgentLifecycle {
+        pub status: AgentStatus,
+        // Time the current status was entered, 0 for agents migrated into their status
+        pub updated_at: Timestamp,
+        // Time the agent was removed from the registry, 0 while registered
+        pub retired_at: Timestamp,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct AgentOverview {
+        pub address: AccountId,
+        pub weight: u128,
+        pub disabled: bool,
+        pub staked: Balance,
+        pub unbonding: Balance,
+    }
+
     #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
     #[cfg_attr(
         feature = "std",
@@ -75,6 +138,83 @@ pub mod registry {
         pub increase: bool,
     }
 
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ScheduledWeightUpdates {
+        pub id: u32,
+        pub updates: Vec<WeightUpdate>,
+        // Time from which the updates are effective
+        pub activate_at: Timestamp,
+        // Account which scheduled the updates, recorded as the actor once they activate
+        pub scheduler: AccountId,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum HistoryAction {
+        WeightUpdated {
+            agent: AccountId,
+            old_weight: u128,
+            new_weight: u128,
+        },
+        WeightUpdatesScheduled {
+            id: u32,
+            activate_at: Timestamp,
+        },
+        WeightUpdatesCancelled {
+            id: u32,
+        },
+        RoleAccountChanged {
+            role_type: RoleType,
+            new_account: AccountId,
+        },
+        RoleAdminChanged {
+            role_type: RoleType,
+            new_account: AccountId,
+        },
+        RoleRenounced {
+            role_type: RoleType,
+            account: AccountId,
+        },
+        RoleGranted {
+            role_type: RoleType,
+            account: AccountId,
+        },
+        RoleRevoked {
+            role_type: RoleType,
+            account: AccountId,
+        },
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct HistoryEntry {
+        pub timestamp: Timestamp,
+        // Caller of the message which made the change
+        pub actor: AccountId,
+        pub action: HistoryAction,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct CodeHashAnnouncement {
+        pub code_hash: [u8; 32],
+        // Earliest time the code hash can be applied
+        pub apply_after: Timestamp,
+    }
+
     #[ink(event)]
     pub struct AgentAdded {
         #[ink(topic)]
@@ -88,12 +228,59 @@ pub mod registry {
         new_weight: u128,
     }
     #[ink(event)]
+    pub struct WeightUpdatesScheduled {
+        id: u32,
+        activate_at: Timestamp,
+    }
+    #[ink(event)]
+    pub struct WeightUpdatesCancelled {
+        id: u32,
+    }
+    #[ink(event)]
+    pub struct WeightUpdatesActivated {
+        id: u32,
+    }
+    #[ink(event)]
+    pub struct WeightUpdatesSkipped {
+        id: u32,
+    }
+    #[ink(event)]
     pub struct AgentDisabled {
         #[ink(topic)]
         agent: AccountId,
         old_weight: u128,
     }
     #[ink(event)]
+    pub struct AgentEnabled {
+        #[ink(topic)]
+        agent: AccountId,
+        new_weight: u128,
+    }
+    #[ink(event)]
+    pub struct AgentStatusChanged {
+        #[ink(topic)]
+        agent: AccountId,
+        status: AgentStatus,
+    }
+    #[ink(event)]
+    pub struct AgentValidatorsUpdated {
+        #[ink(topic)]
+        agent: AccountId,
+        validators: Vec<AccountId>,
+    }
+    #[ink(event)]
+    pub struct AgentLabelUpdated {
+        #[ink(topic)]
+        agent: AccountId,
+        label: String,
+    }
+    #[ink(event)]
+    pub struct AgentCodeUpdated {
+        #[ink(topic)]
+        agent: AccountId,
+        code_hash: [u8; 32],
+    }
+    #[ink(event)]
     pub struct AgentDeleted {
         #[ink(topic)]
         agent: AccountId,
@@ -104,33 +291,150 @@ pub mod registry {
         new_account: AccountId,
     }
     #[ink(event)]
+    pub struct RoleGranted {
+        role_type: RoleType,
+        account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleRevoked {
+        role_type: RoleType,
+        account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleAccountProposed {
+        role_type: RoleType,
+        new_account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleAccountProposalCancelled {
+        role_type: RoleType,
+        new_account: AccountId,
+    }
+    #[ink(event)]
     pub struct RoleAdminChanged {
         role_type: RoleType,
         new_account: AccountId,
     }
+    #[ink(event)]
+    pub struct RoleAdminProposed {
+        role_type: RoleType,
+        new_account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleAdminProposalCancelled {
+        role_type: RoleType,
+        new_account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleRenounced {
+        role_type: RoleType,
+        account: AccountId,
+    }
+    #[ink(event)]
+    pub struct NewCodeHashAnnounced {
+        code_hash: [u8; 32],
+        apply_after: Timestamp,
+    }
+    #[ink(event)]
+    pub struct NewAgentCodeHashAnnounced {
+        code_hash: [u8; 32],
+        apply_after: Timestamp,
+    }
+    #[ink(event)]
+    pub struct CodeHashApplied {
+        code_hash: [u8; 32],
+    }
+    #[ink(event)]
+    pub struct AgentCodeHashApplied {
+        code_hash: [u8; 32],
+    }
+    #[ink(event)]
+    pub struct CodeHashAnnouncementCancelled {
+        code_hash: [u8; 32],
+    }
+    #[ink(event)]
+    pub struct CodeDelayChanged {
+        new_delay: u64,
+    }
+    #[ink(event)]
+    pub struct MaxAgentShareChanged {
+        new_max_share: u16,
+    }
+    #[ink(event)]
+    pub struct MaxAgentsChanged {
+        new_max_agents: u32,
+    }
+    #[ink(event)]
+    pub struct StorageMigrated {
+        from_version: u16,
+        to_version: u16,
+    }
 
     #[ink(storage)]
     pub struct Registry {
-        // List of all nomination agents including their deployment and relative weight
-        pub agents: Vec<Agent>,
+        // Agents of deployments which predate storage versioning, empty once migrated to `agents`
+        pub legacy_agents: Vec<Agent>,
         // Sum of nomination agent relative weights
         pub total_weight: u128,
         // Permissions for adding agents, updating weights, and removing agents
         pub roles: Mapping<RoleType, Role>,
+        // Accounts holding each role including the role account, only the role account when unset
+        pub role_members: Mapping<RoleType, Vec<AccountId>>,
+        // Accounts proposed to take over each role, which must accept before the transfer completes
+        pub pending_roles: Mapping<RoleType, AccountId>,
+        // Accounts proposed to take over administration of each role
+        pub pending_role_admins: Mapping<RoleType, AccountId>,
         // Used for instantiating agents
         pub vault: AccountId,
         pub nomination_agent_hash: Hash,
         pub nomination_agent_counter: u128,
+        // Registry code hash awaiting `code_delay` before it can be applied
+        pub pending_code: Lazy<Option<CodeHashAnnouncement>>,
+        // Nomination agent code hash awaiting `code_delay` before it can be applied
+        pub pending_agent_code: Lazy<Option<CodeHashAnnouncement>>,
+        // Time between announcing and applying a code hash, `MIN_CODE_DELAY` when unset
+        pub code_delay: Lazy<u64>,
+        // Version of the storage layout, kept outside of the packed fields so that it can be read from any layout
+        pub storage_version: Lazy<u16>,
+        // Maximum portion of the total weight a single agent can hold in bips, no limit when unset
+        pub max_agent_share: Lazy<u16>,
+        // Descriptive details of each agent
+        pub agent_metadata: Mapping<AccountId, AgentMetadata>,
+        // All nomination agents including their deployment and relative weight
+        pub agents: Mapping<AccountId, Agent>,
+        // Agent addresses in the order they were added, from 0 to `agent_count`
+        pub agent_index: Mapping<u32, AccountId>,
+        pub agent_count: Lazy<u32>,
+        // Maximum number of agents, `MAX_AGENTS` when unset
+        pub max_agents: Lazy<u32>,
+        // Agents nominating each validator, more than one only when duplicates were explicitly allowed
+        pub validator_agents: Mapping<AccountId, Vec<AccountId>>,
+        // Code hash each agent is running, which differs from `nomination_agent_hash` during a staged rollout
+        pub agent_code_hashes: Mapping<AccountId, Hash>,
+        // Lifecycle of every agent ever added, kept after removal
+        pub agent_lifecycles: Mapping<AccountId, AgentLifecycle>,
+        // Removed agents in the order they were removed
+        pub retired_agents: Lazy<Vec<AccountId>>,
+        // Weight updates awaiting activation, ordered by activation time
+        pub pending_weight_updates: Lazy<Vec<ScheduledWeightUpdates>>,
+        pub weight_update_counter: Lazy<u32>,
+        // Most recent weight and role changes, keyed by their position in the full history
+        pub history: Mapping<u32, HistoryEntry>,
+        // Number of entries ever recorded, including pruned ones
+        pub history_count: Lazy<u32>,
     }
 
     impl Registry {
         #[ink(constructor)]
+        #[allow(clippy::too_many_arguments)]
         pub fn new(
             role_add: AccountId,
             role_update: AccountId,
             role_disable: AccountId,
+            role_enable: AccountId,
             role_remove: AccountId,
             role_set_code_hash: AccountId,
+            role_set_limits: AccountId,
             nomination_agent_hash: Hash,
         ) -> Self {
             let mut initial_roles = Mapping::default();
@@ -155,6 +459,13 @@ pub mod registry {
                     account: role_disable,
                 },
             );
+            initial_roles.insert(
+                RoleType::EnableAgent,
+                &Role {
+                    admin: role_enable,
+                    account: role_enable,
+                },
+            );
             initial_roles.insert(
                 RoleType::RemoveAgent,
                 &Role {
@@ -169,143 +480,713 @@ pub mod registry {
                     account: role_set_code_hash,
                 },
             );
+            initial_roles.insert(
+                RoleType::SetLimits,
+                &Role {
+                    admin: role_set_limits,
+                    account: role_set_limits,
+                },
+            );
+
+            let mut storage_version = Lazy::new();
+            storage_version.set(&STORAGE_VERSION);
 
             Self {
-                agents: Vec::new(),
+                legacy_agents: Vec::new(),
                 total_weight: 0,
                 roles: initial_roles,
+                role_members: Mapping::default(),
+                pending_roles: Mapping::default(),
+                pending_role_admins: Mapping::default(),
                 vault: Self::env().caller(),
                 nomination_agent_hash,
                 nomination_agent_counter: 0,
+                pending_code: Lazy::new(),
+                pending_agent_code: Lazy::new(),
+                code_delay: Lazy::new(),
+                storage_version,
+                max_agent_share: Lazy::new(),
+                agent_metadata: Mapping::default(),
+                agents: Mapping::default(),
+                agent_index: Mapping::default(),
+                agent_count: Lazy::new(),
+                max_agents: Lazy::new(),
+                validator_agents: Mapping::default(),
+                agent_code_hashes: Mapping::default(),
+                agent_lifecycles: Mapping::default(),
+                retired_agents: Lazy::new(),
+                pending_weight_updates: Lazy::new(),
+                weight_update_counter: Lazy::new(),
+                history: Mapping::default(),
+                history_count: Lazy::new(),
             }
         }
-    }
 
-    impl IRegistry for Registry {
-        /// Add a new nomination agent
-        ///
-        /// Caller must have the AddAgent role.
-        /// Cannot add the same nomination agent twice.
-        #[ink(message, payable, selector = 1)]
-        fn add_agent(
-            &mut self,
-            admin: AccountId,
-            validator: AccountId,
-        ) -> Result<AccountId, RegistryError> {
-            let caller = Self::env().caller();
-            let nominator_bond = Self::env().transferred_value();
+        /// Addresses of up to `len` agents starting at index `start`
+        fn agent_addresses(&self, start: u32, len: u32) -> Vec<AccountId> {
+            let end = start.saturating_add(len).min(self.agent_count.get_or_default());
+            (start..end).filter_map(|i| self.agent_index.get(i)).collect()
+        }
 
-            if caller != self.roles.get(RoleType::AddAgent).unwrap().account {
-                return Err(RegistryError::InvalidPermissions);
+        /// All agents in the order they were added
+        fn load_agents(&self) -> Vec<Agent> {
+            self.agent_addresses(0, u32::MAX)
+                .into_iter()
+                .filter_map(|address| self.agents.get(address))
+                .collect()
+        }
+
+        fn push_agent(&mut self, agent: Agent) -> Result<(), RegistryError> {
+            let count = self.agent_count.get_or_default();
+            self.agent_index.insert(count, &agent.address);
+            self.agents.insert(agent.address, &agent);
+            self.agent_count.set(&count.checked_add(1).ok_or(RegistryError::Overflow)?);
+            Ok(())
+        }
+
+        /// Removes an agent while preserving the order of the remaining agents
+        fn delete_agent(&mut self, agent: AccountId) -> Result<(), RegistryError> {
+            let count = self.agent_count.get_or_default();
+            let position = (0..count)
+                .find(|i| self.agent_index.get(i) == Some(agent))
+                .ok_or(RegistryError::AgentNotFound)?;
+
+            for i in position..count - 1 {
+                let next = self.agent_index.get(i + 1).ok_or(RegistryError::AgentNotFound)?;
+                self.agent_index.insert(i, &next);
             }
+            self.agent_index.remove(count - 1);
+            self.agents.remove(agent);
+            self.agent_count.set(&(count - 1));
+            Ok(())
+        }
 
-            if self.agents.len() >= MAX_AGENTS {
-                return Err(RegistryError::TooManyAgents);
+        /// Validators must be unique, at least one and at most `MAX_VALIDATORS`
+        fn ensure_validators(validators: &[AccountId]) -> Result<(), RegistryError> {
+            if validators.is_empty() {
+                return Err(RegistryError::InvalidInput);
             }
+            if validators.len() > MAX_VALIDATORS {
+                return Err(RegistryError::TooManyValidators);
+            }
+            for (i, validator) in validators.iter().enumerate() {
+                if validators[..i].contains(validator) {
+                    return Err(RegistryError::InvalidInput);
+                }
+            }
+            Ok(())
+        }
 
-            let nomination_agent_counter = self.nomination_agent_counter; // shadow
+        /// Fails if an agent other than `agent` already nominates one of `validators`
+        fn ensure_unique_validators(
+            &self,
+            agent: Option<AccountId>,
+            validators: &[AccountId],
+        ) -> Result<(), RegistryError> {
+            for validator in validators.iter() {
+                let agents = self.validator_agents.get(validator).unwrap_or_default();
+                if agents.iter().any(|a| Some(*a) != agent) {
+                    return Err(RegistryError::DuplicateValidator);
+                }
+            }
+            Ok(())
+        }
 
-            let agent_ref = NominationAgentRef::new(
-                self.vault,
-                admin,
-                validator,
-            )
-            .endowment(nominator_bond)
-            .code_hash(self.nomination_agent_hash)
-            .salt_bytes(nomination_agent_counter.to_le_bytes())
-            .instantiate();
+        fn index_validators(&mut self, agent: AccountId, validators: &[AccountId]) {
+            for validator in validators.iter() {
+                let mut agents = self.validator_agents.get(validator).unwrap_or_default();
+                if !agents.contains(&agent) {
+                    agents.push(agent);
+                    self.validator_agents.insert(validator, &agents);
+                }
+            }
+        }
 
-            let agent_address = NominationAgentRef::to_account_id(&agent_ref);
+        fn unindex_validators(&mut self, agent: AccountId, validators: &[AccountId]) {
+            for validator in validators.iter() {
+                let mut agents = self.validator_agents.get(validator).unwrap_or_default();
+                agents.retain(|a| *a != agent);
+                if agents.is_empty() {
+                    self.validator_agents.remove(validator);
+                } else {
+                    self.validator_agents.insert(validator, &agents);
+                }
+            }
+        }
 
-            self.nomination_agent_counter = nomination_agent_counter + 1;
+        /// Ensures no enabled agent holds more than `max_agent_share` of the total weight
+        fn ensure_concentration(&self) -> Result<(), RegistryError> {
+            self.ensure_concentration_of(self.total_weight, &self.load_agents())
+        }
 
-            self.agents.push(Agent {
-                address: agent_address,
-                weight: 0,
-                disabled: false,
-            });
+        fn ensure_concentration_of(&self, total_weight: u128, agents: &[Agent]) -> Result<(), RegistryError> {
+            let max_agent_share = match self.max_agent_share.get() {
+                Some(max_agent_share) => max_agent_share as u128,
+                None => return Ok(()),
+            };
+            let max_weight = total_weight
+                .checked_mul(max_agent_share)
+                .ok_or(RegistryError::Overflow)?;
+
+            for agent in agents.iter() {
+                let weight = agent.weight
+                    .checked_mul(BIPS as u128)
+                    .ok_or(RegistryError::Overflow)?;
+                if weight > max_weight {
+                    return Err(RegistryError::ConcentrationLimit);
+                }
+            }
 
-            Self::env().emit_event(AgentAdded {
-                agent: agent_address,
-            });
+            Ok(())
+        }
 
-            Ok(agent_address)
+        fn summed_weight(agents: &[Agent]) -> Result<u128, RegistryError> {
+            agents
+                .iter()
+                .try_fold(0_u128, |total, agent| total.checked_add(agent.weight))
+                .ok_or(RegistryError::Overflow)
         }
 
-        /// Update weight of existing nomination agents
-        ///
-        /// Caller must have the UpdateAgents role.
-        /// Cannot update a disabled agent.
-        #[ink(message, selector = 2)]
-        fn update_agents(
-            &mut self,
-            weight_updates: Vec<WeightUpdate>,
+        fn apply_weight_update(
+            agent: &mut Agent,
+            total_weight: &mut u128,
+            update: &WeightUpdate,
         ) -> Result<(), RegistryError> {
-            let caller = Self::env().caller();
+            if agent.disabled {
+                return Err(RegistryError::AgentDisabled);
+            }
 
-            if caller != self.roles.get(RoleType::UpdateAgents).unwrap().account {
-                return Err(RegistryError::InvalidPermissions);
+            if update.increase {
+                agent.weight = agent.weight
+                    .checked_add(update.weight)
+                    .ok_or(RegistryError::Overflow)?;
+                *total_weight = total_weight
+                    .checked_add(update.weight)
+                    .ok_or(RegistryError::Overflow)?;
+            } else {
+                if update.weight > agent.weight {
+                    return Err(RegistryError::InvalidInput);
+                }
+                agent.weight -= update.weight;
+                *total_weight = total_weight
+                    .checked_sub(update.weight)
+                    .ok_or(RegistryError::Underflow)?;
             }
 
-            for update in weight_updates.iter() {
-                if let Some(index) = self.agents.iter().position(|a| a.address == update.agent) {
-                    if self.agents[index].disabled {
-                        return Err(RegistryError::AgentDisabled);
+            Ok(())
+        }
+
+        /// Applies a batch of updates on top of the already `changed` agents
+        /// Leaves `total_weight` and `changed` untouched if any update fails or the result exceeds `max_agent_share`
+        fn apply_weight_updates(
+            &self,
+            total_weight: &mut u128,
+            changed: &mut Vec<Agent>,
+            updates: &[WeightUpdate],
+        ) -> Result<(), RegistryError> {
+            let mut new_total_weight = *total_weight;
+            let mut new_changed = changed.clone();
+
+            for update in updates.iter() {
+                let position = match new_changed.iter().position(|a| a.address == update.agent) {
+                    Some(position) => position,
+                    None => {
+                        new_changed.push(self.agents.get(update.agent).ok_or(RegistryError::AgentNotFound)?);
+                        new_changed.len() - 1
                     }
+                };
+                Self::apply_weight_update(&mut new_changed[position], &mut new_total_weight, update)?;
+            }
 
-                    let old_weight = self.agents[index].weight;
-                    let new_weight;
+            // Checked against the limit in effect when the batch applies, which may have changed since scheduling
+            let agents: Vec<Agent> = self.load_agents()
+                .into_iter()
+                .map(|a| Self::with_due_weight(a, &new_changed))
+                .collect();
+            self.ensure_concentration_of(new_total_weight, &agents)?;
 
-                    if update.increase {
-                        new_weight = old_weight + update.weight;
-                        self.total_weight += update.weight;
-                    } else {
-                        if update.weight > old_weight {
-                            return Err(RegistryError::InvalidInput);
-                        }
-                        new_weight = old_weight - update.weight;
-                        self.total_weight -= update.weight;
-                    };
+            *total_weight = new_total_weight;
+            *changed = new_changed;
+            Ok(())
+        }
+
+        /// Stored agent with any of the `changed` weights applied
+        fn with_due_weight(agent: Agent, changed: &[Agent]) -> Agent {
+            changed
+                .iter()
+                .find(|a| a.address == agent.address)
+                .cloned()
+                .unwrap_or(agent)
+        }
+
+        /// Persists the scheduled weight updates which are due, returning the number of batches processed
+        /// Each batch is recorded in the history under the account which scheduled it.
+        /// A batch which can no longer be applied, such as one updating a removed agent or exceeding `max_agent_share`, is skipped as a whole.
+        fn persist_weight_updates(&mut self) -> u32 {
+            let now = Self::env().block_timestamp();
+            let mut pending = self.pending_weight_updates.get_or_default();
+            let due = pending.iter().take_while(|s| s.activate_at <= now).count();
+            if due == 0 {
+                return 0;
+            }
+
+            for scheduled in pending.drain(..due) {
+                let mut total_weight = self.total_weight;
+                let mut changed = Vec::new();
+                if self.apply_weight_updates(&mut total_weight, &mut changed, &scheduled.updates).is_err() {
+                    Self::env().emit_event(WeightUpdatesSkipped { id: scheduled.id });
+                    continue;
+                }
 
-                    self.agents[index].weight = new_weight;
+                for agent in changed.iter() {
+                    let old_weight = self.agents.get(agent.address).map(|a| a.weight).unwrap_or(0);
+                    self.agents.insert(agent.address, agent);
+                    self.record_as(scheduled.scheduler, HistoryAction::WeightUpdated {
+                        agent: agent.address,
+                        old_weight,
+                        new_weight: agent.weight,
+                    });
 
                     Self::env().emit_event(AgentUpdated {
-                        agent: update.agent,
+                        agent: agent.address,
                         old_weight,
-                        new_weight,
+                        new_weight: agent.weight,
                     });
-                } else {
-                    return Err(RegistryError::AgentNotFound);
                 }
+                self.total_weight = total_weight;
+
+                Self::env().emit_event(WeightUpdatesActivated { id: scheduled.id });
             }
+            self.pending_weight_updates.set(&pending);
 
-            Ok(())
+            due as u32
         }
 
-        /// Begins the process of offboarding a nomination agent
-        /// Permanently removes weight allocation from a given agent.
-        /// This allows dynamic registry weights and offboarding to co-exist.
-        ///
-        /// Caller must have the DisableAgent role.
-        #[ink(message, selector = 5)]
-        fn disable_agent(&mut self, agent: AccountId) -> Result<(), RegistryError> {
-            let caller = Self::env().caller();
+        /// Accounts holding a role, starting with the role account
+        fn load_role_members(&self, role_type: RoleType, role: &Role) -> Vec<AccountId> {
+            self.role_members
+                .get(role_type)
+                .unwrap_or_else(|| Vec::from([role.account]))
+        }
 
-            if caller != self.roles.get(RoleType::DisableAgent).unwrap().account {
-                return Err(RegistryError::InvalidPermissions);
+        fn is_member(&self, role_type: RoleType, account: AccountId) -> bool {
+            match self.roles.get(role_type.clone()) {
+                Some(role) => self.load_role_members(role_type, &role).contains(&account),
+                None => false,
             }
+        }
 
-            if let Some(index) = self.agents.iter().position(|a| a.address == agent) {
-                if self.agents[index].disabled {
-                    return Err(RegistryError::NoChange);
-                }
+        /// Removes an account from a role
+        /// The role account passes to the next member, or back to the admin when none remain.
+        fn remove_role_member(&mut self, role_type: RoleType, role: &mut Role, account: AccountId) {
+            let mut members = self.load_role_members(role_type.clone(), role);
+            members.retain(|m| *m != account);
+            if members.is_empty() {
+                members.push(role.admin);
+            }
+            if role.account == account {
+                role.account = members[0];
+            }
+            self.role_members.insert(role_type.clone(), &members);
+            self.roles.insert(role_type, role);
+        }
 
-                let old_weight = self.agents[index].weight;
-                self.agents[index].weight = 0;
-                self.agents[index].disabled = true;
-                self.total_weight -= old_weight;
+        fn record(&mut self, action: HistoryAction) {
+            self.record_as(Self::env().caller(), action);
+        }
 
-                Self::env().emit_event(AgentDisabled { agent, old_weight });
+        /// Appends to the history, pruning the oldest entry once `MAX_HISTORY_ENTRIES` are kept
+        fn record_as(&mut self, actor: AccountId, action: HistoryAction) {
+            let count = self.history_count.get_or_default();
+            self.history.insert(count, &HistoryEntry {
+                timestamp: Self::env().block_timestamp(),
+                actor,
+                action,
+            });
+            if count >= MAX_HISTORY_ENTRIES {
+                self.history.remove(count - MAX_HISTORY_ENTRIES);
+            }
+            self.history_count.set(&count.saturating_add(1));
+        }
+
+        fn set_status(&mut self, agent: AccountId, status: AgentStatus) {
+            let now = Self::env().block_timestamp();
+            let retired_at = match status {
+                AgentStatus::Destroying => now,
+                _ => self.agent_lifecycles.get(agent).map(|l| l.retired_at).unwrap_or(0),
+            };
+            self.agent_lifecycles.insert(agent, &AgentLifecycle {
+                status,
+                updated_at: now,
+                retired_at,
+            });
+
+            Self::env().emit_event(AgentStatusChanged { agent, status });
+        }
+
+        /// Sets the code hash of a single agent, returning any failure instead of reverting
+        fn set_code_of(&mut self, agent: AccountId, code_hash: [u8; 32]) -> Result<(), RegistryError> {
+            if !self.agents.contains(agent) {
+                return Err(RegistryError::AgentNotFound);
+            }
+            if self.agent_code_hashes.get(agent) == Some(Hash::from(code_hash)) {
+                return Err(RegistryError::NoChange);
+            }
+
+            let mut agent_contract: contract_ref!(INominationAgent) = agent.into();
+            agent_contract
+                .call_mut()
+                .set_code(code_hash)
+                .try_invoke()?
+                .map_err(|e| RegistryError::InkEnvError(format!("{:?}", e)))?
+                .map_err(RegistryError::AgentError)?;
+
+            self.agent_code_hashes.insert(agent, &Hash::from(code_hash));
+
+            Self::env().emit_event(AgentCodeUpdated {
+                agent,
+                code_hash,
+            });
+
+            Ok(())
+        }
+
+        /// Sets the code hash of each agent, collecting the agents that failed along with their error
+        fn set_code_for(&mut self, agents: Vec<AccountId>, code_hash: [u8; 32]) -> Vec<(AccountId, RegistryError)> {
+            let mut failures = Vec::new();
+            for agent in agents {
+                match self.set_code_of(agent, code_hash) {
+                    Ok(()) | Err(RegistryError::NoChange) => (),
+                    Err(e) => failures.push((agent, e)),
+                }
+            }
+            failures
+        }
+
+        fn get_apply_after(&self) -> Result<Timestamp, RegistryError> {
+            Self::env()
+                .block_timestamp()
+                .checked_add(self.get_code_delay())
+                .ok_or(RegistryError::Overflow)
+        }
+    }
+
+    impl IRegistry for Registry {
+        /// Add a new nomination agent
+        ///
+        /// Caller must have the AddAgent role.
+        /// Cannot add the same nomination agent twice.
+        /// Validators already nominated by another agent are rejected unless `allow_duplicate_validators` is set.
+        #[ink(message, payable, selector = 1)]
+        fn add_agent(
+            &mut self,
+            admin: AccountId,
+            validators: Vec<AccountId>,
+            allow_duplicate_validators: bool,
+        ) -> Result<AccountId, RegistryError> {
+            let caller = Self::env().caller();
+            let nominator_bond = Self::env().transferred_value();
+
+            if !self.is_member(RoleType::AddAgent, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            if self.agent_count.get_or_default() >= self.get_max_agents() {
+                return Err(RegistryError::TooManyAgents);
+            }
+
+            Self::ensure_validators(&validators)?;
+            if !allow_duplicate_validators {
+                self.ensure_unique_validators(None, &validators)?;
+            }
+
+            let nomination_agent_counter = self.nomination_agent_counter; // shadow
+
+            let agent_ref = NominationAgentRef::new(
+                self.vault,
+                admin,
+                validators.clone(),
+            )
+            .endowment(nominator_bond)
+            .code_hash(self.nomination_agent_hash)
+            .salt_bytes(nomination_agent_counter.to_le_bytes())
+            .instantiate();
+
+            let agent_address = NominationAgentRef::to_account_id(&agent_ref);
+
+            self.nomination_agent_counter = nomination_agent_counter
+                .checked_add(1)
+                .ok_or(RegistryError::Overflow)?;
+
+            self.push_agent(Agent {
+                address: agent_address,
+                weight: 0,
+                disabled: false,
+            })?;
+            self.index_validators(agent_address, &validators);
+            self.agent_code_hashes.insert(agent_address, &self.nomination_agent_hash);
+            self.set_status(agent_address, AgentStatus::Active);
+            self.agent_metadata.insert(agent_address, &AgentMetadata {
+                label: String::new(),
+                validators,
+                created_at: Self::env().block_timestamp(),
+                creation_bond: nominator_bond,
+                admin,
+            });
+
+            Self::env().emit_event(AgentAdded {
+                agent: agent_address,
+            });
+
+            Ok(agent_address)
+        }
+
+        /// Update weight of existing nomination agents
+        ///
+        /// Caller must have the UpdateAgents role.
+        /// Cannot update a disabled agent.
+        /// Resulting weights cannot exceed `max_agent_share` of the total weight.
+        #[ink(message, selector = 2)]
+        fn update_agents(
+            &mut self,
+            weight_updates: Vec<WeightUpdate>,
+        ) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::UpdateAgents, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            self.persist_weight_updates();
+
+            for update in weight_updates.iter() {
+                let mut agent = self.agents.get(update.agent).ok_or(RegistryError::AgentNotFound)?;
+
+                let old_weight = agent.weight;
+                Self::apply_weight_update(&mut agent, &mut self.total_weight, update)?;
+                self.agents.insert(update.agent, &agent);
+                self.record(HistoryAction::WeightUpdated {
+                    agent: update.agent,
+                    old_weight,
+                    new_weight: agent.weight,
+                });
+
+                Self::env().emit_event(AgentUpdated {
+                    agent: update.agent,
+                    old_weight,
+                    new_weight: agent.weight,
+                });
+            }
+
+            self.ensure_concentration()?;
+
+            Ok(())
+        }
+
+        /// Schedule weight updates of existing nomination agents to take effect at `activate_at`
+        /// Updates are applied in order of activation time, by `activate_weight_updates` or the first time the registry is modified after `activate_at`.
+        /// Updates are applied in order of activation time, the first time the registry is used after `activate_at`.
+        ///
+        /// Caller must have the UpdateAgents role.
+        /// Updates must be valid against the weights expected once every earlier pending batch is applied.
+        /// Resulting weights cannot exceed `max_agent_share` of the total weight.
+        /// The batch is skipped if it exceeds `max_agent_share` in effect when it activates.
+        #[ink(message)]
+        fn schedule_weight_updates(
+            &mut self,
+            weight_updates: Vec<WeightUpdate>,
+            activate_at: Timestamp,
+        ) -> Result<u32, RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::UpdateAgents, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            self.persist_weight_updates();
+
+            if weight_updates.is_empty() || activate_at <= Self::env().block_timestamp() {
+                return Err(RegistryError::InvalidInput);
+            }
+
+            let mut pending = self.pending_weight_updates.get_or_default();
+            if pending.len() >= MAX_PENDING_WEIGHT_UPDATES {
+                return Err(RegistryError::TooManyPendingUpdates);
+            }
+
+            let id = self.weight_update_counter.get_or_default();
+            let position = pending
+                .iter()
+                .position(|s| s.activate_at > activate_at)
+                .unwrap_or(pending.len());
+            pending.insert(position, ScheduledWeightUpdates {
+                id,
+                updates: weight_updates,
+                activate_at,
+                scheduler: caller,
+            });
+
+            // Earlier batches which no longer apply are skipped on activation rather than blocking this one
+            let mut total_weight = self.total_weight;
+            let mut changed = Vec::new();
+            for scheduled in pending.iter() {
+                let result = self.apply_weight_updates(&mut total_weight, &mut changed, &scheduled.updates);
+                if scheduled.id == id {
+                    result?;
+                }
+            }
+
+            self.pending_weight_updates.set(&pending);
+            self.weight_update_counter.set(&id.checked_add(1).ok_or(RegistryError::Overflow)?);
+            self.record(HistoryAction::WeightUpdatesScheduled { id, activate_at });
+
+            Self::env().emit_event(WeightUpdatesScheduled { id, activate_at });
+
+            Ok(id)
+        }
+
+        /// Discards a batch of scheduled weight updates before it activates
+        ///
+        /// Caller must have the UpdateAgents role.
+        #[ink(message)]
+        fn cancel_weight_updates(&mut self, id: u32) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::UpdateAgents, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            self.persist_weight_updates();
+
+            let mut pending = self.pending_weight_updates.get_or_default();
+            let position = pending
+                .iter()
+                .position(|s| s.id == id)
+                .ok_or(RegistryError::WeightUpdateNotFound)?;
+            pending.remove(position);
+            self.pending_weight_updates.set(&pending);
+            self.record(HistoryAction::WeightUpdatesCancelled { id });
+
+            Self::env().emit_event(WeightUpdatesCancelled { id });
+
+            Ok(())
+        }
+
+        /// Persists the scheduled weight updates which are due
+        /// Returns the number of batches activated or skipped.
+        ///
+        /// Callable by anyone, the vault calls it before bonding and unbonding.
+        #[ink(message)]
+        fn activate_weight_updates(&mut self) -> u32 {
+            self.persist_weight_updates()
+        }
+
+        /// Scheduled weight updates which have not yet been persisted, including those which are due
+        #[ink(message)]
+        fn get_pending_weight_updates(&self) -> Vec<ScheduledWeightUpdates> {
+            self.pending_weight_updates.get_or_default()
+        }
+
+        /// Set absolute weights of existing nomination agents
+        /// Agents not included keep their current weight
+        ///
+        /// Caller must have the UpdateAgents role.
+        /// Cannot update a disabled agent or the same agent twice.
+        /// Resulting weights cannot exceed `max_agent_share` of the total weight.
+        /// Fails if `expected_total_weight` is given and differs from the current total weight.
+        #[ink(message)]
+        fn set_weights(
+            &mut self,
+            weights: Vec<(AccountId, u128)>,
+            expected_total_weight: Option<u128>,
+        ) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::UpdateAgents, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            self.persist_weight_updates();
+            if let Some(expected) = expected_total_weight {
+                if expected != self.total_weight {
+                    return Err(RegistryError::TotalWeightMismatch);
+                }
+            }
+
+            for (i, (agent, new_weight)) in weights.iter().enumerate() {
+                if weights[..i].iter().any(|(a, _)| a == agent) {
+                    return Err(RegistryError::DuplicateAgent);
+                }
+
+                let mut details = self.agents.get(agent).ok_or(RegistryError::AgentNotFound)?;
+                if details.disabled {
+                    return Err(RegistryError::AgentDisabled);
+                }
+
+                let old_weight = details.weight;
+                if old_weight == *new_weight {
+                    continue;
+                }
+
+                details.weight = *new_weight;
+                self.agents.insert(agent, &details);
+                self.record(HistoryAction::WeightUpdated {
+                    agent: *agent,
+                    old_weight,
+                    new_weight: *new_weight,
+                });
+
+                Self::env().emit_event(AgentUpdated {
+                    agent: *agent,
+                    old_weight,
+                    new_weight: *new_weight,
+                });
+            }
+
+            // Recomputed from the stored agents rather than adjusted per update
+            let agents = self.load_agents();
+            self.total_weight = Self::summed_weight(&agents)?;
+            self.ensure_concentration_of(self.total_weight, &agents)?;
+
+            Ok(())
+        }
+
+        /// Begins the process of offboarding a nomination agent
+        /// Permanently removes weight allocation from a given agent.
+        /// This allows dynamic registry weights and offboarding to co-exist.
+        ///
+        /// Caller must have the DisableAgent role.
+        #[ink(message, selector = 5)]
+        fn disable_agent(&mut self, agent: AccountId) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::DisableAgent, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            self.persist_weight_updates();
+
+            if let Some(mut details) = self.agents.get(agent) {
+                if details.disabled {
+                    return Err(RegistryError::NoChange);
+                }
+
+                let old_weight = details.weight;
+                details.weight = 0;
+                details.disabled = true;
+                self.agents.insert(agent, &details);
+                self.total_weight = self.total_weight
+                    .checked_sub(old_weight)
+                    .ok_or(RegistryError::Underflow)?;
+
+                self.record(HistoryAction::WeightUpdated {
+                    agent,
+                    old_weight,
+                    new_weight: 0,
+                });
+                self.set_status(agent, AgentStatus::Disabled);
+
+                Self::env().emit_event(AgentDisabled { agent, old_weight });
             } else {
                 return Err(RegistryError::AgentNotFound);
             }
@@ -313,77 +1194,520 @@ pub mod registry {
             Ok(())
         }
 
+        /// Reverses `disable_agent` for an agent which has not been removed
+        /// Restores the agent with the given weight.
+        ///
+        /// Caller must have the EnableAgent role.
+        /// Resulting weights cannot exceed `max_agent_share` of the total weight.
+        #[ink(message)]
+        fn enable_agent(&mut self, agent: AccountId, weight: u128) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::EnableAgent, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            self.persist_weight_updates();
+
+            if let Some(mut details) = self.agents.get(agent) {
+                if !details.disabled {
+                    return Err(RegistryError::NoChange);
+                }
+
+                details.weight = weight;
+                details.disabled = false;
+                self.agents.insert(agent, &details);
+                self.total_weight = self.total_weight
+                    .checked_add(weight)
+                    .ok_or(RegistryError::Overflow)?;
+
+                self.ensure_concentration()?;
+
+                self.record(HistoryAction::WeightUpdated {
+                    agent,
+                    old_weight: 0,
+                    new_weight: weight,
+                });
+                self.set_status(agent, AgentStatus::Active);
+
+                Self::env().emit_event(AgentEnabled { agent, new_weight: weight });
+            } else {
+                return Err(RegistryError::AgentNotFound);
+            }
+
+            Ok(())
+        }
+
+        /// Replaces the validators nominated by an agent
+        /// Stake moves to the new validators without draining the agent
+        ///
+        /// Caller must have the UpdateAgents role.
+        /// Validators already nominated by another agent are rejected unless `allow_duplicate_validators` is set.
+        #[ink(message)]
+        fn update_agent_validators(
+            &mut self,
+            agent: AccountId,
+            validators: Vec<AccountId>,
+            allow_duplicate_validators: bool,
+        ) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::UpdateAgents, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            Self::ensure_validators(&validators)?;
+
+            if !self.agents.contains(agent) {
+                return Err(RegistryError::AgentNotFound);
+            }
+            if !allow_duplicate_validators {
+                self.ensure_unique_validators(Some(agent), &validators)?;
+            }
+
+            let mut agent_contract: contract_ref!(INominationAgent) = agent.into();
+            agent_contract
+                .nominate(validators.clone())
+                .map_err(RegistryError::AgentError)?;
+
+            if let Some(mut metadata) = self.agent_metadata.get(agent) {
+                self.unindex_validators(agent, &metadata.validators);
+                metadata.validators = validators.clone();
+                self.agent_metadata.insert(agent, &metadata);
+            }
+            self.index_validators(agent, &validators);
+
+            Self::env().emit_event(AgentValidatorsUpdated { agent, validators });
+
+            Ok(())
+        }
+
+        /// Caller must have the AddAgent role.
+        #[ink(message)]
+        fn set_agent_label(&mut self, agent: AccountId, label: String) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::AddAgent, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            if label.len() > MAX_LABEL_LENGTH {
+                return Err(RegistryError::InvalidInput);
+            }
+
+            let mut metadata = self.agent_metadata.get(agent).ok_or(RegistryError::AgentNotFound)?;
+            if metadata.label == label {
+                return Err(RegistryError::NoChange);
+            }
+
+            metadata.label = label.clone();
+            self.agent_metadata.insert(agent, &metadata);
+
+            Self::env().emit_event(AgentLabelUpdated { agent, label });
+
+            Ok(())
+        }
+
+        /// Marks a disabled agent as Draining while its remaining AZERO is withdrawn
+        /// Draining agents are removed with `remove_agent` once empty.
+        ///
+        /// Caller must have the RemoveAgent role.
+        /// Agent must be disabled.
+        #[ink(message)]
+        fn drain_agent(&mut self, agent: AccountId) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::RemoveAgent, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            self.persist_weight_updates();
+
+            let details = self.agents.get(agent).ok_or(RegistryError::AgentNotFound)?;
+            if !details.disabled {
+                return Err(RegistryError::ActiveAgent);
+            }
+            if self.agent_lifecycles.get(agent).map(|l| l.status) == Some(AgentStatus::Draining) {
+                return Err(RegistryError::NoChange);
+            }
+
+            self.set_status(agent, AgentStatus::Draining);
+
+            Ok(())
+        }
+
         /// Removes a nomination agent
         /// This is intended to remove fully deprecated agents to save gas during iteration.
+        /// Removed agents are Destroying until `confirm_bond_reclaimed` is called.
         ///
         /// Caller must have the RemoveAgent role.
+        /// Agent must be Draining with no weight, see `drain_agent`.
         /// Agent must have no AZERO staked (excludes initial bond).
         /// Agent must have no AZERO unbonding.
         #[ink(message, selector = 3)]
         fn remove_agent(&mut self, agent: AccountId) -> Result<(), RegistryError> {
             let caller = Self::env().caller();
 
-            if caller != self.roles.get(RoleType::RemoveAgent).unwrap().account {
+            if !self.is_member(RoleType::RemoveAgent, caller) {
                 return Err(RegistryError::InvalidPermissions);
             }
 
-            if let Some(index) = self.agents.iter().position(|a| a.address == agent) {
-                let mut agent_contract: contract_ref!(INominationAgent) = agent.into();
-                // Do not delete agents with AZERO staked
-                if agent_contract.get_staked_value() > 0 {
-                    return Err(RegistryError::ActiveAgent);
+            self.persist_weight_updates();
+
+            if let Some(details) = self.agents.get(agent) {
+                let status = self.agent_lifecycles.get(agent).map(|l| l.status);
+                if status != Some(AgentStatus::Draining) || details.weight > 0 {
+                    return Err(RegistryError::AgentNotDraining);
+                }
+                let mut agent_contract: contract_ref!(INominationAgent) = agent.into();
+                // Do not delete agents with AZERO staked or unbonding
+                if agent_contract.get_staked_value() > 0 || agent_contract.get_unbonding_value() > 0 {
+                    return Err(RegistryError::ActiveAgent);
+                }
+                self.delete_agent(agent)?;
+                if let Some(metadata) = self.agent_metadata.get(agent) {
+                    self.unindex_validators(agent, &metadata.validators);
+                }
+                self.agent_code_hashes.remove(agent);
+                agent_contract
+                    .destroy()
+                    .map_err(RegistryError::AgentError)?;
+
+                let mut retired_agents = self.retired_agents.get_or_default();
+                retired_agents.push(agent);
+                self.retired_agents.set(&retired_agents);
+                self.set_status(agent, AgentStatus::Destroying);
+
+                Self::env().emit_event(AgentDeleted { agent });
+            } else {
+                return Err(RegistryError::AgentNotFound);
+            }
+            Ok(())
+        }
+
+        /// Marks a removed agent as Destroyed once its admin has withdrawn the creation bond
+        ///
+        /// Callable by anyone as the agent is queried for its status.
+        #[ink(message)]
+        fn confirm_bond_reclaimed(&mut self, agent: AccountId) -> Result<(), RegistryError> {
+            let lifecycle = self.agent_lifecycles.get(agent).ok_or(RegistryError::AgentNotFound)?;
+
+            match lifecycle.status {
+                AgentStatus::Destroying => (),
+                AgentStatus::Destroyed => return Err(RegistryError::NoChange),
+                _ => return Err(RegistryError::ActiveAgent),
+            }
+
+            let agent_contract: contract_ref!(INominationAgent) = agent.into();
+            let (status, _) = agent_contract.get_status();
+            if status != AgentStatus::Destroyed {
+                return Err(RegistryError::BondNotReclaimed);
+            }
+
+            self.set_status(agent, AgentStatus::Destroyed);
+
+            Ok(())
+        }
+
+        /// Scheduled weight updates are only included once persisted
+        #[ink(message, selector = 4)]
+        fn get_agents(&self) -> (u128, Vec<Agent>) {
+            (self.total_weight, self.load_agents())
+        }
+
+        #[ink(message)]
+        fn get_agent(&self, agent: AccountId) -> Option<(Agent, AgentMetadata)> {
+            Some((self.agents.get(agent)?, self.agent_metadata.get(agent)?))
+        }
+
+        #[ink(message)]
+        fn get_agent_lifecycle(&self, agent: AccountId) -> Option<AgentLifecycle> {
+            self.agent_lifecycles.get(agent)
+        }
+
+        /// Removed agents in the order they were removed, along with their lifecycle
+        #[ink(message)]
+        fn get_retired_agents(&self) -> Vec<(AccountId, AgentLifecycle)> {
+            self.retired_agents
+                .get_or_default()
+                .into_iter()
+                .filter_map(|agent| Some((agent, self.agent_lifecycles.get(agent)?)))
+                .collect()
+        }
+
+        /// Returns the first agent nominating `validator`
+        #[ink(message)]
+        fn get_agent_by_validator(&self, validator: AccountId) -> Option<(Agent, AgentMetadata)> {
+            let agent = *self.validator_agents.get(validator)?.first()?;
+            self.get_agent(agent)
+        }
+
+        /// Returns up to `len` agents starting at index `start`
+        #[ink(message)]
+        fn get_agents_paginated(&self, start: u32, len: u32) -> Vec<Agent> {
+            self.agent_addresses(start, len)
+                .into_iter()
+                .filter_map(|address| self.agents.get(address))
+                .collect()
+        }
+
+        /// Staked and unbonding values of every agent
+        #[ink(message)]
+        fn get_agents_overview(&self) -> Vec<AgentOverview> {
+            self.load_agents()
+                .into_iter()
+                .map(|a| {
+                    let agent_contract: contract_ref!(INominationAgent) = a.address.into();
+                    AgentOverview {
+                        address: a.address,
+                        weight: a.weight,
+                        disabled: a.disabled,
+                        staked: agent_contract.get_staked_value(),
+                        unbonding: agent_contract.get_unbonding_value(),
+                    }
+                })
+                .collect()
+        }
+
+        /// ================================ Update Role Methods ================================
+
+        /// Step 1 of 2 in transferring a role to a new account
+        /// Replaces any pending proposal for the role
+        ///
+        /// Caller must be the admin for the role
+        #[ink(message)]
+        fn propose_role(
+            &mut self,
+            role_type: RoleType,
+            new_account: AccountId,
+        ) -> Result<(), RegistryError> {
+            if let Some(role) = self.roles.get(role_type.clone()) {
+                if Self::env().caller() != role.admin {
+                    return Err(RegistryError::InvalidPermissions);
+                }
+                if role.account == new_account {
+                    return Err(RegistryError::NoChange);
+                }
+
+                self.pending_roles.insert(role_type.clone(), &new_account);
+
+                Self::env().emit_event(RoleAccountProposed {
+                    role_type,
+                    new_account,
+                });
+            } else {
+                return Err(RegistryError::InvalidRole);
+            }
+
+            Ok(())
+        }
+
+        /// Step 2 of 2 in transferring a role to a new account
+        ///
+        /// Caller must be the proposed account for the role
+        #[ink(message)]
+        fn accept_role(&mut self, role_type: RoleType) -> Result<(), RegistryError> {
+            if let Some(mut role) = self.roles.get(role_type.clone()) {
+                let caller = Self::env().caller();
+
+                if Some(caller) != self.pending_roles.get(role_type.clone()) {
+                    return Err(RegistryError::InvalidPermissions);
+                }
+
+                // Replace the role account, other members keep the role
+                let mut members = self.load_role_members(role_type.clone(), &role);
+                members.retain(|m| *m != role.account && *m != caller);
+                members.insert(0, caller);
+                role.account = caller;
+                self.role_members.insert(role_type.clone(), &members);
+                self.roles.insert(role_type.clone(), &role);
+                self.pending_roles.remove(role_type.clone());
+                self.record(HistoryAction::RoleAccountChanged {
+                    role_type: role_type.clone(),
+                    new_account: caller,
+                });
+
+                Self::env().emit_event(RoleAccountChanged {
+                    role_type,
+                    new_account: caller,
+                });
+            } else {
+                return Err(RegistryError::InvalidRole);
+            }
+
+            Ok(())
+        }
+
+        /// Discards the pending account proposal for a role
+        ///
+        /// Caller must be the admin for the role
+        #[ink(message)]
+        fn cancel_role_proposal(&mut self, role_type: RoleType) -> Result<(), RegistryError> {
+            if let Some(role) = self.roles.get(role_type.clone()) {
+                if Self::env().caller() != role.admin {
+                    return Err(RegistryError::InvalidPermissions);
+                }
+
+                let new_account = self.pending_roles.take(role_type.clone()).ok_or(RegistryError::NoChange)?;
+
+                Self::env().emit_event(RoleAccountProposalCancelled {
+                    role_type,
+                    new_account,
+                });
+            } else {
+                return Err(RegistryError::InvalidRole);
+            }
+
+            Ok(())
+        }
+
+        /// Step 1 of 2 in transferring administration of a role to a new account
+        /// Replaces any pending proposal for the role admin
+        ///
+        /// Caller must be the admin for the role
+        #[ink(message)]
+        fn propose_role_admin(
+            &mut self,
+            role_type: RoleType,
+            new_account: AccountId,
+        ) -> Result<(), RegistryError> {
+            if let Some(role) = self.roles.get(role_type.clone()) {
+                if Self::env().caller() != role.admin {
+                    return Err(RegistryError::InvalidPermissions);
+                }
+                if role.admin == new_account {
+                    return Err(RegistryError::NoChange);
+                }
+
+                self.pending_role_admins.insert(role_type.clone(), &new_account);
+
+                Self::env().emit_event(RoleAdminProposed {
+                    role_type,
+                    new_account,
+                });
+            } else {
+                return Err(RegistryError::InvalidRole);
+            }
+
+            Ok(())
+        }
+
+        /// Step 2 of 2 in transferring administration of a role to a new account
+        ///
+        /// Caller must be the proposed admin for the role
+        #[ink(message)]
+        fn accept_role_admin(&mut self, role_type: RoleType) -> Result<(), RegistryError> {
+            if let Some(mut role) = self.roles.get(role_type.clone()) {
+                let caller = Self::env().caller();
+
+                if Some(caller) != self.pending_role_admins.get(role_type.clone()) {
+                    return Err(RegistryError::InvalidPermissions);
+                }
+
+                // Update role admin
+                role.admin = caller;
+                self.roles.insert(role_type.clone(), &role);
+                self.pending_role_admins.remove(role_type.clone());
+                self.record(HistoryAction::RoleAdminChanged {
+                    role_type: role_type.clone(),
+                    new_account: caller,
+                });
+
+                Self::env().emit_event(RoleAdminChanged {
+                    role_type,
+                    new_account: caller,
+                });
+            } else {
+                return Err(RegistryError::InvalidRole);
+            }
+
+            Ok(())
+        }
+
+        /// Discards the pending admin proposal for a role
+        ///
+        /// Caller must be the admin for the role
+        #[ink(message)]
+        fn cancel_role_admin_proposal(&mut self, role_type: RoleType) -> Result<(), RegistryError> {
+            if let Some(role) = self.roles.get(role_type.clone()) {
+                if Self::env().caller() != role.admin {
+                    return Err(RegistryError::InvalidPermissions);
+                }
+
+                let new_account = self.pending_role_admins.take(role_type.clone()).ok_or(RegistryError::NoChange)?;
+
+                Self::env().emit_event(RoleAdminProposalCancelled {
+                    role_type,
+                    new_account,
+                });
+            } else {
+                return Err(RegistryError::InvalidRole);
+            }
+
+            Ok(())
+        }
+
+        /// Gives a role to an additional account alongside its current holders
+        ///
+        /// Caller must be the admin for the role
+        #[ink(message)]
+        fn grant_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), RegistryError> {
+            if let Some(role) = self.roles.get(role_type.clone()) {
+                if Self::env().caller() != role.admin {
+                    return Err(RegistryError::InvalidPermissions);
                 }
-                // Do not delete agents with AZERO unbonding
-                if agent_contract.get_unbonding_value() > 0 {
-                    return Err(RegistryError::ActiveAgent);
+
+                let mut members = self.load_role_members(role_type.clone(), &role);
+                if members.contains(&account) {
+                    return Err(RegistryError::NoChange);
                 }
-                let weight = self.agents[index].weight;
-                if weight > 0 {
-                    self.total_weight -= weight;
+                if members.len() >= MAX_ROLE_MEMBERS {
+                    return Err(RegistryError::TooManyRoleMembers);
                 }
-                self.agents.remove(index);
-                agent_contract
-                    .destroy()
-                    .expect("Agent begins the destruction process");
 
-                Self::env().emit_event(AgentDeleted { agent });
+                members.push(account);
+                self.role_members.insert(role_type.clone(), &members);
+                self.record(HistoryAction::RoleGranted {
+                    role_type: role_type.clone(),
+                    account,
+                });
+
+                Self::env().emit_event(RoleGranted {
+                    role_type,
+                    account,
+                });
             } else {
-                return Err(RegistryError::AgentNotFound);
+                return Err(RegistryError::InvalidRole);
             }
-            Ok(())
-        }
 
-        #[ink(message, selector = 4)]
-        fn get_agents(&self) -> (u128, Vec<Agent>) {
-            (self.total_weight, self.agents.clone())
+            Ok(())
         }
 
-        /// ================================ Update Role Methods ================================
-
-        /// Transfers role to a new account
+        /// Takes a role away from an account, such as one with a compromised key
+        /// Revoking the role account passes it to another member, or back to the admin when none remain.
         ///
         /// Caller must be the admin for the role
         #[ink(message)]
-        fn transfer_role(
-            &mut self,
-            role_type: RoleType,
-            new_account: AccountId,
-        ) -> Result<(), RegistryError> {
+        fn revoke_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), RegistryError> {
             if let Some(mut role) = self.roles.get(role_type.clone()) {
                 if Self::env().caller() != role.admin {
                     return Err(RegistryError::InvalidPermissions);
                 }
-                if role.account == new_account {
+
+                let members = self.load_role_members(role_type.clone(), &role);
+                if !members.contains(&account) || members == [role.admin] {
                     return Err(RegistryError::NoChange);
                 }
 
-                // Update role account
-                role.account = new_account;
-                self.roles.insert(role_type.clone(), &role);
+                self.remove_role_member(role_type.clone(), &mut role, account);
+                self.record(HistoryAction::RoleRevoked {
+                    role_type: role_type.clone(),
+                    account,
+                });
 
-                Self::env().emit_event(RoleAccountChanged {
+                Self::env().emit_event(RoleRevoked {
                     role_type,
-                    new_account,
+                    account,
                 });
             } else {
                 return Err(RegistryError::InvalidRole);
@@ -392,30 +1716,31 @@ pub mod registry {
             Ok(())
         }
 
-        /// Transfers administration of role to a new account
+        /// Gives up a role, returning it to the role admin who may assign it again
+        /// The role account passes to another member if any remain.
         ///
-        /// Caller must be the admin for the role
+        /// Caller must have the role and must not be its admin
         #[ink(message)]
-        fn transfer_role_admin(
-            &mut self,
-            role_type: RoleType,
-            new_account: AccountId,
-        ) -> Result<(), RegistryError> {
+        fn renounce_role(&mut self, role_type: RoleType) -> Result<(), RegistryError> {
             if let Some(mut role) = self.roles.get(role_type.clone()) {
-                if Self::env().caller() != role.admin {
+                let caller = Self::env().caller();
+
+                if !self.load_role_members(role_type.clone(), &role).contains(&caller) {
                     return Err(RegistryError::InvalidPermissions);
                 }
-                if role.admin == new_account {
+                if caller == role.admin {
                     return Err(RegistryError::NoChange);
                 }
 
-                // Update role admin
-                role.admin = new_account;
-                self.roles.insert(role_type.clone(), &role);
+                self.remove_role_member(role_type.clone(), &mut role, caller);
+                self.record(HistoryAction::RoleRenounced {
+                    role_type: role_type.clone(),
+                    account: caller,
+                });
 
-                Self::env().emit_event(RoleAdminChanged {
+                Self::env().emit_event(RoleRenounced {
                     role_type,
-                    new_account,
+                    account: caller,
                 });
             } else {
                 return Err(RegistryError::InvalidRole);
@@ -426,48 +1751,407 @@ pub mod registry {
 
         /// ================================ Code Hash Methods ================================
 
-        /// "Upgrade" the Registry contract logic
+        /// Step 1 of 2 in "upgrading" the Registry contract logic
+        /// Announces a code hash which can be applied after `code_delay`
         ///
         /// Caller must have the SetCodeHash role.
+        /// Replaces any pending announcement.
         #[ink(message)]
-        fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), RegistryError> {
+        fn announce_code(&mut self, code_hash: [u8; 32]) -> Result<(), RegistryError> {
             let caller = Self::env().caller();
 
-            if caller != self.roles.get(RoleType::SetCodeHash).unwrap().account {
+            if !self.is_member(RoleType::SetCodeHash, caller) {
                 return Err(RegistryError::InvalidPermissions);
             }
 
-            ink::env::set_code_hash(&code_hash)?;
+            let apply_after = self.get_apply_after()?;
+            self.pending_code.set(&Some(CodeHashAnnouncement {
+                code_hash,
+                apply_after,
+            }));
+
+            Self::env().emit_event(NewCodeHashAnnounced {
+                code_hash,
+                apply_after,
+            });
 
             Ok(())
         }
 
-        /// "Upgrade" the logic of all nomination agent contracts
+        /// Step 2 of 2 in "upgrading" the Registry contract logic
         ///
         /// Caller must have the SetCodeHash role.
+        /// The announcement delay must have elapsed.
         #[ink(message)]
-        fn set_agent_code(&mut self, nomination_agent_hash: [u8; 32]) -> Result<(), RegistryError> {
+        fn apply_code(&mut self) -> Result<(), RegistryError> {
             let caller = Self::env().caller();
 
-            if caller != self.roles.get(RoleType::SetCodeHash).unwrap().account {
+            if !self.is_member(RoleType::SetCodeHash, caller) {
                 return Err(RegistryError::InvalidPermissions);
             }
 
-            for agent in self.agents.iter() {
-                let mut agent_contract: contract_ref!(INominationAgent) = agent.address.into();
-                agent_contract
-                    .set_code(nomination_agent_hash)
-                    .expect("Agent code hash is updated");
+            let announcement = self.pending_code.get().flatten().ok_or(RegistryError::NoPendingCode)?;
+            self.pending_code.set(&None);
+
+            if Self::env().block_timestamp() < announcement.apply_after {
+                return Err(RegistryError::CodeTimelocked);
+            }
+
+            ink::env::set_code_hash(&announcement.code_hash)?;
+
+            Self::env().emit_event(CodeHashApplied {
+                code_hash: announcement.code_hash,
+            });
+
+            Ok(())
+        }
+
+        /// Discards the pending Registry code hash announcement
+        ///
+        /// Caller must have the SetCodeHash role.
+        #[ink(message)]
+        fn cancel_code(&mut self) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::SetCodeHash, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            let announcement = self.pending_code.get().flatten().ok_or(RegistryError::NoPendingCode)?;
+            self.pending_code.set(&None);
+
+            Self::env().emit_event(CodeHashAnnouncementCancelled {
+                code_hash: announcement.code_hash,
+            });
+
+            Ok(())
+        }
+
+        /// Step 1 of 2 in "upgrading" the logic of all nomination agent contracts
+        /// Announces a code hash which can be applied after `code_delay`
+        ///
+        /// Caller must have the SetCodeHash role.
+        /// Replaces any pending announcement.
+        #[ink(message)]
+        fn announce_agent_code(&mut self, nomination_agent_hash: [u8; 32]) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::SetCodeHash, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            let apply_after = self.get_apply_after()?;
+            self.pending_agent_code.set(&Some(CodeHashAnnouncement {
+                code_hash: nomination_agent_hash,
+                apply_after,
+            }));
+
+            Self::env().emit_event(NewAgentCodeHashAnnounced {
+                code_hash: nomination_agent_hash,
+                apply_after,
+            });
+
+            Ok(())
+        }
+
+        /// Step 2 of 2 in "upgrading" the logic of all nomination agent contracts
+        ///
+        /// Caller must have the SetCodeHash role.
+        /// The announcement delay must have elapsed.
+        /// Agents that fail to upgrade are returned with their error and remain on their previous code.
+        #[ink(message)]
+        fn apply_agent_code(&mut self) -> Result<Vec<(AccountId, RegistryError)>, RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::SetCodeHash, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            let announcement = self.pending_agent_code.get().flatten().ok_or(RegistryError::NoPendingCode)?;
+            self.pending_agent_code.set(&None);
+
+            if Self::env().block_timestamp() < announcement.apply_after {
+                return Err(RegistryError::CodeTimelocked);
             }
 
+            let nomination_agent_hash = announcement.code_hash;
+
+            let failures = self.set_code_for(self.agent_addresses(0, u32::MAX), nomination_agent_hash);
+
             self.nomination_agent_hash = Hash::from(nomination_agent_hash);
 
+            Self::env().emit_event(AgentCodeHashApplied {
+                code_hash: nomination_agent_hash,
+            });
+
+            Ok(failures)
+        }
+
+        /// Sets the code hash of selected agents only, such as for a canary rollout
+        ///
+        /// Caller must have the SetCodeHash role.
+        /// Code hash must be the current agent code hash or a pending announcement whose delay has elapsed.
+        /// Does not consume the announcement, `apply_agent_code` still upgrades the remaining agents.
+        /// Agents that fail to upgrade are returned with their error and remain on their previous code.
+        #[ink(message)]
+        fn set_agent_code_for(
+            &mut self,
+            agents: Vec<AccountId>,
+            code_hash: [u8; 32],
+        ) -> Result<Vec<(AccountId, RegistryError)>, RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::SetCodeHash, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            if Hash::from(code_hash) != self.nomination_agent_hash {
+                match self.pending_agent_code.get().flatten() {
+                    Some(announcement) if announcement.code_hash == code_hash => {
+                        if Self::env().block_timestamp() < announcement.apply_after {
+                            return Err(RegistryError::CodeTimelocked);
+                        }
+                    }
+                    _ => return Err(RegistryError::NoPendingCode),
+                }
+            }
+
+            Ok(self.set_code_for(agents, code_hash))
+        }
+
+        #[ink(message)]
+        fn get_agent_code_hash(&self, agent: AccountId) -> Option<Hash> {
+            self.agent_code_hashes.get(agent)
+        }
+
+        /// Discards the pending nomination agent code hash announcement
+        ///
+        /// Caller must have the SetCodeHash role.
+        #[ink(message)]
+        fn cancel_agent_code(&mut self) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::SetCodeHash, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            let announcement = self.pending_agent_code.get().flatten().ok_or(RegistryError::NoPendingCode)?;
+            self.pending_agent_code.set(&None);
+
+            Self::env().emit_event(CodeHashAnnouncementCancelled {
+                code_hash: announcement.code_hash,
+            });
+
+            Ok(())
+        }
+
+        /// Update the time between announcing and applying a code hash
+        ///
+        /// Caller must have the SetCodeHash role.
+        /// New delay cannot be less than `MIN_CODE_DELAY`.
+        /// Does not affect pending announcements.
+        #[ink(message)]
+        fn set_code_delay(&mut self, new_delay: u64) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::SetCodeHash, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+            if self.get_code_delay() == new_delay {
+                return Err(RegistryError::NoChange);
+            }
+            if new_delay < MIN_CODE_DELAY {
+                return Err(RegistryError::InvalidInput);
+            }
+
+            self.code_delay.set(&new_delay);
+
+            Self::env().emit_event(CodeDelayChanged { new_delay });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_pending_code(&self) -> Option<CodeHashAnnouncement> {
+            self.pending_code.get().flatten()
+        }
+
+        #[ink(message)]
+        fn get_pending_agent_code(&self) -> Option<CodeHashAnnouncement> {
+            self.pending_agent_code.get().flatten()
+        }
+
+        #[ink(message)]
+        fn get_code_delay(&self) -> u64 {
+            self.code_delay.get().unwrap_or(MIN_CODE_DELAY)
+        }
+
+        /// Migrates storage to the layout expected by the current code
+        /// Called once after `apply_code` whenever the code bumps `STORAGE_VERSION`
+        ///
+        /// Caller must have the SetCodeHash role.
+        #[ink(message)]
+        fn migrate(&mut self) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::SetCodeHash, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            let from_version = self.storage_version.get().unwrap_or(0);
+            if from_version >= STORAGE_VERSION {
+                return Err(RegistryError::NoChange);
+            }
+
+            // Roles, agent metadata, the validator index, per agent code hashes and lifecycles
+            // were all introduced since the original unversioned deployment
+            if from_version < 1 {
+                self.roles.insert(RoleType::SetLimits, &Role { admin: caller, account: caller });
+                self.roles.insert(RoleType::EnableAgent, &Role { admin: caller, account: caller });
+
+                // Creation details of existing agents are unknown, previously removed agents are not tracked
+                for agent in core::mem::take(&mut self.legacy_agents) {
+                    let agent_contract: contract_ref!(INominationAgent) = agent.address.into();
+                    let metadata = AgentMetadata {
+                        label: String::new(),
+                        validators: [agent_contract.get_validator()].to_vec(),
+                        created_at: 0,
+                        creation_bond: 0,
+                        admin: agent_contract.get_admin(),
+                    };
+                    self.index_validators(agent.address, &metadata.validators);
+                    self.agent_metadata.insert(agent.address, &metadata);
+                    self.agent_code_hashes.insert(agent.address, &self.nomination_agent_hash);
+                    let status = if agent.disabled { AgentStatus::Disabled } else { AgentStatus::Active };
+                    self.agent_lifecycles.insert(agent.address, &AgentLifecycle {
+                        status,
+                        updated_at: 0,
+                        retired_at: 0,
+                    });
+                    self.push_agent(agent)?;
+                }
+            }
+
+            self.storage_version.set(&STORAGE_VERSION);
+
+            Self::env().emit_event(StorageMigrated {
+                from_version,
+                to_version: STORAGE_VERSION,
+            });
+
+            Ok(())
+        }
+
+        /// Migrates the storage of all nomination agents after `apply_agent_code`
+        ///
+        /// Caller must have the SetCodeHash role.
+        /// Agents already on the current storage version are skipped.
+        #[ink(message)]
+        fn migrate_agents(&mut self) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::SetCodeHash, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+
+            for agent in self.agent_addresses(0, u32::MAX) {
+                let mut agent_contract: contract_ref!(INominationAgent) = agent.into();
+                match agent_contract.migrate() {
+                    Ok(()) | Err(RuntimeError::NoChange) => (),
+                    Err(e) => return Err(RegistryError::AgentError(e)),
+                }
+            }
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_storage_version(&self) -> u16 {
+            self.storage_version.get().unwrap_or(0)
+        }
+
+        /// Update the maximum portion of the total weight a single agent can hold in bips
+        ///
+        /// Caller must have the SetLimits role.
+        /// Only enforced on subsequent weight updates.
+        #[ink(message)]
+        fn set_max_agent_share(&mut self, new_max_share: u16) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::SetLimits, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+            if self.max_agent_share.get().unwrap_or(BIPS) == new_max_share {
+                return Err(RegistryError::NoChange);
+            }
+            if new_max_share == 0 || new_max_share > BIPS {
+                return Err(RegistryError::InvalidInput);
+            }
+
+            self.max_agent_share.set(&new_max_share);
+
+            Self::env().emit_event(MaxAgentShareChanged { new_max_share });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_max_agent_share(&self) -> u16 {
+            self.max_agent_share.get().unwrap_or(BIPS)
+        }
+
+        /// Update the maximum number of agents
+        ///
+        /// Caller must have the SetLimits role.
+        /// Only restricts adding agents, existing agents are kept when lowered below the agent count.
+        #[ink(message)]
+        fn set_max_agents(&mut self, new_max_agents: u32) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            if !self.is_member(RoleType::SetLimits, caller) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+            if self.get_max_agents() == new_max_agents {
+                return Err(RegistryError::NoChange);
+            }
+            if new_max_agents == 0 {
+                return Err(RegistryError::InvalidInput);
+            }
+
+            self.max_agents.set(&new_max_agents);
+
+            Self::env().emit_event(MaxAgentsChanged { new_max_agents });
+
             Ok(())
         }
 
         #[ink(message)]
         fn get_max_agents(&self) -> u32 {
-            MAX_AGENTS as u32
+            self.max_agents.get().unwrap_or(MAX_AGENTS)
+        }
+
+        #[ink(message)]
+        fn get_agent_count(&self) -> u32 {
+            self.agent_count.get_or_default()
+        }
+
+        #[ink(message)]
+        fn get_total_weight(&self) -> u128 {
+            self.total_weight
+        }
+
+        /// Up to `len` history entries starting at position `offset` of the full history
+        /// Entries older than the most recent `MAX_HISTORY_ENTRIES` have been pruned and are skipped.
+        #[ink(message)]
+        fn get_history(&self, offset: u32, len: u32) -> Vec<HistoryEntry> {
+            let end = offset.saturating_add(len).min(self.history_count.get_or_default());
+            (offset..end).filter_map(|i| self.history.get(i)).collect()
+        }
+
+        /// Number of history entries ever recorded, including pruned ones
+        #[ink(message)]
+        fn get_history_count(&self) -> u32 {
+            self.history_count.get_or_default()
         }
 
         /// ================================ View Only Role Methods ================================
@@ -477,9 +2161,32 @@ pub mod registry {
             self.roles.get(role_type).unwrap().account
         }
 
+        #[ink(message)]
+        fn get_role_members(&self, role_type: RoleType) -> Vec<AccountId> {
+            match self.roles.get(role_type.clone()) {
+                Some(role) => self.load_role_members(role_type, &role),
+                None => Vec::new(),
+            }
+        }
+
+        #[ink(message)]
+        fn has_role(&self, role_type: RoleType, account: AccountId) -> bool {
+            self.is_member(role_type, account)
+        }
+
         #[ink(message)]
         fn get_role_admin(&self, role_type: RoleType) -> AccountId {
             self.roles.get(role_type).unwrap().admin
         }
+
+        #[ink(message)]
+        fn get_pending_role(&self, role_type: RoleType) -> Option<AccountId> {
+            self.pending_roles.get(role_type)
+        }
+
+        #[ink(message)]
+        fn get_pending_role_admin(&self, role_type: RoleType) -> Option<AccountId> {
+            self.pending_role_admins.get(role_type)
+        }
     }
 }
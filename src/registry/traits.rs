@@ -1,6 +1,6 @@
 use crate::errors::RegistryError;
-use crate::registry::{Agent, RoleType, WeightUpdate};
-use ink::{primitives::AccountId, prelude::vec::Vec};
+use crate::registry::{Agent, AgentLifecycle, AgentMetadata, AgentOverview, CodeHashAnnouncement, HistoryEntry, RoleType, ScheduledWeightUpdates, WeightUpdate};
+use ink::{primitives::{AccountId, Hash}, prelude::{string::String, vec::Vec}};
 
 #[ink::trait_definition]
 pub trait IRegistry {
@@ -8,43 +8,186 @@ pub trait IRegistry {
     fn add_agent(
         &mut self,
         admin: AccountId,
-        validator: AccountId,
+        validators: Vec<AccountId>,
+        allow_duplicate_validators: bool,
     ) -> Result<AccountId, RegistryError>;
     #[ink(message, selector = 2)]
     fn update_agents(&mut self, updates: Vec<WeightUpdate>) -> Result<(), RegistryError>;
+    #[ink(message)]
+    fn set_weights(
+        &mut self,
+        weights: Vec<(AccountId, u128)>,
+        expected_total_weight: Option<u128>,
+    ) -> Result<(), RegistryError>;
+    #[ink(message)]
+    fn schedule_weight_updates(
+        &mut self,
+        weight_updates: Vec<WeightUpdate>,
+        activate_at: u64,
+    ) -> Result<u32, RegistryError>;
+    #[ink(message)]
+    fn cancel_weight_updates(&mut self, id: u32) -> Result<(), RegistryError>;
+    #[ink(message)]
+    fn activate_weight_updates(&mut self) -> u32;
+    #[ink(message)]
+    fn get_pending_weight_updates(&self) -> Vec<ScheduledWeightUpdates>;
     #[ink(message, selector = 5)]
     fn disable_agent(&mut self, agent: AccountId) -> Result<(), RegistryError>;
+    #[ink(message)]
+    fn enable_agent(&mut self, agent: AccountId, weight: u128) -> Result<(), RegistryError>;
+    #[ink(message)]
+    fn update_agent_validators(
+        &mut self,
+        agent: AccountId,
+        validators: Vec<AccountId>,
+        allow_duplicate_validators: bool,
+    ) -> Result<(), RegistryError>;
+    #[ink(message)]
+    fn set_agent_label(&mut self, agent: AccountId, label: String) -> Result<(), RegistryError>;
+    #[ink(message)]
+    fn drain_agent(&mut self, agent: AccountId) -> Result<(), RegistryError>;
     #[ink(message, selector = 3)]
     fn remove_agent(&mut self, account: AccountId) -> Result<(), RegistryError>;
+    #[ink(message)]
+    fn confirm_bond_reclaimed(&mut self, agent: AccountId) -> Result<(), RegistryError>;
     #[ink(message, selector = 4)]
     fn get_agents(&self) -> (u128, Vec<Agent>);
+    #[ink(message)]
+    fn get_agent(&self, agent: AccountId) -> Option<(Agent, AgentMetadata)>;
+    #[ink(message)]
+    fn get_agent_lifecycle(&self, agent: AccountId) -> Option<AgentLifecycle>;
+    #[ink(message)]
+    fn get_retired_agents(&self) -> Vec<(AccountId, AgentLifecycle)>;
+    #[ink(message)]
+    fn get_agent_by_validator(&self, validator: AccountId) -> Option<(Agent, AgentMetadata)>;
+    #[ink(message)]
+    fn get_agents_paginated(&self, start: u32, len: u32) -> Vec<Agent>;
+    #[ink(message)]
+    fn get_agents_overview(&self) -> Vec<AgentOverview>;
 
     #[ink(message)]
-    fn transfer_role(
+    fn propose_role(
         &mut self,
         role_type: RoleType,
         new_account: AccountId,
     ) -> Result<(), RegistryError>;
 
     #[ink(message)]
-    fn transfer_role_admin(
+    fn accept_role(&mut self, role_type: RoleType) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn cancel_role_proposal(&mut self, role_type: RoleType) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn propose_role_admin(
         &mut self,
         role_type: RoleType,
         new_account: AccountId,
     ) -> Result<(), RegistryError>;
 
     #[ink(message)]
-    fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), RegistryError>;
+    fn accept_role_admin(&mut self, role_type: RoleType) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn cancel_role_admin_proposal(&mut self, role_type: RoleType) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn grant_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn revoke_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn renounce_role(&mut self, role_type: RoleType) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn announce_code(&mut self, code_hash: [u8; 32]) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn apply_code(&mut self) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn cancel_code(&mut self) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn announce_agent_code(&mut self, nomination_agent_hash: [u8; 32]) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn apply_agent_code(&mut self) -> Result<Vec<(AccountId, RegistryError)>, RegistryError>;
+
+    #[ink(message)]
+    fn set_agent_code_for(
+        &mut self,
+        agents: Vec<AccountId>,
+        code_hash: [u8; 32],
+    ) -> Result<Vec<(AccountId, RegistryError)>, RegistryError>;
+
+    #[ink(message)]
+    fn get_agent_code_hash(&self, agent: AccountId) -> Option<Hash>;
 
     #[ink(message)]
-    fn set_agent_code(&mut self, nomination_agent_hash: [u8; 32]) -> Result<(), RegistryError>;
+    fn cancel_agent_code(&mut self) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn set_code_delay(&mut self, new_delay: u64) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn get_pending_code(&self) -> Option<CodeHashAnnouncement>;
+
+    #[ink(message)]
+    fn get_pending_agent_code(&self) -> Option<CodeHashAnnouncement>;
+
+    #[ink(message)]
+    fn get_code_delay(&self) -> u64;
+
+    #[ink(message)]
+    fn migrate(&mut self) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn migrate_agents(&mut self) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn get_storage_version(&self) -> u16;
 
     #[ink(message)]
     fn get_role(&self, role_type: RoleType) -> AccountId;
 
+    #[ink(message)]
+    fn get_role_members(&self, role_type: RoleType) -> Vec<AccountId>;
+
+    #[ink(message)]
+    fn has_role(&self, role_type: RoleType, account: AccountId) -> bool;
+
     #[ink(message)]
     fn get_role_admin(&self, role_type: RoleType) -> AccountId;
 
+    #[ink(message)]
+    fn get_pending_role(&self, role_type: RoleType) -> Option<AccountId>;
+
+    #[ink(message)]
+    fn get_pending_role_admin(&self, role_type: RoleType) -> Option<AccountId>;
+
+    #[ink(message)]
+    fn set_max_agent_share(&mut self, new_max_share: u16) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn get_max_agent_share(&self) -> u16;
+
+    #[ink(message)]
+    fn set_max_agents(&mut self, new_max_agents: u32) -> Result<(), RegistryError>;
+
     #[ink(message)]
     fn get_max_agents(&self) -> u32;
+
+    #[ink(message)]
+    fn get_agent_count(&self) -> u32;
+
+    #[ink(message)]
+    fn get_total_weight(&self) -> u128;
+
+    #[ink(message)]
+    fn get_history(&self, offset: u32, len: u32) -> Vec<HistoryEntry>;
+
+    #[ink(message)]
+    fn get_history_count(&self) -> u32;
 }
@@ -2,13 +2,31 @@
 mod traits;
 
 pub use traits::ShareToken;
-pub use crate::token::TokenRef;
+pub use crate::token::{TokenRef, SNAPSHOT_PERIOD};
 
 #[ink::contract]
 mod token {
 
-    use ink::prelude::{string::String, vec::Vec};
+    use ink::{
+        prelude::{string::String, vec::Vec},
+        storage::Mapping,
+    };
     use psp22::{PSP22Burnable, PSP22Data, PSP22Error, PSP22Event, PSP22Metadata, PSP22};
+
+    /// Length of the periods at the start of which balances are snapshotted
+    pub const SNAPSHOT_PERIOD: u64 = 7 * 86400 * 1000;
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Snapshot {
+        pub period: u64,
+        // Balance held at the start of `period`
+        pub balance: u128,
+    }
+
     #[ink(storage)]
     pub struct Token {
         data: PSP22Data, // (1)
@@ -16,6 +34,8 @@ mod token {
         name: Option<String>,
         symbol: Option<String>,
         decimals: u8,
+        // Balance held at the start of the period in which each account's balance last changed
+        snapshots: Mapping<AccountId, Snapshot>,
     }
 
     impl Token {
@@ -28,6 +48,7 @@ mod token {
                 name,
                 symbol,
                 decimals: 12_u8,
+                snapshots: Mapping::default(),
             }
         }
         #[ink(message, selector = 7777)]
@@ -35,14 +56,46 @@ mod token {
             if Self::env().caller() != self.owner {
                 return Err(PSP22Error::Custom(String::from("Caller is not Owner")));
             }
+            self.checkpoint(to);
             let events = self.data.mint(to, value)?;
             self.emit_events(events);
             Ok(())
         }
+        /// Balance held at the start of the current snapshot period
+        ///
+        /// Share tokens deployed before snapshots were added have no code upgrade path,
+        /// deployments relying on this must redeploy the vault and its token, see `scripts/deploy.ts`.
+        #[ink(message, selector = 7778)]
+        pub fn snapshot_balance_of(&self, owner: AccountId) -> u128 {
+            match self.snapshots.get(owner) {
+                Some(snapshot) if snapshot.period == self.current_period() => snapshot.balance,
+                // Balance has not changed since the period started
+                _ => self.data.balance_of(owner),
+            }
+        }
         #[ink(message)]
         pub fn get_owner(&self) -> AccountId {
             self.owner
         }
+        fn current_period(&self) -> u64 {
+            Self::env().block_timestamp() / SNAPSHOT_PERIOD
+        }
+        // Records the balance held at the start of the current period before it first changes
+        fn checkpoint(&mut self, account: AccountId) {
+            let period = self.current_period();
+            match self.snapshots.get(account) {
+                Some(snapshot) if snapshot.period == period => (),
+                _ => {
+                    self.snapshots.insert(
+                        account,
+                        &Snapshot {
+                            period,
+                            balance: self.data.balance_of(account),
+                        },
+                    );
+                }
+            }
+        }
         // A helper function translating a vector of PSP22Events into the proper
         // ink event types (defined internally in this contract) and emitting them.
         // (5)
@@ -91,6 +144,7 @@ mod token {
             if Self::env().caller() != self.owner {
                 return Err(PSP22Error::Custom(String::from("Caller is not Owner")));
             }
+            self.checkpoint(self.env().caller());
             let events = self.data.burn(self.env().caller(), value)?;
             self.emit_events(events);
             Ok(())
@@ -120,6 +174,8 @@ mod token {
             value: u128,
             _data: Vec<u8>,
         ) -> Result<(), PSP22Error> {
+            self.checkpoint(self.env().caller());
+            self.checkpoint(to);
             let events = self.data.transfer(self.env().caller(), to, value)?;
             self.emit_events(events);
             Ok(())
@@ -135,6 +191,8 @@ mod token {
         ) -> Result<(), PSP22Error> {
             let caller = self.env().caller();
 
+            self.checkpoint(from);
+            self.checkpoint(to);
             if caller == self.owner {
                 let events = self.data.transfer(from, to, value)?;
                 self.emit_events(events);
@@ -5,4 +5,7 @@ use psp22::PSP22Error;
 pub trait ShareToken {
     #[ink(message, selector = 7777)]
     fn mint(&mut self, to: AccountId, value: u128) -> Result<(), PSP22Error>;
+
+    #[ink(message, selector = 7778)]
+    fn snapshot_balance_of(&self, owner: AccountId) -> u128;
 }
@@ -14,7 +14,7 @@ use ink::{
     },
     prelude::vec::Vec,
     primitives::AccountId,
-    storage::Mapping,
+    storage::{Lazy, Mapping},
 };
 use num_bigint::BigUint;
 use num_traits::cast::ToPrimitive;
@@ -27,8 +27,78 @@ pub type Balance = <DefaultEnvironment as Environment>::Balance;
 pub type Timestamp = u64;
 
 pub const BIPS: u16 = 10000;
+/// version of the storage layout defined by this code, bumped whenever a migration is required
+/// 1 - storage version introduced, settings added before it are kept outside of the packed fields
+/// 2 - roles moved out of the packed fields into `roles`, with admins and two-step transfers
+pub const STORAGE_VERSION: u16 = 2;
+pub const MAX_FEE_RECIPIENTS: usize = 10;
+/// number of agents loaded from the registry per call
+pub const AGENT_PAGE_SIZE: u32 = 50;
+pub const MAX_FEE_PERCENTAGE: u16 = 10_00; // 10.00%
+pub const MAX_FEE_STEP: u16 = 2_00; // 2.00%
 pub const DAY: u64 = 86400 * 1000;
 pub const YEAR: u64 = DAY * 365_25 / 100; // https://docs.alephzero.org/aleph-zero/use/stake/staking-rewards
+pub const FEE_CHANGE_DELAY: u64 = DAY * 7;
+pub const MIN_REWARD_VESTING_PERIOD: u64 = DAY;
+pub const ALL_ROLES: [VaultRoleType; 7] = [
+    VaultRoleType::AdjustFee,
+    VaultRoleType::FeeTo,
+    VaultRoleType::SetCode,
+    VaultRoleType::Pauser,
+    VaultRoleType::Keeper,
+    VaultRoleType::LimitsAdmin,
+    VaultRoleType::Guardian,
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum VaultRoleType {
+    /// Permission to adjust protocol fees and the fee model
+    AdjustFee,
+    /// Permission to withdraw protocol fees and update fee recipients
+    FeeTo,
+    /// Permission to "upgrade" Vault logic via `announce_code` and `apply_code`
+    SetCode,
+    /// Reserved for pausing protocol operations
+    Pauser,
+    /// Reserved for permissioned maintenance operations
+    Keeper,
+    /// Permission to adjust protocol limits such as the maximum agent share
+    LimitsAdmin,
+    /// Permission to cancel pending fee proposals and code hash announcements
+    Guardian,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Role {
+    /// account that can transfer the role
+    pub admin: AccountId,
+    /// account holding the role
+    pub account: AccountId,
+}
+
+/// Role holders as stored up to storage version 1, moved into `VaultData::roles` by `migrate`
+/// Encodes identically to the role fields of the original deployment, which lead the packed layout of versions 0 and 1
+/// The guardian added with version 1 is stored outside of the packed fields in `VaultData::legacy_role_guardian`
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct LegacyRoles {
+    pub role_adjust_fee: AccountId,
+    pub role_fee_to: AccountId,
+    pub role_set_code: Option<AccountId>,
+}
+
+impl Default for LegacyRoles {
+    fn default() -> Self {
+        let zero = AccountId::from([0u8; 32]);
+        LegacyRoles {
+            role_adjust_fee: zero,
+            role_fee_to: zero,
+            role_set_code: None,
+        }
+    }
+}
 
 #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
@@ -37,17 +107,74 @@ pub struct UnlockRequest {
     pub azero: u128,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct FeeProposal {
+    pub new_fee: u16,
+    /// earliest time the proposal can be executed
+    pub execute_after: Timestamp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct FeeModelProposal {
+    pub fee_model: FeeModel,
+    /// earliest time the proposal can be executed
+    pub execute_after: Timestamp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct VestingPeriodProposal {
+    pub new_period: u64,
+    /// earliest time the proposal can be executed
+    pub execute_after: Timestamp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct CodeHashAnnouncement {
+    pub code_hash: [u8; 32],
+    /// earliest time the code hash can be applied
+    pub apply_after: Timestamp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct FeeRecipient {
+    pub account: AccountId,
+    /// portion of protocol fees expressed in basis points
+    pub bips: u16,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum FeeModel {
+    /// Annualized fee on all shares which accrues over time as virtual shares (`fee_percentage`)
+    #[default]
+    Management,
+    /// Fee on compounded rewards which is minted upon compounding (`performance_fee_percentage`)
+    Performance,
+}
+
+/// Packed fields keep the layout of the original deployment so that storage of every version decodes
+/// Fields added since are kept outside of the packed fields
 #[ink::storage_item]
 #[derive(Debug)]
 pub struct VaultData {
-    /// account that can adjust fees
-    pub role_adjust_fee: AccountId,
-    /// account that receives the fees from `withdraw_fees`
-    pub role_fee_to: AccountId,
-    /// account that can "upgrade" Vault logic via `set_code`
-    pub role_set_code: Option<AccountId>,
+    /// role holders from storage version 1, empty once migrated
+    pub legacy_roles: LegacyRoles,
+    /// guardian from storage version 1, only read by `migrate`
+    pub legacy_role_guardian: Lazy<AccountId>,
+    /// holder and admin of each role, absent if the role is disabled
+    pub roles: Mapping<VaultRoleType, Role>,
+    /// accounts proposed to take over each role, which must accept before the transfer completes
+    pub pending_roles: Mapping<VaultRoleType, AccountId>,
+    /// accounts proposed to take over administration of each role
+    pub pending_role_admins: Mapping<VaultRoleType, AccountId>,
 
     /// total AZERO staked excluding AZERO being unbonded
+    /// includes compounded rewards which have not yet vested
     pub total_pooled: Balance,
     /// total sA0 minted
     pub total_shares_minted: u128,
@@ -60,15 +187,51 @@ pub struct VaultData {
     /// time required to unbond staked funds
     pub cooldown_period: u64,
 
+    /// code hash awaiting `set_code_delay` before it can be applied
+    pub pending_code: Lazy<Option<CodeHashAnnouncement>>,
+    /// time between announcing and applying a code hash, never less than `cooldown_period`
+    pub set_code_delay: Lazy<u64>,
+
     /// last update time of claimable fees variable only modified by stake, redeem, withdraw_fees, and adjust_fee
     pub last_fee_update: Timestamp,
     /// annualized fee percentage expressed in basis points
     pub fee_percentage: u16,
+    /// fee increase awaiting `FEE_CHANGE_DELAY` before it can be executed
+    pub pending_fee: Lazy<Option<FeeProposal>>,
+    /// fee model determining how protocol fees are taken
+    pub fee_model: Lazy<FeeModel>,
+    /// percentage of compounded rewards taken as fees expressed in basis points
+    pub performance_fee_percentage: Lazy<u16>,
+    /// cumulative sA0 minted as performance fees
+    pub total_performance_fee_shares: Lazy<u128>,
+    /// accounts receiving protocol fees and their portions which sum to `BIPS`
+    pub fee_recipients: Lazy<Vec<FeeRecipient>>,
+
+    /// compounded rewards which had not yet vested as of `last_reward_lock`
+    pub locked_rewards: Lazy<Balance>,
+    /// last time compounded rewards were locked, from which `locked_rewards` begin vesting
+    pub last_reward_lock: Lazy<Timestamp>,
+    /// duration over which compounded rewards vest linearly into the redemption ratio
+    pub reward_vesting_period: Lazy<u64>,
 
     /// token contract used for representing protocol staked AZERO ownership
     pub shares_contract: AccountId,
     /// registry contract used for tracking agents and weights
     pub registry_contract: RegistryRef,
+
+    /// version of the storage layout, can be read from any layout
+    /// absent on deployments which predate storage versioning
+    pub storage_version: Lazy<u16>,
+    /// maximum portion of `total_pooled` a single agent can hold in bips, no limit when unset
+    pub max_agent_share: Lazy<u16>,
+    /// index of the first agent receiving the next deposit, rotates through the registry one page at a time
+    pub bonding_cursor: Lazy<u32>,
+    /// performance fee increase awaiting `FEE_CHANGE_DELAY` before it can be executed
+    pub pending_performance_fee: Lazy<Option<FeeProposal>>,
+    /// fee model change awaiting `FEE_CHANGE_DELAY` before it can be executed
+    pub pending_fee_model: Lazy<Option<FeeModelProposal>>,
+    /// reward vesting period change awaiting `FEE_CHANGE_DELAY` before it can be executed
+    pub pending_reward_vesting_period: Lazy<Option<VestingPeriodProposal>>,
 }
 
 impl VaultData {
@@ -79,20 +242,212 @@ impl VaultData {
         current_time: Timestamp,
         era: u64,
     ) -> VaultData {
-        VaultData {
-            role_adjust_fee: admin,
-            role_fee_to: admin,
-            role_set_code: Some(admin),
+        let mut roles = Mapping::default();
+        for role_type in ALL_ROLES {
+            roles.insert(role_type, &Role { admin, account: admin });
+        }
+
+        let mut data = VaultData {
+            legacy_roles: LegacyRoles::default(),
+            legacy_role_guardian: Lazy::new(),
+            roles,
+            pending_roles: Mapping::default(),
+            pending_role_admins: Mapping::default(),
             total_pooled: 0,
             total_shares_minted: 0,
             total_shares_virtual: 0,
             user_unlock_requests: Mapping::default(),
             cooldown_period: era * 14,
+            pending_code: Lazy::new(),
+            set_code_delay: Lazy::new(),
             last_fee_update: current_time,
             fee_percentage: 2_00, // 2.00%
+            pending_fee: Lazy::new(),
+            fee_model: Lazy::new(),
+            performance_fee_percentage: Lazy::new(),
+            total_performance_fee_shares: Lazy::new(),
+            fee_recipients: Lazy::new(),
+            locked_rewards: Lazy::new(),
+            last_reward_lock: Lazy::new(),
+            reward_vesting_period: Lazy::new(),
             shares_contract: shares_contract_,
             registry_contract: registry_ref,
+            storage_version: Lazy::new(),
+            max_agent_share: Lazy::new(),
+            bonding_cursor: Lazy::new(),
+            pending_performance_fee: Lazy::new(),
+            pending_fee_model: Lazy::new(),
+            pending_reward_vesting_period: Lazy::new(),
+        };
+        data.storage_version.set(&STORAGE_VERSION);
+        data.set_code_delay.set(&(era * 14));
+        data.fee_model.set(&FeeModel::Management);
+        data.performance_fee_percentage.set(&10_00); // 10.00%
+        data.fee_recipients.set(&[FeeRecipient { account: admin, bips: BIPS }].to_vec());
+        data.last_reward_lock.set(&current_time);
+        data.reward_vesting_period.set(&era);
+        data
+    }
+
+    /// Returns the version of the storage layout, zero if it predates storage versioning
+    pub fn get_storage_version(&self) -> u16 {
+        self.storage_version.get().unwrap_or(0)
+    }
+
+    /// Returns the time between announcing and applying a code hash, `cooldown_period` until migrated
+    pub fn get_set_code_delay(&self) -> u64 {
+        self.set_code_delay.get().unwrap_or(self.cooldown_period)
+    }
+
+    /// Initializes the settings introduced before storage versioning, which an unversioned deployment lacks
+    ///
+    /// Fees keep going to the fee recipient of the original deployment under the management fee model
+    /// Compounded rewards keep entering the redemption ratio immediately until a vesting period is set
+    pub fn migrate_unversioned_settings(&mut self, current_time: Timestamp) {
+        self.set_code_delay.set(&self.cooldown_period);
+        self.fee_model.set(&FeeModel::Management);
+        self.performance_fee_percentage.set(&10_00); // 10.00%
+        self.fee_recipients.set(&[FeeRecipient { account: self.legacy_roles.role_fee_to, bips: BIPS }].to_vec());
+        self.last_reward_lock.set(&current_time);
+    }
+
+    /// Returns the maximum portion of `total_pooled` a single agent can hold, `BIPS` if unlimited
+    pub fn get_max_agent_share(&self) -> u16 {
+        self.max_agent_share.get().unwrap_or(BIPS)
+    }
+
+    /// Checks a proposed fee against `MAX_FEE_PERCENTAGE` and `MAX_FEE_STEP` from the current fee
+    pub fn check_fee_change(current_fee: u16, new_fee: u16) -> Result<(), VaultError> {
+        if new_fee > MAX_FEE_PERCENTAGE {
+            return Err(VaultError::InvalidPercent);
+        }
+        if new_fee.abs_diff(current_fee) > MAX_FEE_STEP {
+            return Err(VaultError::FeeStepTooLarge);
+        }
+        Ok(())
+    }
+
+    /// Returns the account holding a role, `None` if the role is disabled
+    pub fn get_role(&self, role_type: VaultRoleType) -> Option<AccountId> {
+        self.roles.get(role_type).map(|role| role.account)
+    }
+
+    /// Moves role holders from `legacy_roles` and `legacy_role_guardian` into `roles`
+    ///
+    /// Each holder becomes the admin of its own role, role transfers did not exist before version 2
+    /// Roles introduced with storage version 2, and the guardian of unversioned deployments, are assigned to `new_role_admin`
+    pub fn migrate_legacy_roles(&mut self, new_role_admin: AccountId) {
+        let legacy = core::mem::take(&mut self.legacy_roles);
+        let role_guardian = self.legacy_role_guardian.get().unwrap_or(new_role_admin);
+
+        for (role_type, account) in [
+            (VaultRoleType::AdjustFee, Some(legacy.role_adjust_fee)),
+            (VaultRoleType::FeeTo, Some(legacy.role_fee_to)),
+            (VaultRoleType::SetCode, legacy.role_set_code),
+            (VaultRoleType::Guardian, Some(role_guardian)),
+        ] {
+            if let Some(account) = account {
+                self.roles.insert(role_type, &Role { admin: account, account });
+            }
+        }
+
+        for role_type in [VaultRoleType::Pauser, VaultRoleType::Keeper, VaultRoleType::LimitsAdmin] {
+            self.roles.insert(role_type, &Role { admin: new_role_admin, account: new_role_admin });
+        }
+    }
+
+    /// Loads all agents from the registry one page at a time
+    pub fn get_all_agents(&self) -> (u128, Vec<Agent>) {
+        let total_weight = self.registry_contract.get_total_weight();
+
+        let mut agents = Vec::new();
+        loop {
+            let page = self.registry_contract.get_agents_paginated(agents.len() as u32, AGENT_PAGE_SIZE);
+            let is_last = (page.len() as u32) < AGENT_PAGE_SIZE;
+            agents.extend(page);
+            if is_last {
+                break;
+            }
+        }
+
+        (total_weight, agents)
+    }
+
+    /// Selects the page of agents receiving the next deposit
+    /// Starts at `bonding_cursor` and skips pages without any weight
+    ///
+    /// # Returns
+    ///
+    /// `start` - Registry index of the first agent in the page
+    /// `agents` - Up to `AGENT_PAGE_SIZE` agents
+    /// `page_weight` - Sum of the weights of the agents in the page
+    fn get_bonding_page(&self) -> Result<(u32, Vec<Agent>, u128), VaultError> {
+        let agent_count = self.registry_contract.get_agent_count();
+        let mut start = self.bonding_cursor.get_or_default();
+
+        for _ in 0..agent_count.div_ceil(AGENT_PAGE_SIZE) {
+            if start >= agent_count {
+                start = 0;
+            }
+            let agents = self.registry_contract.get_agents_paginated(start, AGENT_PAGE_SIZE);
+            let page_weight = agents
+                .iter()
+                .try_fold(0u128, |total, a| total.checked_add(a.weight))
+                .ok_or(VaultError::Overflow)?;
+            if page_weight > 0 {
+                return Ok((start, agents, page_weight));
+            }
+            start = start.checked_add(agents.len() as u32).ok_or(VaultError::Overflow)?;
+        }
+
+        Err(VaultError::ZeroTotalWeight)
+    }
+
+    /// Places the part of a deposit which the bonding page cannot take below `max_stake`
+    /// Walks the weighted agents following the page in registry order, wrapping around
+    ///
+    /// # Returns
+    ///
+    /// Address and deposit amount of each agent taking part of the excess
+    fn get_excess_deposits(
+        &self,
+        start: u32,
+        len: u32,
+        mut excess: u128,
+        max_stake: u128,
+    ) -> Result<Vec<(AccountId, u128)>, VaultError> {
+        let agent_count = self.registry_contract.get_agent_count();
+        let mut index = start.checked_add(len).ok_or(VaultError::Overflow)?;
+        let mut remaining = agent_count.saturating_sub(len);
+        let mut deposits = Vec::new();
+
+        while excess > 0 && remaining > 0 {
+            if index >= agent_count {
+                index = 0;
+            }
+            let page = self.registry_contract.get_agents_paginated(index, AGENT_PAGE_SIZE.min(remaining));
+            if page.is_empty() {
+                break;
+            }
+            for a in page.iter().filter(|a| a.weight > 0) {
+                let amount = excess.min(max_stake.saturating_sub(query_staked_value(a.address)));
+                if amount > 0 {
+                    deposits.push((a.address, amount));
+                    excess -= amount;
+                    if excess == 0 {
+                        break;
+                    }
+                }
+            }
+            index = index.checked_add(page.len() as u32).ok_or(VaultError::Overflow)?;
+            remaining = remaining.saturating_sub(page.len() as u32);
+        }
+
+        if excess > 0 {
+            return Err(VaultError::ConcentrationLimit);
         }
+
+        Ok(deposits)
     }
 
     /// Calculates differences between current staked amounts and optimal staked amounts
@@ -110,30 +465,33 @@ impl VaultData {
         agents: &Vec<Agent>,
         total_weight: u128,
         total_pooled: u128,
-    ) -> (u128, u128, Vec<u128>, Vec<i128>) {
+    ) -> Result<(u128, u128, Vec<u128>, Vec<i128>), VaultError> {
         let mut pos_diff = 0_u128;
         let mut neg_diff = 0_u128;
         let mut stakes = Vec::new();
         let mut imbalances = Vec::new();
 
         for a in agents.into_iter() {
-            let staked_amount_current = query_staked_value(a.address) as i128;
+            let staked_amount_current = query_staked_value(a.address);
             let staked_amount_optimal = if total_weight > 0 {
-                self.pro_rata(a.weight, total_pooled, total_weight) as i128
+                self.pro_rata(a.weight, total_pooled, total_weight)?
             } else {
                 0
             };
-            let diff = staked_amount_current - staked_amount_optimal;
+            let diff = i128::try_from(staked_amount_current)
+                .map_err(|_| VaultError::Overflow)?
+                .checked_sub(i128::try_from(staked_amount_optimal).map_err(|_| VaultError::Overflow)?)
+                .ok_or(VaultError::Underflow)?;
             if diff > 0 {
-                pos_diff += diff as u128;
+                pos_diff = pos_diff.checked_add(diff.unsigned_abs()).ok_or(VaultError::Overflow)?;
             } else if diff < 0 {
-                neg_diff += -diff as u128;
+                neg_diff = neg_diff.checked_add(diff.unsigned_abs()).ok_or(VaultError::Overflow)?;
             }
-            stakes.push(staked_amount_current as u128);
+            stakes.push(staked_amount_current);
             imbalances.push(diff);
         }
 
-        (pos_diff, neg_diff, stakes, imbalances)
+        Ok((pos_diff, neg_diff, stakes, imbalances))
     }
 
     /// Deposits a given amount to nominator agents splitting deposits by nominator weights and stake imbalances
@@ -141,55 +499,69 @@ impl VaultData {
     /// Uses a weighting algorithm that prioritizes negatively imbalanced (under-allocated) agents.
     /// Phase1: The amount is split among negatively imbalanced nodes according to their proportion of the total imbalance.
     /// Phase2: If the deposit amount is more than the negative imbalance, the remainder is split according to nominator weight proportions.
+    /// No agent receives more than would bring it to `max_agent_share` of the new total pooled,
+    /// the excess goes to other agents with room left and fails only if none remains.
+    ///
+    /// Only a single page of up to `AGENT_PAGE_SIZE` agents receives each deposit, moving to the next page on every call.
+    /// Imbalances are measured against all agents, so repeated deposits converge on the registry weights.
     pub fn delegate_bonding(&mut self, azero: Balance) -> Result<(), VaultError> {
-        let (total_weight, agents) = self.registry_contract.get_agents();
+        self.registry_contract.activate_weight_updates();
+        let total_weight = self.registry_contract.get_total_weight();
 
         if total_weight == 0 {
             return Err(VaultError::ZeroTotalWeight);
         }
 
-        let new_total_pooled = self.total_pooled + azero;
+        let (start, agents, page_weight) = self.get_bonding_page()?;
+
+        let new_total_pooled = self.total_pooled
+            .checked_add(azero)
+            .ok_or(VaultError::Overflow)?;
 
-        let (_pos_diff, neg_diff, _stakes, imbalances) = self
-            .get_weight_imbalances(&agents, total_weight, new_total_pooled);
+        let (_pos_diff, neg_diff, stakes, imbalances) = self
+            .get_weight_imbalances(&agents, total_weight, new_total_pooled)?;
 
         // Amount to distribute to under-allocated agents
         let phase1 = if azero < neg_diff { azero } else { neg_diff };
 
-        // Remaining amount to distribute equitably to all agents
-        let phase2 = azero - phase1;
+        // Remaining amount to distribute equitably to all agents in the page
+        let phase2 = azero.checked_sub(phase1).ok_or(VaultError::Underflow)?;
 
         let n = agents.len();
         let mut deposit_amounts: Vec<u128> = Vec::with_capacity(n);
-        let mut deposit_summation = 0;
+        let mut deposit_summation = 0_u128;
 
         for i in 0..n {
             // Distribute to under-allocated agents
             // Weighted by agent imbalance
             let phase1_amount = if imbalances[i] < 0 {
-                self.pro_rata(phase1, -imbalances[i] as u128, neg_diff)
+                self.pro_rata(phase1, imbalances[i].unsigned_abs(), neg_diff)?
             } else {
                 0
             };
 
-            // Distribute remaining amount equitably to all agents
+            // Distribute remaining amount equitably to all agents in the page
             // Weighted by agent weight
             let phase2_amount = if phase2 > 0 {
-                self.pro_rata(phase2, agents[i].weight, total_weight)
+                self.pro_rata(phase2, agents[i].weight, page_weight)?
             } else {
                 0
             };
 
-            let deposit_amount = phase1_amount + phase2_amount;
+            let deposit_amount = phase1_amount
+                .checked_add(phase2_amount)
+                .ok_or(VaultError::Overflow)?;
             deposit_amounts.push(deposit_amount);
-            deposit_summation += deposit_amount;
+            deposit_summation = deposit_summation
+                .checked_add(deposit_amount)
+                .ok_or(VaultError::Overflow)?;
         }
 
         if deposit_summation == 0 {
             return Err(VaultError::ZeroDepositing);
         }
 
-        let dust = azero - deposit_summation;
+        let dust = azero.checked_sub(deposit_summation).ok_or(VaultError::Underflow)?;
         debug_println!("Dust: {}", dust);
 
         // Allocate dust
@@ -199,12 +571,54 @@ impl VaultData {
             for i in 0..n {
                 if deposit_amounts[i] > 0 {
                     debug_println!("Allocating {} dust to agent #{}", dust, i);
-                    deposit_amounts[i] += dust;
+                    deposit_amounts[i] = deposit_amounts[i]
+                        .checked_add(dust)
+                        .ok_or(VaultError::Overflow)?;
                     break;
                 }
             }
         }
 
+        // Enforce concentration limit
+        // Caps deposits at the room left below the limit and moves the excess to agents which still have room
+        let mut excess_deposits = Vec::new();
+        if let Some(max_agent_share) = self.max_agent_share.get() {
+            let max_stake = self.pro_rata(new_total_pooled, max_agent_share as u128, BIPS as u128)?;
+            let mut rooms: Vec<u128> = Vec::with_capacity(n);
+            let mut excess = 0_u128;
+            for (i, deposit_amount) in deposit_amounts.iter_mut().enumerate() {
+                let room = if agents[i].weight > 0 {
+                    max_stake.saturating_sub(stakes[i])
+                } else {
+                    0
+                };
+                if *deposit_amount > room {
+                    debug_println!("Capping deposit into agent #{} at {}", i, room);
+                    excess = excess
+                        .checked_add(*deposit_amount - room)
+                        .ok_or(VaultError::Overflow)?;
+                    *deposit_amount = room;
+                }
+                rooms.push(room - *deposit_amount);
+            }
+
+            // Prioritizes agents in the page, in registry order
+            for (i, deposit_amount) in deposit_amounts.iter_mut().enumerate() {
+                if excess == 0 {
+                    break;
+                }
+                let amount = excess.min(rooms[i]);
+                *deposit_amount = deposit_amount
+                    .checked_add(amount)
+                    .ok_or(VaultError::Overflow)?;
+                excess -= amount;
+            }
+
+            if excess > 0 {
+                excess_deposits = self.get_excess_deposits(start, n as u32, excess, max_stake)?;
+            }
+        }
+
         // Deposit
         for (i, a) in agents.iter().enumerate() {
             let deposit_amount = deposit_amounts[i];
@@ -215,8 +629,15 @@ impl VaultData {
                 }
             }
         }
+        for (address, deposit_amount) in excess_deposits {
+            debug_println!("Depositing {} excess into agent {:?}", deposit_amount, address);
+            if let Err(e) = call_deposit(address, deposit_amount) {
+                return Err(VaultError::InternalError(e));
+            }
+        }
 
         self.total_pooled = new_total_pooled;
+        self.bonding_cursor.set(&start.checked_add(n as u32).ok_or(VaultError::Overflow)?);
 
         Ok(())
     }
@@ -227,32 +648,37 @@ impl VaultData {
     /// Phase1: The amount is split among positively imbalanced nodes according to their proportion of the total imbalance.
     /// Phase2: If the unlock amount is more than the positive imbalance, the remainder is split according to nominator stake proportions.
     pub fn delegate_unbonding(&mut self, azero: Balance) -> Result<(), VaultError> {
-        let (total_weight, agents) = self.registry_contract.get_agents();
+        self.registry_contract.activate_weight_updates();
+        let (total_weight, agents) = self.get_all_agents();
 
         let total_pooled_ = self.total_pooled; // shadow
 
-        let new_total_pooled = total_pooled_ - azero;
+        let new_total_pooled = total_pooled_
+            .checked_sub(azero)
+            .ok_or(VaultError::Underflow)?;
 
         let (pos_diff, _neg_diff, stakes, imbalances) = self
-            .get_weight_imbalances(&agents, total_weight, new_total_pooled);
+            .get_weight_imbalances(&agents, total_weight, new_total_pooled)?;
 
         // Amount to withdraw from over-allocated agents
         let phase1 = if azero < pos_diff { azero } else { pos_diff };
 
         // Remaining amount to withdraw equitably from all agents
-        let phase2 = azero - phase1;
+        let phase2 = azero.checked_sub(phase1).ok_or(VaultError::Underflow)?;
 
-        let total_staked_after_phase1 = total_pooled_ - phase1;
+        let total_staked_after_phase1 = total_pooled_
+            .checked_sub(phase1)
+            .ok_or(VaultError::Underflow)?;
 
         let n = agents.len();
         let mut unbond_amounts: Vec<u128> = Vec::with_capacity(n);
-        let mut unbond_summation = 0;
+        let mut unbond_summation = 0_u128;
 
         for i in 0..n {
             // Unbond from over-allocated agents
             // Weighted by agent imbalance
             let phase1_amount = if imbalances[i] > 0 {
-                self.pro_rata(phase1, imbalances[i] as u128, pos_diff)
+                self.pro_rata(phase1, imbalances[i].unsigned_abs(), pos_diff)?
             } else {
                 0
             };
@@ -260,21 +686,28 @@ impl VaultData {
             // Unbond remaining amount equitably from all agents
             // Weighted by agent remaining stake
             let phase2_amount = if phase2 > 0 {
-                self.pro_rata(phase2, stakes[i] - phase1_amount, total_staked_after_phase1)
+                let remaining_stake = stakes[i]
+                    .checked_sub(phase1_amount)
+                    .ok_or(VaultError::Underflow)?;
+                self.pro_rata(phase2, remaining_stake, total_staked_after_phase1)?
             } else {
                 0
             };
 
-            let unbond_amount = phase1_amount + phase2_amount;
+            let unbond_amount = phase1_amount
+                .checked_add(phase2_amount)
+                .ok_or(VaultError::Overflow)?;
             unbond_amounts.push(unbond_amount);
-            unbond_summation += unbond_amount;
+            unbond_summation = unbond_summation
+                .checked_add(unbond_amount)
+                .ok_or(VaultError::Overflow)?;
         }
 
         if unbond_summation == 0 {
             return Err(VaultError::ZeroUnbonding);
         }
 
-        let mut dust = azero - unbond_summation;
+        let mut dust = azero.checked_sub(unbond_summation).ok_or(VaultError::Underflow)?;
         debug_println!("Dust: {}", dust);
 
         // Allocate dust
@@ -284,14 +717,16 @@ impl VaultData {
         if dust > 0 {
             for i in 0..n {
                 if stakes[i] > unbond_amounts[i] {
-                    let surplus = stakes[i] - unbond_amounts[i];
+                    let surplus = stakes[i] - unbond_amounts[i]; // checked by the condition above
                     if dust > surplus {
                         debug_println!("Allocating {} dust to agent #{}", surplus, i);
-                        unbond_amounts[i] += surplus;
-                        dust -= surplus;
+                        unbond_amounts[i] = stakes[i];
+                        dust -= surplus; // checked by the condition above
                     } else {
                         debug_println!("Allocating {} dust to agent #{}", dust, i);
-                        unbond_amounts[i] += dust;
+                        unbond_amounts[i] = unbond_amounts[i]
+                            .checked_add(dust)
+                            .ok_or(VaultError::Overflow)?;
                         break;
                     }
                 }
@@ -319,11 +754,9 @@ impl VaultData {
     /// Specified agents must be currently known by the Registry
     /// Specifying duplicate agents will waste gas
     pub fn delegate_withdraw_unbonded(&self, agents: Vec<AccountId>) -> Result<(), VaultError> {
-        let (_total_weight, registry_agents) = self.registry_contract.get_agents();
-
         for agent in agents.into_iter() {
             // Ensure user-provided agent is known by the Registry
-            if !registry_agents.iter().any(|registry_agent| registry_agent.address == agent) {
+            if self.registry_contract.get_agent(agent).is_none() {
                 return Err(VaultError::InvalidIndex);
             }
             if let Err(e) = call_withdraw_unbonded(agent) {
@@ -336,7 +769,7 @@ impl VaultData {
 
     /// Claim unbonded AZERO from all agents
     pub fn delegate_withdraw_unbonded_all(&self) -> Result<(), VaultError> {
-        let (_total_weight, agents) = self.registry_contract.get_agents();
+        let (_total_weight, agents) = self.get_all_agents();
 
         for a in agents.into_iter() {
             if let Err(e) = call_withdraw_unbonded(a.address) {
@@ -354,15 +787,17 @@ impl VaultData {
     ///
     /// `total_compounded` - Total AZERO compounded across all agents
     pub fn delegate_compound(&mut self) -> Result<Balance, VaultError> {
-        let (_total_weight, agents) = self.registry_contract.get_agents();
+        let (_total_weight, agents) = self.get_all_agents();
 
-        let mut total_compounded = 0;
+        let mut total_compounded = 0_u128;
 
         for (i, a) in agents.into_iter().enumerate() {
             match call_compound(a.address) {
                 Ok(compound_amount) => {
                     debug_println!("Compounded {} to agent #{}", compound_amount, i);
-                    total_compounded += compound_amount;
+                    total_compounded = total_compounded
+                        .checked_add(compound_amount)
+                        .ok_or(VaultError::Overflow)?;
                 },
                 Err(e) => return Err(VaultError::InternalError(e)),
             }
@@ -372,55 +807,153 @@ impl VaultData {
             return Err(VaultError::ZeroCompounding);
         }
 
-        self.total_pooled += total_compounded;
+        self.total_pooled = self.total_pooled
+            .checked_add(total_compounded)
+            .ok_or(VaultError::Overflow)?;
 
         Ok(total_compounded)
     }
 
+    /// Returns the compounded rewards which are still vesting at the given time
+    ///
+    /// Locked rewards vest linearly over `reward_vesting_period` starting from `last_reward_lock`
+    pub fn get_unvested_rewards_at_time(&self, current_time: Timestamp) -> Result<Balance, VaultError> {
+        let locked_rewards = self.locked_rewards.get_or_default();
+        let reward_vesting_period = self.reward_vesting_period.get_or_default();
+        let vesting_end = self.last_reward_lock.get_or_default()
+            .checked_add(reward_vesting_period)
+            .ok_or(VaultError::Overflow)?;
+
+        if locked_rewards == 0 || current_time >= vesting_end {
+            return Ok(0);
+        }
+
+        let remaining_time = vesting_end - current_time; // checked by the condition above
+        self.pro_rata(
+            locked_rewards,
+            remaining_time as u128,
+            reward_vesting_period as u128,
+        )
+    }
+
+    /// Returns the AZERO backing the redemption ratio at the given time
+    /// This excludes compounded rewards which are still vesting
+    pub fn get_vested_pooled_at_time(&self, current_time: Timestamp) -> Result<Balance, VaultError> {
+        self.total_pooled
+            .checked_sub(self.get_unvested_rewards_at_time(current_time)?)
+            .ok_or(VaultError::Underflow)
+    }
+
+    /// Locks newly compounded rewards along with any rewards still vesting
+    /// All locked rewards restart vesting from the current time
+    pub fn lock_rewards(&mut self, current_time: Timestamp, rewards: Balance) -> Result<(), VaultError> {
+        let locked_rewards = self.get_unvested_rewards_at_time(current_time)?
+            .checked_add(rewards)
+            .ok_or(VaultError::Overflow)?;
+        self.locked_rewards.set(&locked_rewards);
+        self.last_reward_lock.set(&current_time);
+
+        Ok(())
+    }
+
+    /// Splits fee shares among `fee_recipients` by their portions
+    /// Rounding dust is allocated to the first recipient
+    pub fn split_fee_shares(&self, shares: u128) -> Result<Vec<(AccountId, u128)>, VaultError> {
+        let fee_recipients = self.fee_recipients.get_or_default();
+        let mut splits = Vec::with_capacity(fee_recipients.len());
+        let mut summation = 0_u128;
+
+        for recipient in fee_recipients.iter() {
+            let amount = self.pro_rata(shares, recipient.bips as u128, BIPS as u128)?;
+            summation = summation.checked_add(amount).ok_or(VaultError::Overflow)?;
+            splits.push((recipient.account, amount));
+        }
+
+        let dust = shares.checked_sub(summation).ok_or(VaultError::Underflow)?;
+        if let Some(first) = splits.first_mut() {
+            first.1 = first.1.checked_add(dust).ok_or(VaultError::Overflow)?;
+        }
+
+        Ok(splits)
+    }
+
+    /// Calculates the portion of compounded rewards taken as performance fees
+    /// No performance fee is taken under the management fee model
+    pub fn get_performance_fee(&self, compounded: Balance) -> Result<Balance, VaultError> {
+        match self.fee_model.get_or_default() {
+            FeeModel::Management => Ok(0),
+            FeeModel::Performance => self.pro_rata(
+                compounded,
+                self.performance_fee_percentage.get_or_default() as u128,
+                BIPS as u128,
+            ),
+        }
+    }
+
     /// Calculates summation of fees from last update until now
-    /// Must be called before changing: `total_shares_minted`, `fee_percentage`
+    /// No fees accrue under the performance fee model
+    /// Must be called before changing: `total_shares_minted`, `fee_percentage`, `fee_model`
     /// Must be called before calculating redemption ratio via: `get_shares_from_azero()` and `get_azero_from_shares()`
-    pub fn update_fees(&mut self, current_time: Timestamp) {
+    pub fn update_fees(&mut self, current_time: Timestamp) -> Result<(), VaultError> {
         // Time since last update
-        let time = current_time - self.last_fee_update;
+        let time = current_time
+            .checked_sub(self.last_fee_update)
+            .ok_or(VaultError::Underflow)?;
 
         // Calculate fee accumulation since last update
-        if time > 0 {
+        if time > 0 && self.fee_model.get_or_default() == FeeModel::Management {
             let virtual_shares = self.pro_rata(
-                self.total_shares_minted + self.total_shares_virtual,
+                self.total_shares_minted
+                    .checked_add(self.total_shares_virtual)
+                    .ok_or(VaultError::Overflow)?,
                 self.fee_percentage as u128,
                 BIPS as u128,
-            );
-            let time_weighted_virtual_shares = self.pro_rata(virtual_shares, time as u128, YEAR as u128);
+            )?;
+            let time_weighted_virtual_shares = self.pro_rata(virtual_shares, time as u128, YEAR as u128)?;
 
-            self.total_shares_virtual += time_weighted_virtual_shares;
-            self.last_fee_update = current_time;
+            self.total_shares_virtual = self.total_shares_virtual
+                .checked_add(time_weighted_virtual_shares)
+                .ok_or(VaultError::Overflow)?;
         }
+        self.last_fee_update = current_time;
+
+        Ok(())
     }
 
     /// Returns the virtual shares that will exist at the given time
-    pub fn get_virtual_shares_at_time(&self, current_time: Timestamp) -> Balance {
+    pub fn get_virtual_shares_at_time(&self, current_time: Timestamp) -> Result<Balance, VaultError> {
         // Time since last update
-        let time = current_time - self.last_fee_update;
+        let time = current_time
+            .checked_sub(self.last_fee_update)
+            .ok_or(VaultError::Underflow)?;
 
-        if time > 0 {
+        if time > 0 && self.fee_model.get_or_default() == FeeModel::Management {
             // Calculate fee accumulation since last update
             let virtual_shares = self.pro_rata(
-                self.total_shares_minted + self.total_shares_virtual,
+                self.total_shares_minted
+                    .checked_add(self.total_shares_virtual)
+                    .ok_or(VaultError::Overflow)?,
                 self.fee_percentage as u128,
                 BIPS as u128,
-            );
-            let time_weighted_virtual_shares = self.pro_rata(virtual_shares, time as u128, YEAR as u128);
-            self.total_shares_virtual + time_weighted_virtual_shares
+            )?;
+            let time_weighted_virtual_shares = self.pro_rata(virtual_shares, time as u128, YEAR as u128)?;
+            self.total_shares_virtual
+                .checked_add(time_weighted_virtual_shares)
+                .ok_or(VaultError::Overflow)
         } else {
             // No additional fee accumulation is required
-            self.total_shares_virtual
+            Ok(self.total_shares_virtual)
         }
     }
 
     /// Performs the u128 operations: a * b / c
-    pub fn pro_rata(&self, a: u128, b: u128, c: u128) -> u128 {
+    ///
+    /// Intermediate multiplication is unbounded so only the final result must fit into u128
+    pub fn pro_rata(&self, a: u128, b: u128, c: u128) -> Result<u128, VaultError> {
+        if c == 0 {
+            return Err(VaultError::DivisionByZero);
+        }
         let result = BigUint::from(a) * BigUint::from(b) / BigUint::from(c);
-        BigUint::to_u128(&result).unwrap()
+        BigUint::to_u128(&result).ok_or(VaultError::Overflow)
     }
 }
@@ -8,16 +8,33 @@ use crate::nomination_agent_utils::RuntimeError;
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum VaultError {
     InvalidPercent,
+    InvalidFeeRecipients,
+    FeeStepTooLarge,
+    FeeTimelocked,
+    NoFeeProposal,
+    CodeTimelocked,
+    NoPendingCode,
+    InvalidDelay,
+    /// Reward vesting period is shorter than `MIN_REWARD_VESTING_PERIOD`
+    InvalidPeriod,
     InvalidIndex,
     InvalidUserUnlockRequest,
     CooldownPeriod,
     InvalidPermissions,
+    InvalidRole,
     NoChange,
     ZeroDepositing,
     ZeroUnbonding,
     ZeroTotalWeight,
     ZeroCompounding,
     MinimumStake,
+    /// No agent has room left below the maximum share of `total_pooled`
+    ConcentrationLimit,
+    /// Arithmetic operation would exceed the bounds of its type
+    Overflow,
+    /// Arithmetic operation would drop below zero
+    Underflow,
+    DivisionByZero,
     /// An interaction with ink! environment has failed
     // NOTE: We're representing the `ink::env::Error` as `String` b/c the
     // type does not have Encode/Decode implemented.
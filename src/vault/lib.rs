@@ -1,5 +1,5 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
-mod data;
+pub mod data;
 pub mod errors;
 mod nomination_agent_utils;
 pub mod traits;
@@ -19,7 +19,7 @@ mod vault {
         ToAccountId,
     };
     use psp22::{PSP22Burnable, PSP22};
-    use registry::{registry::RegistryRef, traits::IRegistry};
+    use registry::registry::RegistryRef;
     use share_token::{ShareToken, TokenRef};
 
     /// Errors returned by the contract's methods.
@@ -71,31 +71,137 @@ mod vault {
     }
     #[ink(event)]
     pub struct FeesWithdrawn {
+        #[ink(topic)]
+        recipient: AccountId,
         shares: u128,
     }
     #[ink(event)]
+    pub struct FeeRecipientsUpdated {
+        recipients: Vec<FeeRecipient>,
+    }
+    #[ink(event)]
     pub struct FeesAdjusted {
         new_fee: u16,
         virtual_shares: u128,
     }
     #[ink(event)]
-    pub struct RoleAdjustFeeTransferred {
+    pub struct FeeProposed {
+        new_fee: u16,
+        execute_after: Timestamp,
+    }
+    #[ink(event)]
+    pub struct FeeProposalCancelled {
+        new_fee: u16,
+    }
+    #[ink(event)]
+    pub struct PerformanceFeesMinted {
+        azero: Balance,
+        shares: u128,
+    }
+    #[ink(event)]
+    pub struct PerformanceFeeAdjusted {
+        new_fee: u16,
+    }
+    #[ink(event)]
+    pub struct PerformanceFeeProposed {
+        new_fee: u16,
+        execute_after: Timestamp,
+    }
+    #[ink(event)]
+    pub struct PerformanceFeeProposalCancelled {
+        new_fee: u16,
+    }
+    #[ink(event)]
+    pub struct FeeModelProposed {
+        fee_model: FeeModel,
+        execute_after: Timestamp,
+    }
+    #[ink(event)]
+    pub struct FeeModelProposalCancelled {
+        fee_model: FeeModel,
+    }
+    #[ink(event)]
+    pub struct FeeModelChanged {
+        fee_model: FeeModel,
+        virtual_shares: u128,
+    }
+    #[ink(event)]
+    pub struct MaxAgentShareAdjusted {
+        new_max_share: u16,
+    }
+    #[ink(event)]
+    pub struct RewardVestingPeriodProposed {
+        new_period: u64,
+        execute_after: Timestamp,
+    }
+    #[ink(event)]
+    pub struct RewardVestingPeriodProposalCancelled {
+        new_period: u64,
+    }
+    #[ink(event)]
+    pub struct RewardVestingPeriodAdjusted {
+        new_period: u64,
+        unvested_rewards: Balance,
+    }
+    #[ink(event)]
+    pub struct RoleAccountChanged {
+        role_type: VaultRoleType,
+        new_account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleAccountProposed {
+        role_type: VaultRoleType,
         new_account: AccountId,
     }
     #[ink(event)]
-    pub struct RoleFeeToTransferred {
+    pub struct RoleAccountProposalCancelled {
+        role_type: VaultRoleType,
         new_account: AccountId,
     }
     #[ink(event)]
-    pub struct RoleSetCodeTransferred {
+    pub struct RoleAdminChanged {
+        role_type: VaultRoleType,
         new_account: AccountId,
     }
     #[ink(event)]
+    pub struct RoleAdminProposed {
+        role_type: VaultRoleType,
+        new_account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleAdminProposalCancelled {
+        role_type: VaultRoleType,
+        new_account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleRenounced {
+        role_type: VaultRoleType,
+        account: AccountId,
+    }
+    #[ink(event)]
+    pub struct NewCodeHashAnnounced {
+        code_hash: [u8; 32],
+        apply_after: Timestamp,
+    }
+    #[ink(event)]
+    pub struct CodeHashAnnouncementCancelled {
+        code_hash: [u8; 32],
+    }
+    #[ink(event)]
     pub struct NewCodeHash {
         code_hash: [u8; 32],
     }
     #[ink(event)]
+    pub struct SetCodeDelayAdjusted {
+        new_delay: u64,
+    }
+    #[ink(event)]
     pub struct SetCodeDisabled {}
+    #[ink(event)]
+    pub struct StorageMigrated {
+        from_version: u16,
+        to_version: u16,
+    }
 
     #[ink(storage)]
     pub struct Vault {
@@ -113,7 +219,7 @@ mod vault {
             let caller = Self::env().caller();
             let now = Self::env().block_timestamp();
 
-            let registry_ref = RegistryRef::new(caller, caller, caller, caller, caller, nomination_agent_hash)
+            let registry_ref = RegistryRef::new(caller, caller, caller, caller, caller, caller, caller, nomination_agent_hash)
                 .endowment(0)
                 .code_hash(registry_code_hash)
                 .salt_bytes(now.to_le_bytes())
@@ -157,7 +263,9 @@ mod vault {
 
         fn mint_shares(&mut self, amount: u128, to: AccountId) -> Result<(), VaultError> {
             let mut token: contract_ref!(ShareToken) = self.data.shares_contract.into();
-            self.data.total_shares_minted += amount;
+            self.data.total_shares_minted = self.data.total_shares_minted
+                .checked_add(amount)
+                .ok_or(VaultError::Overflow)?;
             if let Err(e) = token.mint(to, amount) {
                 return Err(VaultError::TokenError(e));
             }
@@ -166,12 +274,88 @@ mod vault {
 
         fn burn_shares(&mut self, amount: u128) -> Result<(), VaultError> {
             let mut token: contract_ref!(PSP22Burnable) = self.data.shares_contract.into();
-            self.data.total_shares_minted -= amount;
+            self.data.total_shares_minted = self.data.total_shares_minted
+                .checked_sub(amount)
+                .ok_or(VaultError::Underflow)?;
             if let Err(e) = token.burn(amount) {
                 return Err(VaultError::TokenError(e));
             }
             Ok(())
         }
+
+        /// Updates the protocol fee after settling fees accrued at the old fee level
+        fn apply_fee(&mut self, new_fee: u16) -> Result<(), VaultError> {
+            let now = Self::env().block_timestamp();
+
+            self.data.update_fees(now)?;
+            self.data.fee_percentage = new_fee;
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeesAdjusted(FeesAdjusted {
+                    new_fee,
+                    virtual_shares: self.data.total_shares_virtual, // updated in update_fees()
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Updates the performance fee, which only applies to rewards compounded afterwards
+        fn apply_performance_fee(&mut self, new_fee: u16) {
+            self.data.performance_fee_percentage.set(&new_fee);
+
+            Self::emit_event(
+                Self::env(),
+                Event::PerformanceFeeAdjusted(PerformanceFeeAdjusted {
+                    new_fee,
+                }),
+            );
+        }
+
+        /// Mints fee shares split among the fee recipients
+        fn mint_fee_shares(&mut self, shares: u128) -> Result<Vec<(AccountId, u128)>, VaultError> {
+            let splits = self.data.split_fee_shares(shares)?;
+            for (recipient, amount) in splits.iter() {
+                if *amount > 0 {
+                    self.mint_shares(*amount, *recipient)?;
+                }
+            }
+            Ok(splits)
+        }
+
+        /// Minted and virtual shares at the current block timestamp
+        fn total_shares(&self) -> Result<u128, VaultError> {
+            let now = Self::env().block_timestamp();
+            self.data.total_shares_minted
+                .checked_add(self.data.get_virtual_shares_at_time(now)?)
+                .ok_or(VaultError::Overflow)
+        }
+
+        fn shares_from_azero(&self, azero: Balance) -> Result<u128, VaultError> {
+            let now = Self::env().block_timestamp();
+            let total_pooled = self.data.get_vested_pooled_at_time(now)?;
+            let total_shares = self.total_shares()?; // shadow
+            if total_pooled == 0 || total_shares == 0 {
+                // This happens upon initial stake
+                // Also known as 1:1 redemption ratio
+                Ok(azero)
+            } else {
+                self.data.pro_rata(azero, total_shares, total_pooled)
+            }
+        }
+
+        fn azero_from_shares(&self, shares: u128) -> Result<Balance, VaultError> {
+            let now = Self::env().block_timestamp();
+            let total_shares = self.total_shares()?;
+            if total_shares == 0 {
+                // This should never happen
+                Ok(0)
+            } else {
+                let total_pooled = self.data.get_vested_pooled_at_time(now)?;
+                self.data.pro_rata(shares, total_pooled, total_shares)
+            }
+        }
     }
 
     impl RateProvider for Vault {
@@ -180,7 +364,12 @@ mod vault {
         fn get_rate(&mut self) -> u128 {
             // Because both RATE_DECIMALS and sA0.decimals() are 12,
             // no further adjustment is necessary
-            self.get_azero_from_shares(1e12 as u128)
+            // The interface cannot report errors, so the rate saturates on overflow and falls back to zero otherwise
+            match self.azero_from_shares(1e12 as u128) {
+                Ok(rate) => rate,
+                Err(VaultError::Overflow) => u128::MAX,
+                Err(_) => 0,
+            }
         }
     }
 
@@ -201,9 +390,9 @@ mod vault {
             }
 
             // Update fees before calculating redemption ratio and minting shares
-            self.data.update_fees(Self::env().block_timestamp());
+            self.data.update_fees(Self::env().block_timestamp())?;
 
-            let new_shares = self.get_shares_from_azero(azero);
+            let new_shares = self.shares_from_azero(azero)?;
             self.mint_shares(new_shares, caller)?;
 
             self.data.delegate_bonding(azero)?;
@@ -250,9 +439,9 @@ mod vault {
             self.transfer_shares_from(&caller, &Self::env().account_id(), shares)?;
 
             // Update fees before calculating redemption ratio and burning shares
-            self.data.update_fees(now);
+            self.data.update_fees(now)?;
 
-            let azero = self.get_azero_from_shares(shares);
+            let azero = self.azero_from_shares(shares)?;
 
             // Update user's unlock requests
             let mut user_unlock_requests = self.data.user_unlock_requests.get(caller).unwrap_or_default();
@@ -310,7 +499,10 @@ mod vault {
             let azero = user_unlock_requests[unlock_id as usize].azero;
 
             // Ensure unbond has completed
-            if now < creation_time + self.data.cooldown_period {
+            let unlock_time = creation_time
+                .checked_add(self.data.cooldown_period)
+                .ok_or(VaultError::Overflow)?;
+            if now < unlock_time {
                 return Err(VaultError::CooldownPeriod);
             }
 
@@ -350,19 +542,61 @@ mod vault {
         /// Compound earned interest for all validators
         ///
         /// Can be called by anyone
+        /// Compounded AZERO vests linearly into the redemption ratio over `reward_vesting_period`
+        /// Under the performance fee model, a cut of the compounded AZERO is minted as sA0 to the fee recipients
         #[ink(message)]
         fn compound(&mut self) -> Result<Balance, VaultError> {
             let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
+
+            // Update fees before calculating redemption ratio and minting shares
+            self.data.update_fees(now)?;
 
             // Delegate compounding to all agents
             let compounded = self.data.delegate_compound()?;
 
+            let fee_azero = self.data.get_performance_fee(compounded)?;
+            let rewards = compounded
+                .checked_sub(fee_azero)
+                .ok_or(VaultError::Underflow)?;
+
+            // Stream rewards into the redemption ratio rather than applying them instantly
+            self.data.lock_rewards(now, rewards)?;
+
+            if fee_azero > 0 {
+                // Fee AZERO is vested immediately so the fee shares are priced as a stake of `fee_azero`
+                let total_pooled = self.data
+                    .get_vested_pooled_at_time(now)?
+                    .checked_sub(fee_azero)
+                    .ok_or(VaultError::Underflow)?;
+                let total_shares = self.total_shares()?;
+                let fee_shares = if total_pooled == 0 || total_shares == 0 {
+                    fee_azero
+                } else {
+                    self.data.pro_rata(fee_azero, total_shares, total_pooled)?
+                };
+
+                self.mint_fee_shares(fee_shares)?;
+                let total_performance_fee_shares = self.data.total_performance_fee_shares.get_or_default()
+                    .checked_add(fee_shares)
+                    .ok_or(VaultError::Overflow)?;
+                self.data.total_performance_fee_shares.set(&total_performance_fee_shares);
+
+                Self::emit_event(
+                    Self::env(),
+                    Event::PerformanceFeesMinted(PerformanceFeesMinted {
+                        azero: fee_azero,
+                        shares: fee_shares,
+                    }),
+                );
+            }
+
             Self::emit_event(
                 Self::env(),
                 Event::Compounded(Compounded {
                     caller,
                     azero: compounded,
-                    virtual_shares: self.get_current_virtual_shares(),
+                    virtual_shares: self.data.get_virtual_shares_at_time(now)?,
                 }),
             );
 
@@ -371,50 +605,139 @@ mod vault {
 
         /// Claim fees by inflating sA0 supply
         ///
-        /// Caller must have the fee to role (`role_fee_to`)
-        /// Mints virtual shares as sA0 to the caller
+        /// Caller must have the fee to role (`VaultRoleType::FeeTo`)
+        /// Mints virtual shares as sA0 split among the fee recipients (`fee_recipients`)
         /// Effectively serves as a compounding for protocol fee
         /// sets total_shares_virtual to 0
         #[ink(message)]
         fn withdraw_fees(&mut self) -> Result<(), VaultError> {
             let caller = Self::env().caller();
             let now = Self::env().block_timestamp();
-            let role_fee_to = self.data.role_fee_to; // shadow
 
-            if caller != role_fee_to {
+            if Some(caller) != self.data.get_role(VaultRoleType::FeeTo) {
                 return Err(VaultError::InvalidPermissions);
             }
 
-            self.data.update_fees(now);
+            self.data.update_fees(now)?;
 
             let shares = self.data.total_shares_virtual;
-            self.mint_shares(shares, role_fee_to)?;
+            let splits = self.mint_fee_shares(shares)?;
             self.data.total_shares_virtual = 0;
 
+            for (recipient, shares) in splits.into_iter() {
+                Self::emit_event(
+                    Self::env(),
+                    Event::FeesWithdrawn(FeesWithdrawn {
+                        recipient,
+                        shares,
+                    }),
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Update the accounts receiving protocol fees and their portions
+        ///
+        /// Caller must have the fee to role (`VaultRoleType::FeeTo`)
+        /// Portions must be non-zero and sum to `BIPS`
+        /// Recipients must be unique and cannot exceed `MAX_FEE_RECIPIENTS`
+        #[ink(message)]
+        fn set_fee_recipients(&mut self, recipients: Vec<FeeRecipient>) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::FeeTo) {
+                return Err(VaultError::InvalidPermissions);
+            }
+            if recipients.is_empty() || recipients.len() > MAX_FEE_RECIPIENTS {
+                return Err(VaultError::InvalidFeeRecipients);
+            }
+
+            let mut total_bips = 0_u16;
+            for (i, recipient) in recipients.iter().enumerate() {
+                if recipient.bips == 0 {
+                    return Err(VaultError::InvalidFeeRecipients);
+                }
+                if recipients[..i].iter().any(|r| r.account == recipient.account) {
+                    return Err(VaultError::InvalidFeeRecipients);
+                }
+                total_bips = total_bips
+                    .checked_add(recipient.bips)
+                    .ok_or(VaultError::InvalidPercent)?;
+            }
+            if total_bips != BIPS {
+                return Err(VaultError::InvalidPercent);
+            }
+
+            self.data.fee_recipients.set(&recipients);
+
             Self::emit_event(
                 Self::env(),
-                Event::FeesWithdrawn(FeesWithdrawn {
-                    shares,
+                Event::FeeRecipientsUpdated(FeeRecipientsUpdated {
+                    recipients,
                 }),
             );
 
             Ok(())
         }
 
-        /// Upgrade the contract by the ink env set_code_hash function
+        /// Step 1 of 2 in upgrading the contract
+        /// Announces a code hash which can be applied after `set_code_delay`
+        /// This guarantees users an exit window before any logic changes
         ///
-        /// The set code role (`role_set_code`) must be set
-        /// Caller must have the set code role (`role_set_code`)
-        /// See ink documentation for details https://paritytech.github.io/ink/ink_env/fn.set_code_hash.html
+        /// The set code role (`VaultRoleType::SetCode`) must be set
+        /// Caller must have the set code role (`VaultRoleType::SetCode`)
+        /// Replaces any pending announcement
         #[ink(message)]
-        fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), VaultError> {
+        fn announce_code(&mut self, code_hash: [u8; 32]) -> Result<(), VaultError> {
             let caller = Self::env().caller();
-            let role_set_code = self.data.role_set_code; // shadow
+            let now = Self::env().block_timestamp();
+            if Some(caller) != self.data.get_role(VaultRoleType::SetCode) {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            let apply_after = now
+                .checked_add(self.data.get_set_code_delay())
+                .ok_or(VaultError::Overflow)?;
+            self.data.pending_code.set(&Some(CodeHashAnnouncement {
+                code_hash,
+                apply_after,
+            }));
+
+            Self::emit_event(
+                Self::env(),
+                Event::NewCodeHashAnnounced(NewCodeHashAnnounced {
+                    code_hash,
+                    apply_after,
+                }),
+            );
+
+            Ok(())
+        }
 
-            if role_set_code.is_none() || caller != role_set_code.unwrap() {
+        /// Step 2 of 2 in upgrading the contract
+        /// Upgrades the contract to the announced code hash by the ink env set_code_hash function
+        ///
+        /// The set code role (`VaultRoleType::SetCode`) must be set
+        /// Caller must have the set code role (`VaultRoleType::SetCode`)
+        /// The announcement delay must have elapsed
+        /// See ink documentation for details https://paritytech.github.io/ink/ink_env/fn.set_code_hash.html
+        #[ink(message)]
+        fn apply_code(&mut self) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
+            if Some(caller) != self.data.get_role(VaultRoleType::SetCode) {
                 return Err(VaultError::InvalidPermissions);
             }
 
+            let announcement = self.data.pending_code.get().flatten().ok_or(VaultError::NoPendingCode)?;
+            self.data.pending_code.set(&None);
+
+            if now < announcement.apply_after {
+                return Err(VaultError::CodeTimelocked);
+            }
+
+            let code_hash = announcement.code_hash;
             ink::env::set_code_hash(&code_hash)?;
 
             Self::emit_event(
@@ -427,59 +750,55 @@ mod vault {
             Ok(())
         }
 
-        /// Removes the ability to "upgrade" the contract via `self.set_code()`
+        /// Discards the pending code hash announcement
         ///
-        /// The set code role (`role_set_code`) must be set
-        /// Caller must have the set code role (`role_set_code`)
+        /// Caller must have the guardian role (`VaultRoleType::Guardian`) or the set code role (`VaultRoleType::SetCode`)
         #[ink(message)]
-        fn disable_set_code(&mut self) -> Result<(), VaultError> {
+        fn cancel_code(&mut self) -> Result<(), VaultError> {
             let caller = Self::env().caller();
-            let role_set_code = self.data.role_set_code; // shadow
 
-            if role_set_code.is_none() {
-                return Err(VaultError::NoChange);
-            }
-            if caller != role_set_code.unwrap() {
+            if Some(caller) != self.data.get_role(VaultRoleType::Guardian) && Some(caller) != self.data.get_role(VaultRoleType::SetCode) {
                 return Err(VaultError::InvalidPermissions);
             }
 
-            self.data.role_set_code = None;
+            let announcement = self.data.pending_code.get().flatten().ok_or(VaultError::NoPendingCode)?;
+            self.data.pending_code.set(&None);
 
             Self::emit_event(
                 Self::env(),
-                Event::SetCodeDisabled(SetCodeDisabled {}),
+                Event::CodeHashAnnouncementCancelled(CodeHashAnnouncementCancelled {
+                    code_hash: announcement.code_hash,
+                }),
             );
 
             Ok(())
         }
 
-        /// Update the protocol fee
+        /// Update the time between announcing and applying a code hash
         ///
-        /// Caller must have the adjust fee role (`role_adjust_fee`)
-        /// Updates the total_shares_virtual accumulator at the old fee level first
+        /// The set code role (`VaultRoleType::SetCode`) must be set
+        /// Caller must have the set code role (`VaultRoleType::SetCode`)
+        /// New delay cannot be less than the unbonding cooldown period
+        /// Does not affect a pending announcement
         #[ink(message)]
-        fn adjust_fee(&mut self, new_fee: u16) -> Result<(), VaultError> {
+        fn adjust_set_code_delay(&mut self, new_delay: u64) -> Result<(), VaultError> {
             let caller = Self::env().caller();
-            let now = Self::env().block_timestamp();
-
-            if caller != self.data.role_adjust_fee {
+            if Some(caller) != self.data.get_role(VaultRoleType::SetCode) {
                 return Err(VaultError::InvalidPermissions);
             }
-            if self.data.fee_percentage == new_fee {
+            if self.data.get_set_code_delay() == new_delay {
                 return Err(VaultError::NoChange);
             }
-            if new_fee >= BIPS {
-                return Err(VaultError::InvalidPercent);
+            if new_delay < self.data.cooldown_period {
+                return Err(VaultError::InvalidDelay);
             }
 
-            self.data.update_fees(now);
-            self.data.fee_percentage = new_fee;
+            self.data.set_code_delay.set(&new_delay);
 
             Self::emit_event(
                 Self::env(),
-                Event::FeesAdjusted(FeesAdjusted {
-                    new_fee,
-                    virtual_shares: self.data.total_shares_virtual, // updated in update_fees()
+                Event::SetCodeDelayAdjusted(SetCodeDelayAdjusted {
+                    new_delay,
                 }),
             );
 
@@ -487,31 +806,54 @@ mod vault {
         }
 
         #[ink(message)]
-        fn get_role_adjust_fee(&self) -> AccountId {
-            self.data.role_adjust_fee
+        fn get_pending_code(&self) -> Option<CodeHashAnnouncement> {
+            self.data.pending_code.get().flatten()
         }
 
-        /// Transfers adjust fee role to a new account
+        #[ink(message)]
+        fn get_set_code_delay(&self) -> u64 {
+            self.data.get_set_code_delay()
+        }
+
+        /// Migrates storage to the layout expected by the current code
+        /// Called once after `apply_code` whenever the code bumps `STORAGE_VERSION`
         ///
-        /// Caller must have the adjust fee role (`role_adjust_fee`)
+        /// The set code role (`VaultRoleType::SetCode`) must be set
+        /// Caller must have the set code role (`VaultRoleType::SetCode`)
         #[ink(message)]
-        fn transfer_role_adjust_fee(&mut self, new_account: AccountId) -> Result<(), VaultError> {
+        fn migrate(&mut self) -> Result<(), VaultError> {
             let caller = Self::env().caller();
-            let role_adjust_fee = self.data.role_adjust_fee; // shadow
+            let from_version = self.data.get_storage_version();
+
+            // Roles remain in `legacy_roles` until storage version 2
+            let role_set_code = if from_version < 2 {
+                self.data.legacy_roles.role_set_code
+            } else {
+                self.data.get_role(VaultRoleType::SetCode)
+            };
 
-            if caller != role_adjust_fee {
+            if Some(caller) != role_set_code {
                 return Err(VaultError::InvalidPermissions);
             }
-            if role_adjust_fee == new_account {
+            if from_version >= STORAGE_VERSION {
                 return Err(VaultError::NoChange);
             }
 
-            self.data.role_adjust_fee = new_account;
+            // Migration steps are applied in order
+            if from_version < 1 {
+                self.data.migrate_unversioned_settings(Self::env().block_timestamp());
+            }
+            if from_version < 2 {
+                self.data.migrate_legacy_roles(caller);
+            }
+
+            self.data.storage_version.set(&STORAGE_VERSION);
 
             Self::emit_event(
                 Self::env(),
-                Event::RoleAdjustFeeTransferred(RoleAdjustFeeTransferred {
-                    new_account,
+                Event::StorageMigrated(StorageMigrated {
+                    from_version,
+                    to_version: STORAGE_VERSION,
                 }),
             );
 
@@ -519,130 +861,837 @@ mod vault {
         }
 
         #[ink(message)]
-        fn get_role_fee_to(&self) -> AccountId {
-            self.data.role_fee_to
+        fn get_storage_version(&self) -> u16 {
+            self.data.get_storage_version()
         }
 
-        /// Transfers fee to role to a new account
+        /// Removes the ability to "upgrade" the contract via `self.apply_code()`
+        /// Removes the set code role entirely, discarding any pending role proposals and code hash announcement
         ///
-        /// Caller must have the fee to role (`role_fee_to`)
+        /// The set code role (`VaultRoleType::SetCode`) must be set
+        /// Caller must have the set code role (`VaultRoleType::SetCode`)
         #[ink(message)]
-        fn transfer_role_fee_to(&mut self, new_account: AccountId) -> Result<(), VaultError> {
+        fn disable_set_code(&mut self) -> Result<(), VaultError> {
             let caller = Self::env().caller();
-            let role_fee_to = self.data.role_fee_to; // shadow
+            let role_set_code = self.data.get_role(VaultRoleType::SetCode); // shadow
 
-            if caller != role_fee_to {
-                return Err(VaultError::InvalidPermissions);
-            }
-            if role_fee_to == new_account {
+            if role_set_code.is_none() {
                 return Err(VaultError::NoChange);
             }
+            if caller != role_set_code.unwrap() {
+                return Err(VaultError::InvalidPermissions);
+            }
 
-            self.data.role_fee_to = new_account;
+            self.data.roles.remove(VaultRoleType::SetCode);
+            self.data.pending_roles.remove(VaultRoleType::SetCode);
+            self.data.pending_role_admins.remove(VaultRoleType::SetCode);
+            self.data.pending_code.set(&None);
 
             Self::emit_event(
                 Self::env(),
-                Event::RoleFeeToTransferred(RoleFeeToTransferred {
-                    new_account,
-                }),
+                Event::SetCodeDisabled(SetCodeDisabled {}),
             );
 
             Ok(())
         }
 
+        /// Lower the protocol fee immediately
+        ///
+        /// Caller must have the adjust fee role (`VaultRoleType::AdjustFee`)
+        /// Fee increases must go through `propose_fee` and `execute_fee`
+        /// Updates the total_shares_virtual accumulator at the old fee level first
         #[ink(message)]
-        fn get_role_set_code(&self) -> Option<AccountId> {
-            self.data.role_set_code
+        fn adjust_fee(&mut self, new_fee: u16) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+            if self.data.fee_percentage == new_fee {
+                return Err(VaultError::NoChange);
+            }
+            if new_fee >= BIPS {
+                return Err(VaultError::InvalidPercent);
+            }
+            if new_fee > self.data.fee_percentage {
+                return Err(VaultError::FeeTimelocked);
+            }
+
+            self.apply_fee(new_fee)
         }
 
-        /// Transfers set code role to a new account
+        /// Step 1 of 2 in changing the protocol fee
+        /// Queues a new fee which can be executed after `FEE_CHANGE_DELAY`
         ///
-        /// The set code role (`role_set_code`) must be set
-        /// Caller must have the set code role (`role_set_code`)
+        /// Caller must have the adjust fee role (`VaultRoleType::AdjustFee`)
+        /// New fee cannot exceed `MAX_FEE_PERCENTAGE`
+        /// New fee cannot differ from the current fee by more than `MAX_FEE_STEP`
+        /// Replaces any pending proposal
         #[ink(message)]
-        fn transfer_role_set_code(&mut self, new_account: AccountId) -> Result<(), VaultError> {
+        fn propose_fee(&mut self, new_fee: u16) -> Result<(), VaultError> {
             let caller = Self::env().caller();
-            let role_set_code = self.data.role_set_code; // shadow
+            let now = Self::env().block_timestamp();
 
-            if role_set_code.is_none() || caller != role_set_code.unwrap() {
+            if Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
                 return Err(VaultError::InvalidPermissions);
             }
-            if role_set_code.unwrap() == new_account {
+            if self.data.fee_percentage == new_fee {
                 return Err(VaultError::NoChange);
             }
+            VaultData::check_fee_change(self.data.fee_percentage, new_fee)?;
 
-            self.data.role_set_code = Some(new_account);
+            let execute_after = now
+                .checked_add(FEE_CHANGE_DELAY)
+                .ok_or(VaultError::Overflow)?;
+            self.data.pending_fee.set(&Some(FeeProposal {
+                new_fee,
+                execute_after,
+            }));
 
             Self::emit_event(
                 Self::env(),
-                Event::RoleSetCodeTransferred(RoleSetCodeTransferred {
-                    new_account,
+                Event::FeeProposed(FeeProposed {
+                    new_fee,
+                    execute_after,
                 }),
             );
 
             Ok(())
         }
 
-        /// Returns the total amount of bonded AZERO
+        /// Step 2 of 2 in changing the protocol fee
+        /// Applies the pending fee proposal
+        ///
+        /// Caller must have the adjust fee role (`VaultRoleType::AdjustFee`)
+        /// The proposal delay must have elapsed
         #[ink(message)]
-        fn get_total_pooled(&self) -> Balance {
-            self.data.total_pooled
-        }
+        fn execute_fee(&mut self) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
 
-        /// Shares effectively in circulation by the protocol including:
-        ///     1) sA0 that has already been minted
-        ///     2) sA0 that could be minted (virtual) representing accumulating protocol fees
-        #[ink(message)]
-        fn get_total_shares(&self) -> u128 {
-            self.data.total_shares_minted + self.get_current_virtual_shares()
-        }
+            if Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
 
-        /// Protocol fees (sA0) which can be minted and withdrawn at the current block timestamp
-        #[ink(message)]
-        fn get_current_virtual_shares(&self) -> u128 {
-            let now = Self::env().block_timestamp();
-            self.data.get_virtual_shares_at_time(now)
-        }
+            let proposal = self.data.pending_fee.get().flatten().ok_or(VaultError::NoFeeProposal)?;
 
-        #[ink(message)]
-        fn get_fee_percentage(&self) -> u16 {
-            self.data.fee_percentage
+            if now < proposal.execute_after {
+                return Err(VaultError::FeeTimelocked);
+            }
+            // The fee may have been lowered since the proposal was made
+            if proposal.new_fee.abs_diff(self.data.fee_percentage) > MAX_FEE_STEP {
+                return Err(VaultError::FeeStepTooLarge);
+            }
+
+            if self.data.fee_percentage == proposal.new_fee {
+                return Err(VaultError::NoChange);
+            }
+
+            self.data.pending_fee.set(&None);
+
+            self.apply_fee(proposal.new_fee)
         }
-        
+
+        /// Discards the pending fee proposal
+        ///
+        /// Caller must have the guardian role (`VaultRoleType::Guardian`) or the adjust fee role (`VaultRoleType::AdjustFee`)
         #[ink(message)]
-        fn get_share_token_contract(&self) -> AccountId {
-            self.data.shares_contract
+        fn cancel_fee_proposal(&mut self) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::Guardian) && Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            let proposal = self.data.pending_fee.get().flatten().ok_or(VaultError::NoFeeProposal)?;
+            self.data.pending_fee.set(&None);
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeeProposalCancelled(FeeProposalCancelled {
+                    new_fee: proposal.new_fee,
+                }),
+            );
+
+            Ok(())
         }
 
         #[ink(message)]
-        fn get_registry_contract(&self) -> AccountId {
-            RegistryRef::to_account_id(&self.data.registry_contract)
+        fn get_pending_fee(&self) -> Option<FeeProposal> {
+            self.data.pending_fee.get().flatten()
         }
 
-        /// Calculate the value of AZERO in terms of sA0 shares
+        /// Lower the percentage of compounded rewards taken under the performance fee model immediately
+        ///
+        /// Caller must have the adjust fee role (`VaultRoleType::AdjustFee`)
+        /// Increases must go through `propose_performance_fee` and `execute_performance_fee`
         #[ink(message)]
-        fn get_shares_from_azero(&self, azero: Balance) -> u128 {
-            let total_pooled = self.data.total_pooled; // shadow
-            let total_shares = self.get_total_shares(); // shadow
-            if total_pooled == 0 || total_shares == 0 {
-                // This happens upon initial stake
-                // Also known as 1:1 redemption ratio
-                azero
-            } else {
-                self.data.pro_rata(azero, total_shares, total_pooled)
+        fn adjust_performance_fee(&mut self, new_fee: u16) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+            if self.data.performance_fee_percentage.get_or_default() == new_fee {
+                return Err(VaultError::NoChange);
             }
+            if new_fee > self.data.performance_fee_percentage.get_or_default() {
+                return Err(VaultError::FeeTimelocked);
+            }
+
+            self.apply_performance_fee(new_fee);
+
+            Ok(())
         }
 
-        /// Calculate the value of sA0 shares in terms of AZERO
+        /// Step 1 of 2 in changing the performance fee
+        /// Queues a new performance fee which can be executed after `FEE_CHANGE_DELAY`
+        ///
+        /// Caller must have the adjust fee role (`VaultRoleType::AdjustFee`)
+        /// New fee cannot exceed `MAX_FEE_PERCENTAGE`
+        /// New fee cannot differ from the current performance fee by more than `MAX_FEE_STEP`
+        /// Replaces any pending proposal
         #[ink(message)]
-        fn get_azero_from_shares(&self, shares: u128) -> Balance {
-            let total_shares = self.get_total_shares();
-            if total_shares == 0 {
-                // This should never happen
-                0
-            } else {
-                self.data.pro_rata(shares, self.data.total_pooled, total_shares)
+        fn propose_performance_fee(&mut self, new_fee: u16) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+            if self.data.performance_fee_percentage.get_or_default() == new_fee {
+                return Err(VaultError::NoChange);
+            }
+            VaultData::check_fee_change(self.data.performance_fee_percentage.get_or_default(), new_fee)?;
+
+            let execute_after = now
+                .checked_add(FEE_CHANGE_DELAY)
+                .ok_or(VaultError::Overflow)?;
+            self.data.pending_performance_fee.set(&Some(FeeProposal {
+                new_fee,
+                execute_after,
+            }));
+
+            Self::emit_event(
+                Self::env(),
+                Event::PerformanceFeeProposed(PerformanceFeeProposed {
+                    new_fee,
+                    execute_after,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Step 2 of 2 in changing the performance fee
+        /// Applies the pending performance fee proposal
+        ///
+        /// Caller must have the adjust fee role (`VaultRoleType::AdjustFee`)
+        /// The proposal delay must have elapsed
+        #[ink(message)]
+        fn execute_performance_fee(&mut self) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            let proposal = self.data.pending_performance_fee.get().flatten().ok_or(VaultError::NoFeeProposal)?;
+
+            if now < proposal.execute_after {
+                return Err(VaultError::FeeTimelocked);
+            }
+            // The performance fee may have been lowered since the proposal was made
+            if proposal.new_fee.abs_diff(self.data.performance_fee_percentage.get_or_default()) > MAX_FEE_STEP {
+                return Err(VaultError::FeeStepTooLarge);
+            }
+
+            if self.data.performance_fee_percentage.get_or_default() == proposal.new_fee {
+                return Err(VaultError::NoChange);
+            }
+
+            self.data.pending_performance_fee.set(&None);
+
+            self.apply_performance_fee(proposal.new_fee);
+
+            Ok(())
+        }
+
+        /// Discards the pending performance fee proposal
+        ///
+        /// Caller must have the guardian role (`VaultRoleType::Guardian`) or the adjust fee role (`VaultRoleType::AdjustFee`)
+        #[ink(message)]
+        fn cancel_performance_fee_proposal(&mut self) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::Guardian) && Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            let proposal = self.data.pending_performance_fee.get().flatten().ok_or(VaultError::NoFeeProposal)?;
+            self.data.pending_performance_fee.set(&None);
+
+            Self::emit_event(
+                Self::env(),
+                Event::PerformanceFeeProposalCancelled(PerformanceFeeProposalCancelled {
+                    new_fee: proposal.new_fee,
+                }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_pending_performance_fee(&self) -> Option<FeeProposal> {
+            self.data.pending_performance_fee.get().flatten()
+        }
+
+        /// Step 1 of 2 in selecting how protocol fees are taken
+        /// Queues a new fee model which can be executed after `FEE_CHANGE_DELAY`
+        ///
+        /// Caller must have the adjust fee role (`VaultRoleType::AdjustFee`)
+        /// Replaces any pending proposal
+        #[ink(message)]
+        fn propose_fee_model(&mut self, fee_model: FeeModel) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+            if self.data.fee_model.get_or_default() == fee_model {
+                return Err(VaultError::NoChange);
+            }
+
+            let execute_after = now
+                .checked_add(FEE_CHANGE_DELAY)
+                .ok_or(VaultError::Overflow)?;
+            self.data.pending_fee_model.set(&Some(FeeModelProposal {
+                fee_model,
+                execute_after,
+            }));
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeeModelProposed(FeeModelProposed {
+                    fee_model,
+                    execute_after,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Step 2 of 2 in selecting how protocol fees are taken
+        /// Applies the pending fee model proposal
+        ///
+        /// Caller must have the adjust fee role (`VaultRoleType::AdjustFee`)
+        /// The proposal delay must have elapsed
+        /// Management fees accrued under the old model remain withdrawable via `withdraw_fees`
+        #[ink(message)]
+        fn execute_fee_model(&mut self) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            let proposal = self.data.pending_fee_model.get().flatten().ok_or(VaultError::NoFeeProposal)?;
+
+            if now < proposal.execute_after {
+                return Err(VaultError::FeeTimelocked);
+            }
+            if self.data.fee_model.get_or_default() == proposal.fee_model {
+                return Err(VaultError::NoChange);
             }
+
+            self.data.pending_fee_model.set(&None);
+
+            self.data.update_fees(now)?;
+            self.data.fee_model.set(&proposal.fee_model);
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeeModelChanged(FeeModelChanged {
+                    fee_model: proposal.fee_model,
+                    virtual_shares: self.data.total_shares_virtual, // updated in update_fees()
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Discards the pending fee model proposal
+        ///
+        /// Caller must have the guardian role (`VaultRoleType::Guardian`) or the adjust fee role (`VaultRoleType::AdjustFee`)
+        #[ink(message)]
+        fn cancel_fee_model_proposal(&mut self) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::Guardian) && Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            let proposal = self.data.pending_fee_model.get().flatten().ok_or(VaultError::NoFeeProposal)?;
+            self.data.pending_fee_model.set(&None);
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeeModelProposalCancelled(FeeModelProposalCancelled {
+                    fee_model: proposal.fee_model,
+                }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_pending_fee_model(&self) -> Option<FeeModelProposal> {
+            self.data.pending_fee_model.get().flatten()
+        }
+
+        /// Step 1 of 2 in changing the duration over which compounded rewards vest
+        /// Queues a new vesting period which can be executed after `FEE_CHANGE_DELAY`
+        ///
+        /// Caller must have the adjust fee role (`VaultRoleType::AdjustFee`)
+        /// New period cannot be shorter than `MIN_REWARD_VESTING_PERIOD`
+        /// Replaces any pending proposal
+        #[ink(message)]
+        fn propose_reward_vesting_period(&mut self, new_period: u64) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+            if self.data.reward_vesting_period.get_or_default() == new_period {
+                return Err(VaultError::NoChange);
+            }
+            if new_period < MIN_REWARD_VESTING_PERIOD {
+                return Err(VaultError::InvalidPeriod);
+            }
+
+            let execute_after = now
+                .checked_add(FEE_CHANGE_DELAY)
+                .ok_or(VaultError::Overflow)?;
+            self.data.pending_reward_vesting_period.set(&Some(VestingPeriodProposal {
+                new_period,
+                execute_after,
+            }));
+
+            Self::emit_event(
+                Self::env(),
+                Event::RewardVestingPeriodProposed(RewardVestingPeriodProposed {
+                    new_period,
+                    execute_after,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Step 2 of 2 in changing the duration over which compounded rewards vest
+        /// Applies the pending vesting period proposal
+        ///
+        /// Caller must have the adjust fee role (`VaultRoleType::AdjustFee`)
+        /// The proposal delay must have elapsed
+        /// Rewards which are still vesting restart vesting over the new period
+        #[ink(message)]
+        fn execute_reward_vesting_period(&mut self) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            let proposal = self.data.pending_reward_vesting_period.get().flatten().ok_or(VaultError::NoFeeProposal)?;
+
+            if now < proposal.execute_after {
+                return Err(VaultError::FeeTimelocked);
+            }
+            if self.data.reward_vesting_period.get_or_default() == proposal.new_period {
+                return Err(VaultError::NoChange);
+            }
+
+            self.data.pending_reward_vesting_period.set(&None);
+
+            // Checkpoint the rewards which are still vesting under the old period
+            self.data.lock_rewards(now, 0)?;
+            self.data.reward_vesting_period.set(&proposal.new_period);
+
+            Self::emit_event(
+                Self::env(),
+                Event::RewardVestingPeriodAdjusted(RewardVestingPeriodAdjusted {
+                    new_period: proposal.new_period,
+                    unvested_rewards: self.data.locked_rewards.get_or_default(),
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Discards the pending vesting period proposal
+        ///
+        /// Caller must have the guardian role (`VaultRoleType::Guardian`) or the adjust fee role (`VaultRoleType::AdjustFee`)
+        #[ink(message)]
+        fn cancel_reward_vesting_period_proposal(&mut self) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::Guardian) && Some(caller) != self.data.get_role(VaultRoleType::AdjustFee) {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            let proposal = self.data.pending_reward_vesting_period.get().flatten().ok_or(VaultError::NoFeeProposal)?;
+            self.data.pending_reward_vesting_period.set(&None);
+
+            Self::emit_event(
+                Self::env(),
+                Event::RewardVestingPeriodProposalCancelled(RewardVestingPeriodProposalCancelled {
+                    new_period: proposal.new_period,
+                }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_pending_reward_vesting_period(&self) -> Option<VestingPeriodProposal> {
+            self.data.pending_reward_vesting_period.get().flatten()
+        }
+
+        /// Update the maximum portion of `total_pooled` a single agent can hold in bips
+        ///
+        /// Caller must have the limits admin role (`VaultRoleType::LimitsAdmin`)
+        /// Enforced when staking, deposits exceeding it are rejected
+        #[ink(message)]
+        fn adjust_max_agent_share(&mut self, new_max_share: u16) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+
+            if Some(caller) != self.data.get_role(VaultRoleType::LimitsAdmin) {
+                return Err(VaultError::InvalidPermissions);
+            }
+            if self.data.get_max_agent_share() == new_max_share {
+                return Err(VaultError::NoChange);
+            }
+            if new_max_share == 0 || new_max_share > BIPS {
+                return Err(VaultError::InvalidPercent);
+            }
+
+            self.data.max_agent_share.set(&new_max_share);
+
+            Self::emit_event(
+                Self::env(),
+                Event::MaxAgentShareAdjusted(MaxAgentShareAdjusted {
+                    new_max_share,
+                }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_role(&self, role_type: VaultRoleType) -> Option<AccountId> {
+            self.data.get_role(role_type)
+        }
+
+        #[ink(message)]
+        fn get_role_admin(&self, role_type: VaultRoleType) -> Option<AccountId> {
+            self.data.roles.get(role_type).map(|role| role.admin)
+        }
+
+        #[ink(message)]
+        fn get_pending_role(&self, role_type: VaultRoleType) -> Option<AccountId> {
+            self.data.pending_roles.get(role_type)
+        }
+
+        #[ink(message)]
+        fn get_pending_role_admin(&self, role_type: VaultRoleType) -> Option<AccountId> {
+            self.data.pending_role_admins.get(role_type)
+        }
+
+        /// Step 1 of 2 in transferring a role to a new account
+        /// Replaces any pending proposal for the role
+        ///
+        /// Caller must be the admin for the role
+        #[ink(message)]
+        fn propose_role(&mut self, role_type: VaultRoleType, new_account: AccountId) -> Result<(), VaultError> {
+            let role = self.data.roles.get(role_type).ok_or(VaultError::InvalidRole)?;
+
+            if Self::env().caller() != role.admin {
+                return Err(VaultError::InvalidPermissions);
+            }
+            if role.account == new_account {
+                return Err(VaultError::NoChange);
+            }
+
+            self.data.pending_roles.insert(role_type, &new_account);
+
+            Self::emit_event(
+                Self::env(),
+                Event::RoleAccountProposed(RoleAccountProposed {
+                    role_type,
+                    new_account,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Step 2 of 2 in transferring a role to a new account
+        ///
+        /// Caller must be the proposed account for the role
+        #[ink(message)]
+        fn accept_role(&mut self, role_type: VaultRoleType) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            let mut role = self.data.roles.get(role_type).ok_or(VaultError::InvalidRole)?;
+
+            if Some(caller) != self.data.pending_roles.get(role_type) {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            role.account = caller;
+            self.data.roles.insert(role_type, &role);
+            self.data.pending_roles.remove(role_type);
+
+            Self::emit_event(
+                Self::env(),
+                Event::RoleAccountChanged(RoleAccountChanged {
+                    role_type,
+                    new_account: caller,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Discards the pending account proposal for a role
+        ///
+        /// Caller must be the admin for the role
+        #[ink(message)]
+        fn cancel_role_proposal(&mut self, role_type: VaultRoleType) -> Result<(), VaultError> {
+            let role = self.data.roles.get(role_type).ok_or(VaultError::InvalidRole)?;
+
+            if Self::env().caller() != role.admin {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            let new_account = self.data.pending_roles.take(role_type).ok_or(VaultError::NoChange)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::RoleAccountProposalCancelled(RoleAccountProposalCancelled {
+                    role_type,
+                    new_account,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Step 1 of 2 in transferring administration of a role to a new account
+        /// Replaces any pending proposal for the role admin
+        ///
+        /// Caller must be the admin for the role
+        #[ink(message)]
+        fn propose_role_admin(&mut self, role_type: VaultRoleType, new_account: AccountId) -> Result<(), VaultError> {
+            let role = self.data.roles.get(role_type).ok_or(VaultError::InvalidRole)?;
+
+            if Self::env().caller() != role.admin {
+                return Err(VaultError::InvalidPermissions);
+            }
+            if role.admin == new_account {
+                return Err(VaultError::NoChange);
+            }
+
+            self.data.pending_role_admins.insert(role_type, &new_account);
+
+            Self::emit_event(
+                Self::env(),
+                Event::RoleAdminProposed(RoleAdminProposed {
+                    role_type,
+                    new_account,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Step 2 of 2 in transferring administration of a role to a new account
+        ///
+        /// Caller must be the proposed admin for the role
+        #[ink(message)]
+        fn accept_role_admin(&mut self, role_type: VaultRoleType) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            let mut role = self.data.roles.get(role_type).ok_or(VaultError::InvalidRole)?;
+
+            if Some(caller) != self.data.pending_role_admins.get(role_type) {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            role.admin = caller;
+            self.data.roles.insert(role_type, &role);
+            self.data.pending_role_admins.remove(role_type);
+
+            Self::emit_event(
+                Self::env(),
+                Event::RoleAdminChanged(RoleAdminChanged {
+                    role_type,
+                    new_account: caller,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Discards the pending admin proposal for a role
+        ///
+        /// Caller must be the admin for the role
+        #[ink(message)]
+        fn cancel_role_admin_proposal(&mut self, role_type: VaultRoleType) -> Result<(), VaultError> {
+            let role = self.data.roles.get(role_type).ok_or(VaultError::InvalidRole)?;
+
+            if Self::env().caller() != role.admin {
+                return Err(VaultError::InvalidPermissions);
+            }
+
+            let new_account = self.data.pending_role_admins.take(role_type).ok_or(VaultError::NoChange)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::RoleAdminProposalCancelled(RoleAdminProposalCancelled {
+                    role_type,
+                    new_account,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Gives up a role, returning it to the role admin who may assign it again
+        ///
+        /// Caller must have the role and must not be its admin
+        #[ink(message)]
+        fn renounce_role(&mut self, role_type: VaultRoleType) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
+            let mut role = self.data.roles.get(role_type).ok_or(VaultError::InvalidRole)?;
+
+            if caller != role.account {
+                return Err(VaultError::InvalidPermissions);
+            }
+            if caller == role.admin {
+                return Err(VaultError::NoChange);
+            }
+
+            role.account = role.admin;
+            self.data.roles.insert(role_type, &role);
+
+            Self::emit_event(
+                Self::env(),
+                Event::RoleRenounced(RoleRenounced {
+                    role_type,
+                    account: caller,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Returns the total amount of bonded AZERO backing the redemption ratio
+        ///
+        /// Excludes compounded rewards which are still vesting (see `get_unvested_rewards`)
+        #[ink(message)]
+        fn get_total_pooled(&self) -> Result<Balance, VaultError> {
+            let now = Self::env().block_timestamp();
+            self.data.get_vested_pooled_at_time(now)
+        }
+
+        /// Returns the compounded AZERO which is bonded but has not yet vested
+        #[ink(message)]
+        fn get_unvested_rewards(&self) -> Result<Balance, VaultError> {
+            let now = Self::env().block_timestamp();
+            self.data.get_unvested_rewards_at_time(now)
+        }
+
+        #[ink(message)]
+        fn get_reward_vesting_period(&self) -> u64 {
+            self.data.reward_vesting_period.get_or_default()
+        }
+
+        /// Shares effectively in circulation by the protocol including:
+        ///     1) sA0 that has already been minted
+        ///     2) sA0 that could be minted (virtual) representing accumulating protocol fees
+        #[ink(message)]
+        fn get_total_shares(&self) -> Result<u128, VaultError> {
+            self.total_shares()
+        }
+
+        /// Protocol fees (sA0) which can be minted and withdrawn at the current block timestamp
+        #[ink(message)]
+        fn get_current_virtual_shares(&self) -> Result<u128, VaultError> {
+            let now = Self::env().block_timestamp();
+            self.data.get_virtual_shares_at_time(now)
+        }
+
+        #[ink(message)]
+        fn get_fee_percentage(&self) -> u16 {
+            self.data.fee_percentage
+        }
+
+        #[ink(message)]
+        fn get_fee_recipients(&self) -> Vec<FeeRecipient> {
+            self.data.fee_recipients.get_or_default()
+        }
+
+        #[ink(message)]
+        fn get_fee_model(&self) -> FeeModel {
+            self.data.fee_model.get_or_default()
+        }
+
+        #[ink(message)]
+        fn get_max_agent_share(&self) -> u16 {
+            self.data.get_max_agent_share()
+        }
+
+        #[ink(message)]
+        fn get_performance_fee_percentage(&self) -> u16 {
+            self.data.performance_fee_percentage.get_or_default()
+        }
+
+        /// Performance fees (sA0) which have been minted since deployment
+        #[ink(message)]
+        fn get_total_performance_fee_shares(&self) -> u128 {
+            self.data.total_performance_fee_shares.get_or_default()
+        }
+
+        /// Performance fees (AZERO) which would be taken if the given amount were compounded
+        #[ink(message)]
+        fn get_performance_fee(&self, compounded: Balance) -> Result<Balance, VaultError> {
+            self.data.get_performance_fee(compounded)
+        }
+        
+        #[ink(message)]
+        fn get_share_token_contract(&self) -> AccountId {
+            self.data.shares_contract
+        }
+
+        #[ink(message)]
+        fn get_registry_contract(&self) -> AccountId {
+            RegistryRef::to_account_id(&self.data.registry_contract)
+        }
+
+        /// Calculate the value of AZERO in terms of sA0 shares
+        #[ink(message)]
+        fn get_shares_from_azero(&self, azero: Balance) -> Result<u128, VaultError> {
+            self.shares_from_azero(azero)
+        }
+
+        /// Calculate the value of sA0 shares in terms of AZERO
+        #[ink(message)]
+        fn get_azero_from_shares(&self, shares: u128) -> Result<Balance, VaultError> {
+            self.azero_from_shares(shares)
         }
 
         /// Returns the unlock requests for a given user
@@ -652,8 +1701,8 @@ mod vault {
         }
 
         #[ink(message)]
-        fn get_weight_imbalances(&self, total_pooled: u128) -> (u128, u128, Vec<u128>, Vec<i128>) {
-            let (total_weight, agents) = self.data.registry_contract.get_agents();
+        fn get_weight_imbalances(&self, total_pooled: u128) -> Result<(u128, u128, Vec<u128>, Vec<i128>), VaultError> {
+            let (total_weight, agents) = self.data.get_all_agents();
             self.data.get_weight_imbalances(&agents, total_weight, total_pooled)
         }
     }
@@ -14,6 +14,9 @@ pub enum RuntimeError {
     CallRuntimeFailed,
     Unauthorized,
     Active,
+    Overflow,
+    Underflow,
+    NoChange,
 }
 
 type Balance = <DefaultEnvironment as Environment>::Balance;
@@ -1,4 +1,4 @@
-use crate::data::{Balance, UnlockRequest};
+use crate::data::{Balance, CodeHashAnnouncement, FeeModel, FeeModelProposal, FeeProposal, FeeRecipient, UnlockRequest, VaultRoleType, VestingPeriodProposal};
 use crate::errors::VaultError;
 use ink::{
     primitives::AccountId,
@@ -44,7 +44,31 @@ pub trait IVault {
     fn withdraw_fees(&mut self) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), VaultError>;
+    fn set_fee_recipients(&mut self, recipients: Vec<FeeRecipient>) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn announce_code(&mut self, code_hash: [u8; 32]) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn apply_code(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn cancel_code(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn adjust_set_code_delay(&mut self, new_delay: u64) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_pending_code(&self) -> Option<CodeHashAnnouncement>;
+
+    #[ink(message)]
+    fn get_set_code_delay(&self) -> u64;
+
+    #[ink(message)]
+    fn migrate(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_storage_version(&self) -> u16;
 
     #[ink(message)]
     fn disable_set_code(&mut self) -> Result<(), VaultError>;
@@ -53,35 +77,128 @@ pub trait IVault {
     fn adjust_fee(&mut self, new_fee: u16) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn get_role_adjust_fee(&self) -> AccountId;
+    fn propose_fee(&mut self, new_fee: u16) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn execute_fee(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn cancel_fee_proposal(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_pending_fee(&self) -> Option<FeeProposal>;
+
+    #[ink(message)]
+    fn adjust_performance_fee(&mut self, new_fee: u16) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn propose_performance_fee(&mut self, new_fee: u16) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn execute_performance_fee(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn cancel_performance_fee_proposal(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_pending_performance_fee(&self) -> Option<FeeProposal>;
+
+    #[ink(message)]
+    fn propose_fee_model(&mut self, fee_model: FeeModel) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn execute_fee_model(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn cancel_fee_model_proposal(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_pending_fee_model(&self) -> Option<FeeModelProposal>;
+
+    #[ink(message)]
+    fn propose_reward_vesting_period(&mut self, new_period: u64) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn execute_reward_vesting_period(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn cancel_reward_vesting_period_proposal(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_pending_reward_vesting_period(&self) -> Option<VestingPeriodProposal>;
+
+    #[ink(message)]
+    fn adjust_max_agent_share(&mut self, new_max_share: u16) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_role(&self, role_type: VaultRoleType) -> Option<AccountId>;
+
+    #[ink(message)]
+    fn get_role_admin(&self, role_type: VaultRoleType) -> Option<AccountId>;
 
     #[ink(message)]
-    fn transfer_role_adjust_fee(&mut self, new_account: AccountId) -> Result<(), VaultError>;
+    fn get_pending_role(&self, role_type: VaultRoleType) -> Option<AccountId>;
 
     #[ink(message)]
-    fn get_role_fee_to(&self) -> AccountId;
+    fn get_pending_role_admin(&self, role_type: VaultRoleType) -> Option<AccountId>;
 
     #[ink(message)]
-    fn transfer_role_fee_to(&mut self, new_account: AccountId) -> Result<(), VaultError>;
+    fn propose_role(&mut self, role_type: VaultRoleType, new_account: AccountId) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn get_role_set_code(&self) -> Option<AccountId>;
+    fn accept_role(&mut self, role_type: VaultRoleType) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn transfer_role_set_code(&mut self, new_account: AccountId) -> Result<(), VaultError>;
+    fn cancel_role_proposal(&mut self, role_type: VaultRoleType) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn get_total_pooled(&self) -> Balance;
+    fn propose_role_admin(&mut self, role_type: VaultRoleType, new_account: AccountId) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn get_total_shares(&self) -> u128;
+    fn accept_role_admin(&mut self, role_type: VaultRoleType) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn get_current_virtual_shares(&self) -> u128;
+    fn cancel_role_admin_proposal(&mut self, role_type: VaultRoleType) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn renounce_role(&mut self, role_type: VaultRoleType) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_total_pooled(&self) -> Result<Balance, VaultError>;
+
+    #[ink(message)]
+    fn get_unvested_rewards(&self) -> Result<Balance, VaultError>;
+
+    #[ink(message)]
+    fn get_reward_vesting_period(&self) -> u64;
+
+    #[ink(message)]
+    fn get_total_shares(&self) -> Result<u128, VaultError>;
+
+    #[ink(message)]
+    fn get_current_virtual_shares(&self) -> Result<u128, VaultError>;
 
     #[ink(message)]
     fn get_fee_percentage(&self) -> u16;
 
+    #[ink(message)]
+    fn get_fee_recipients(&self) -> Vec<FeeRecipient>;
+
+    #[ink(message)]
+    fn get_fee_model(&self) -> FeeModel;
+
+    #[ink(message)]
+    fn get_max_agent_share(&self) -> u16;
+
+    #[ink(message)]
+    fn get_performance_fee_percentage(&self) -> u16;
+
+    #[ink(message)]
+    fn get_total_performance_fee_shares(&self) -> u128;
+
+    #[ink(message)]
+    fn get_performance_fee(&self, compounded: Balance) -> Result<Balance, VaultError>;
+
     #[ink(message)]
     fn get_share_token_contract(&self) -> AccountId;
 
@@ -89,14 +206,14 @@ pub trait IVault {
     fn get_registry_contract(&self) -> AccountId;
 
     #[ink(message)]
-    fn get_shares_from_azero(&self, azero: Balance) -> u128;
+    fn get_shares_from_azero(&self, azero: Balance) -> Result<u128, VaultError>;
 
     #[ink(message)]
-    fn get_azero_from_shares(&self, shares: u128) -> Balance;
+    fn get_azero_from_shares(&self, shares: u128) -> Result<Balance, VaultError>;
 
     #[ink(message)]
     fn get_unlock_requests(&self, user: AccountId) -> Vec<UnlockRequest>;
 
     #[ink(message)]
-    fn get_weight_imbalances(&self, total_pooled: u128) -> (u128, u128, Vec<u128>, Vec<i128>);
+    fn get_weight_imbalances(&self, total_pooled: u128) -> Result<(u128, u128, Vec<u128>, Vec<i128>), VaultError>;
 }
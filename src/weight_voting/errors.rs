@@ -0,0 +1,25 @@
+use registry::errors::RegistryError;
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum VotingError {
+    InvalidPermissions,
+    InvalidInput,
+    InvalidPercent,
+    NoChange,
+    AgentNotFound,
+    AgentDisabled,
+    /// Caller held no shares at the start of the epoch
+    NoVotingPower,
+    /// Epoch has not ended or was already finalized
+    EpochNotEnded,
+    /// No shares were allocated to any active agent during the epoch
+    NoVotes,
+    /// Too few agents received votes for the maximum share to be respected
+    CapUnreachable,
+    /// Arithmetic operation would exceed the bounds of its type
+    Overflow,
+    /// Arithmetic operation would drop below zero
+    Underflow,
+    RegistryError(RegistryError),
+}
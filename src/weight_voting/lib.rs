@@ -0,0 +1,360 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub mod errors;
+pub mod traits;
+
+#[ink::contract]
+pub mod weight_voting {
+    use crate::errors::VotingError;
+    use crate::traits::IWeightVoting;
+    use ink::{
+        codegen::EmitEvent,
+        contract_ref,
+        prelude::vec::Vec,
+        reflect::ContractEventBase,
+        storage::Mapping,
+    };
+    use core::cmp::Reverse;
+    use registry::{registry::{RoleType, WeightUpdate}, traits::IRegistry};
+    use share_token::{ShareToken, SNAPSHOT_PERIOD};
+
+    pub const BIPS: u16 = 10000;
+
+    type Event = <WeightVoting as ContractEventBase>::Type;
+
+    /// Shares allocated to each agent by a voter
+    pub type VoterAllocations = Vec<(AccountId, u128)>;
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Allocation {
+        pub agent: AccountId,
+        // Portion of the voter's snapshot balance
+        pub bips: u16,
+    }
+
+    #[ink(event)]
+    pub struct Allocated {
+        #[ink(topic)]
+        epoch: u64,
+        #[ink(topic)]
+        voter: AccountId,
+        voting_power: u128,
+    }
+    #[ink(event)]
+    pub struct EpochFinalized {
+        #[ink(topic)]
+        epoch: u64,
+        updates: Vec<WeightUpdate>,
+    }
+    #[ink(event)]
+    pub struct MaxShareChanged {
+        new_max_share: u16,
+    }
+    #[ink(event)]
+    pub struct AdminChanged {
+        new_admin: AccountId,
+    }
+
+    #[ink(storage)]
+    pub struct WeightVoting {
+        pub registry: AccountId,
+        pub share_token: AccountId,
+        // Can adjust the maximum share
+        pub admin: AccountId,
+        // Maximum portion of the total weight a single agent can receive in bips
+        pub max_share: u16,
+        pub last_finalized_epoch: Option<u64>,
+        // Shares allocated to each agent per epoch
+        pub tallies: Mapping<(u64, AccountId), u128>,
+        // Shares allocated by each voter per epoch, used to replace earlier allocations
+        pub allocations: Mapping<(u64, AccountId), VoterAllocations>,
+    }
+
+    impl WeightVoting {
+        /// Must be granted the UpdateAgents role on the registry before finalizing
+        /// `share_token` must serve `snapshot_balance_of`, earlier tokens are replaced as described in `scripts/deploy.ts`
+        #[ink(constructor)]
+        pub fn new(registry: AccountId, share_token: AccountId, admin: AccountId, max_share: u16) -> Self {
+            if max_share == 0 || max_share > BIPS {
+                panic!("Invalid max share");
+            }
+
+            Self {
+                registry,
+                share_token,
+                admin,
+                max_share,
+                last_finalized_epoch: None,
+                tallies: Mapping::default(),
+                allocations: Mapping::default(),
+            }
+        }
+
+        fn emit_event<EE>(emitter: EE, event: Event)
+        where
+            EE: EmitEvent<WeightVoting>,
+        {
+            emitter.emit_event(event);
+        }
+
+        fn current_epoch() -> u64 {
+            Self::env().block_timestamp() / SNAPSHOT_PERIOD
+        }
+
+        /// Caps every tally at `max_share` of the resulting total
+        ///
+        /// Agents above the cap are set to the common weight `c` solving
+        /// `c = max_share * (uncapped + capped_count * c) / BIPS`
+        fn apply_cap(&self, tallies: &mut [(usize, u128)]) -> Result<(), VotingError> {
+            let max_share = self.max_share as u128;
+            let bips = BIPS as u128;
+
+            tallies.sort_by_key(|t| Reverse(t.1));
+
+            let mut uncapped = tallies.iter().try_fold(0u128, |acc, t| acc.checked_add(t.1))
+                .ok_or(VotingError::Overflow)?;
+            let mut capped_count = 0;
+            let mut cap;
+
+            loop {
+                // Every agent with votes exceeds the cap
+                if capped_count > 0 && uncapped == 0 {
+                    return Err(VotingError::CapUnreachable);
+                }
+                let remaining = bips
+                    .checked_sub(max_share.checked_mul(capped_count as u128).ok_or(VotingError::Overflow)?)
+                    .filter(|r| *r > 0)
+                    .ok_or(VotingError::CapUnreachable)?;
+                cap = uncapped
+                    .checked_mul(max_share)
+                    .ok_or(VotingError::Overflow)?
+                    / remaining;
+
+                if capped_count < tallies.len() && tallies[capped_count].1 > cap {
+                    uncapped -= tallies[capped_count].1;
+                    capped_count += 1;
+                } else {
+                    break;
+                }
+            }
+
+            for tally in tallies[..capped_count].iter_mut() {
+                tally.1 = cap;
+            }
+
+            Ok(())
+        }
+    }
+
+    impl IWeightVoting for WeightVoting {
+        /// Allocates the caller's share balance held at the start of the epoch across agents
+        /// Replaces any earlier allocation by the caller in the same epoch
+        ///
+        /// Portions are in bips and cannot sum above `BIPS`
+        /// Agents must be registered and enabled
+        #[ink(message)]
+        fn allocate(&mut self, allocations: Vec<Allocation>) -> Result<(), VotingError> {
+            let caller = Self::env().caller();
+            let epoch = Self::current_epoch();
+
+            let token: contract_ref!(ShareToken) = self.share_token.into();
+            let voting_power = token.snapshot_balance_of(caller);
+            if voting_power == 0 {
+                return Err(VotingError::NoVotingPower);
+            }
+
+            let registry: contract_ref!(IRegistry) = self.registry.into();
+
+            let mut total_bips: u16 = 0;
+            for (i, allocation) in allocations.iter().enumerate() {
+                if allocations[..i].iter().any(|a| a.agent == allocation.agent) {
+                    return Err(VotingError::InvalidInput);
+                }
+                match registry.get_agent(allocation.agent) {
+                    Some((agent, _)) if agent.disabled => return Err(VotingError::AgentDisabled),
+                    Some(_) => (),
+                    None => return Err(VotingError::AgentNotFound),
+                }
+                total_bips = total_bips.checked_add(allocation.bips).ok_or(VotingError::InvalidPercent)?;
+            }
+            if total_bips > BIPS {
+                return Err(VotingError::InvalidPercent);
+            }
+
+            // Remove the previous allocation from the tallies
+            for (agent, amount) in self.allocations.get((epoch, caller)).unwrap_or_default() {
+                let tally = self.tallies.get((epoch, agent)).unwrap_or(0);
+                self.tallies.insert((epoch, agent), &tally.checked_sub(amount).ok_or(VotingError::Underflow)?);
+            }
+
+            let mut amounts = Vec::new();
+            for allocation in allocations.iter() {
+                let amount = voting_power
+                    .checked_mul(allocation.bips as u128)
+                    .ok_or(VotingError::Overflow)?
+                    / BIPS as u128;
+                let tally = self.tallies.get((epoch, allocation.agent)).unwrap_or(0);
+                self.tallies.insert((epoch, allocation.agent), &tally.checked_add(amount).ok_or(VotingError::Overflow)?);
+                amounts.push((allocation.agent, amount));
+            }
+            self.allocations.insert((epoch, caller), &amounts);
+
+            Self::emit_event(Self::env(), Event::Allocated(Allocated {
+                epoch,
+                voter: caller,
+                voting_power,
+            }));
+
+            Ok(())
+        }
+
+        /// Sets registry weights to the tallies of the previous epoch, capped at `max_share`
+        /// Can be called by anyone once per epoch
+        ///
+        /// Submits the difference to current weights through `IRegistry::update_agents`
+        /// Disabled agents are left unchanged
+        #[ink(message)]
+        fn finalize(&mut self) -> Result<Vec<WeightUpdate>, VotingError> {
+            let current_epoch = Self::current_epoch();
+            if current_epoch == 0 {
+                return Err(VotingError::EpochNotEnded);
+            }
+            let epoch = current_epoch - 1;
+            if let Some(last) = self.last_finalized_epoch {
+                if last >= epoch {
+                    return Err(VotingError::EpochNotEnded);
+                }
+            }
+
+            let mut registry: contract_ref!(IRegistry) = self.registry.into();
+            let (_, agents) = registry.get_agents();
+
+            let mut tallies: Vec<(usize, u128)> = agents
+                .iter()
+                .enumerate()
+                .filter(|(_, a)| !a.disabled)
+                .map(|(i, a)| (i, self.tallies.get((epoch, a.address)).unwrap_or(0)))
+                .collect();
+            if tallies.iter().all(|t| t.1 == 0) {
+                return Err(VotingError::NoVotes);
+            }
+
+            self.apply_cap(&mut tallies)?;
+
+            let mut updates = Vec::new();
+            for (index, weight) in tallies {
+                let agent = &agents[index];
+                if weight > agent.weight {
+                    updates.push(WeightUpdate {
+                        agent: agent.address,
+                        weight: weight - agent.weight,
+                        increase: true,
+                    });
+                } else if weight < agent.weight {
+                    updates.push(WeightUpdate {
+                        agent: agent.address,
+                        weight: agent.weight - weight,
+                        increase: false,
+                    });
+                }
+            }
+
+            self.last_finalized_epoch = Some(epoch);
+
+            if !updates.is_empty() {
+                registry.update_agents(updates.clone()).map_err(VotingError::RegistryError)?;
+            }
+
+            Self::emit_event(Self::env(), Event::EpochFinalized(EpochFinalized {
+                epoch,
+                updates: updates.clone(),
+            }));
+
+            Ok(updates)
+        }
+
+        /// Accepts the UpdateAgents role proposed by its current holder on the registry
+        ///
+        /// Caller must be the admin
+        #[ink(message)]
+        fn accept_role(&mut self) -> Result<(), VotingError> {
+            if Self::env().caller() != self.admin {
+                return Err(VotingError::InvalidPermissions);
+            }
+
+            let mut registry: contract_ref!(IRegistry) = self.registry.into();
+            registry.accept_role(RoleType::UpdateAgents).map_err(VotingError::RegistryError)
+        }
+
+        /// Caller must be the admin
+        #[ink(message)]
+        fn set_max_share(&mut self, new_max_share: u16) -> Result<(), VotingError> {
+            if Self::env().caller() != self.admin {
+                return Err(VotingError::InvalidPermissions);
+            }
+            if self.max_share == new_max_share {
+                return Err(VotingError::NoChange);
+            }
+            if new_max_share == 0 || new_max_share > BIPS {
+                return Err(VotingError::InvalidPercent);
+            }
+
+            self.max_share = new_max_share;
+
+            Self::emit_event(Self::env(), Event::MaxShareChanged(MaxShareChanged { new_max_share }));
+
+            Ok(())
+        }
+
+        /// Caller must be the admin
+        #[ink(message)]
+        fn set_admin(&mut self, new_admin: AccountId) -> Result<(), VotingError> {
+            if Self::env().caller() != self.admin {
+                return Err(VotingError::InvalidPermissions);
+            }
+            if self.admin == new_admin {
+                return Err(VotingError::NoChange);
+            }
+
+            self.admin = new_admin;
+
+            Self::emit_event(Self::env(), Event::AdminChanged(AdminChanged { new_admin }));
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_current_epoch(&self) -> u64 {
+            Self::current_epoch()
+        }
+
+        #[ink(message)]
+        fn get_last_finalized_epoch(&self) -> Option<u64> {
+            self.last_finalized_epoch
+        }
+
+        #[ink(message)]
+        fn get_tally(&self, epoch: u64, agent: AccountId) -> u128 {
+            self.tallies.get((epoch, agent)).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        fn get_allocations(&self, epoch: u64, voter: AccountId) -> VoterAllocations {
+            self.allocations.get((epoch, voter)).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        fn get_max_share(&self) -> u16 {
+            self.max_share
+        }
+
+        #[ink(message)]
+        fn get_admin(&self) -> AccountId {
+            self.admin
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::errors::VotingError;
+use crate::weight_voting::{Allocation, VoterAllocations};
+use ink::{primitives::AccountId, prelude::vec::Vec};
+use registry::registry::WeightUpdate;
+
+#[ink::trait_definition]
+pub trait IWeightVoting {
+    #[ink(message)]
+    fn allocate(&mut self, allocations: Vec<Allocation>) -> Result<(), VotingError>;
+
+    #[ink(message)]
+    fn finalize(&mut self) -> Result<Vec<WeightUpdate>, VotingError>;
+
+    #[ink(message)]
+    fn accept_role(&mut self) -> Result<(), VotingError>;
+
+    #[ink(message)]
+    fn set_max_share(&mut self, new_max_share: u16) -> Result<(), VotingError>;
+
+    #[ink(message)]
+    fn set_admin(&mut self, new_admin: AccountId) -> Result<(), VotingError>;
+
+    #[ink(message)]
+    fn get_current_epoch(&self) -> u64;
+
+    #[ink(message)]
+    fn get_last_finalized_epoch(&self) -> Option<u64>;
+
+    #[ink(message)]
+    fn get_tally(&self, epoch: u64, agent: AccountId) -> u128;
+
+    #[ink(message)]
+    fn get_allocations(&self, epoch: u64, voter: AccountId) -> VoterAllocations;
+
+    #[ink(message)]
+    fn get_max_share(&self) -> u16;
+
+    #[ink(message)]
+    fn get_admin(&self) -> AccountId;
+}